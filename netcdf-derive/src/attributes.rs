@@ -0,0 +1,84 @@
+//! Implementation of `#[derive(NcAttributes)]`
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::{format_ident, quote};
+use syn::{Data, DataStruct, DeriveInput, Fields, LitStr};
+
+pub(crate) fn derive(input: &DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields,
+        _ => abort!(
+            input,
+            "Can not derive NcAttributes for this item";
+            help = "NcAttributes can only be derived for structs with named fields"
+        ),
+    };
+
+    let mut idents = vec![];
+    let mut slots = vec![];
+    let mut names = vec![];
+    for (i, field) in fields.named.iter().enumerate() {
+        let ident = field.ident.as_ref().expect("Field must have a name");
+        let mut rename = None;
+        for attr in &field.attrs {
+            if !attr.path().is_ident("netcdf") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else {
+                    abort!(meta.path, "NcAttributes did not understand this attribute");
+                }
+                Ok(())
+            })
+            .unwrap();
+        }
+        names.push(rename.unwrap_or_else(|| ident.to_string()));
+        idents.push(ident);
+        slots.push(format_ident!("__field{i}"));
+    }
+
+    quote! {
+        const _: () = {
+            use netcdf::metadata::AttributeField;
+
+            #[automatically_derived]
+            impl #impl_generics netcdf::NcAttributes for #name #ty_generics #where_clause {
+                fn from_attributes<'a>(
+                    attributes: impl Iterator<Item = netcdf::Attribute<'a>>,
+                ) -> netcdf::Result<Self> {
+                    #(let mut #slots = None;)*
+                    for attr in attributes {
+                        match attr.name() {
+                            #(#names => #slots = Some(attr.value()?),)*
+                            _ => {}
+                        }
+                    }
+                    Ok(Self {
+                        #(#idents: netcdf::metadata::field(#names, #slots)?,)*
+                    })
+                }
+
+                fn put_attributes(
+                    &self,
+                    target: &mut impl netcdf::metadata::AttributeTarget,
+                ) -> netcdf::Result<()> {
+                    #(
+                        if let Some(value) = AttributeField::to_value(&self.#idents) {
+                            target.put_attribute_value(#names, value)?;
+                        }
+                    )*
+                    Ok(())
+                }
+            }
+        };
+    }
+}
@@ -0,0 +1,171 @@
+//! Implementation of `#[derive(NcDataset)]`
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{Attribute, Data, DataStruct, DeriveInput, Expr, ExprArray, Fields, Lit, LitStr};
+
+/// Attribute given as `key = value`
+struct KeyValue {
+    key: String,
+    value: Expr,
+}
+
+/// Collect `key = value` items from `#[netcdf(...)]`, handing
+/// the keys in `special` to `handle`
+fn parse_attributes(
+    attrs: &[Attribute],
+    special: &[&str],
+    mut handle: impl FnMut(&str, syn::meta::ParseNestedMeta) -> syn::Result<()>,
+) -> Vec<KeyValue> {
+    let mut items = vec![];
+    for attr in attrs {
+        if !attr.path().is_ident("netcdf") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            let Some(key) = meta.path.get_ident().map(ToString::to_string) else {
+                abort!(
+                    meta.path,
+                    "NcDataset expects attributes on the form `name = value`"
+                );
+            };
+            if special.contains(&key.as_str()) {
+                return handle(&key, meta);
+            }
+            let value = match meta.value()?.parse::<Expr>()? {
+                // Arrays are written as attributes with multiple values
+                Expr::Array(array) => {
+                    let elems = array.elems;
+                    syn::parse_quote!(vec![#elems])
+                }
+                value => value,
+            };
+            items.push(KeyValue { key, value });
+            Ok(())
+        })
+        .unwrap();
+    }
+    items
+}
+
+fn string_literal(expr: &Expr) -> Option<&LitStr> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Some(s),
+        _ => None,
+    }
+}
+
+pub(crate) fn derive(input: &DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields,
+        _ => abort!(
+            input,
+            "Can not derive NcDataset for this item";
+            help = "NcDataset can only be derived for structs with named fields"
+        ),
+    };
+
+    let global = parse_attributes(&input.attrs, &[], |_, _| Ok(()));
+    let global_keys = global.iter().map(|kv| &kv.key);
+    let global_values = global.iter().map(|kv| &kv.value);
+
+    let mut writes = vec![];
+    let mut reads = vec![];
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("Field must have a name");
+        let ty = &field.ty;
+
+        let mut rename = None;
+        let mut dims = vec![];
+        let mut fill_value = None;
+        let attributes = parse_attributes(
+            &field.attrs,
+            &["rename", "dims", "fill_value"],
+            |key, meta| {
+                match key {
+                    "rename" => rename = Some(meta.value()?.parse::<LitStr>()?.value()),
+                    "fill_value" => fill_value = Some(meta.value()?.parse::<Expr>()?),
+                    _ => {
+                        let array = meta.value()?.parse::<ExprArray>()?;
+                        for elem in &array.elems {
+                            let Some(dim) = string_literal(elem) else {
+                                abort!(elem, "Dimension names must be string literals");
+                            };
+                            dims.push(dim.value());
+                        }
+                    }
+                }
+                Ok(())
+            },
+        );
+        let varname = rename.unwrap_or_else(|| ident.to_string());
+        let units = match attributes.iter().find(|kv| kv.key == "units") {
+            Some(kv) => {
+                let Some(units) = string_literal(&kv.value) else {
+                    abort!(kv.value, "units must be a string literal");
+                };
+                quote!(Some(#units))
+            }
+            None => quote!(None),
+        };
+        let keys = attributes.iter().map(|kv| &kv.key);
+        let values = attributes.iter().map(|kv| &kv.value);
+        let fill_value = fill_value.map(|value| {
+            quote! {
+                var.set_fill_value::<<#ty as DatasetVariable>::Element>(#value)?;
+            }
+        });
+
+        writes.push(quote! {
+            {
+                let dims: &[&str] = &[#(#dims),*];
+                netcdf::dataset::define_dimensions(
+                    file,
+                    #varname,
+                    dims,
+                    &DatasetVariable::shape(&self.#ident),
+                )?;
+                let mut var = file.add_variable::<<#ty as DatasetVariable>::Element>(#varname, dims)?;
+                #fill_value
+                #(var.put_attribute(#keys, #values)?;)*
+                DatasetVariable::put(&self.#ident, &mut var)?;
+            }
+        });
+        reads.push(quote! {
+            #ident: {
+                let var = netcdf::dataset::find_variable(file, #varname, &[#(#dims),*], #units)?;
+                <#ty as DatasetVariable>::get(&var)?
+            }
+        });
+    }
+
+    quote! {
+        const _: () = {
+            use netcdf::dataset::DatasetVariable;
+
+            #[automatically_derived]
+            impl #impl_generics netcdf::NcDataset for #name #ty_generics #where_clause {
+                fn write_to(&self, file: &mut netcdf::FileMut) -> netcdf::Result<()> {
+                    #(file.add_attribute(#global_keys, #global_values)?;)*
+                    #(#writes)*
+                    Ok(())
+                }
+
+                fn read_from(file: &netcdf::File) -> netcdf::Result<Self> {
+                    Ok(Self {
+                        #(#reads,)*
+                    })
+                }
+            }
+        };
+    }
+}
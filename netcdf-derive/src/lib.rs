@@ -3,9 +3,12 @@ use proc_macro_error::{abort, proc_macro_error};
 use quote::quote;
 use syn::{
     parse_macro_input, Data, DataEnum, DataStruct, DeriveInput, Fields, FieldsNamed, LitStr, Type,
-    Variant,
+    Variant, Visibility,
 };
 
+mod attributes;
+mod dataset;
+
 #[proc_macro_derive(NcType, attributes(netcdf))]
 /// Derives `NcTypeDescriptor` for user defined types.
 ///
@@ -18,8 +21,14 @@ use syn::{
 /// Types one derives `NcType` for must have some properties to
 /// ensure correctness:
 /// * Structs must have `repr(C)` to ensure layout compatibility
-/// * Structs must be packed (no padding allowed)
 /// * Enums must have `repr(T)` where `T` is an int type (`{i/u}{8/16/32/64}`)
+///
+/// Structs with `String` or `Vec<T>` fields are stored differently in
+/// memory by `netCDF`. For such structs only `NcOwnedType` is implemented,
+/// with a generated companion struct describing the `netCDF` layout.
+/// Memory is allocated when writing and freed by the conversion when reading.
+/// The struct does not need `repr(C)`.
+/// Use `#[netcdf(owned)]` on structs which contain other such structs.
 #[proc_macro_error]
 pub fn derive(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(stream as DeriveInput);
@@ -28,11 +37,14 @@ pub fn derive(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
     let mut renamed = None;
     let mut repr_c = false;
+    let mut owned = false;
     for attr in &input.attrs {
         if attr.path().is_ident("netcdf") {
             attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("rename") {
                     renamed = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("owned") {
+                    owned = true;
                 } else {
                     abort!(meta.path, "NcType encountered an unknown attribute");
                 }
@@ -57,6 +69,18 @@ pub fn derive(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
             ref fields,
             semi_token: _,
         }) => {
+            if let Fields::Named(fields) = fields {
+                let has_owned_fields = fields.named.iter().any(|f| is_owned_type(&f.ty));
+                if owned || has_owned_fields {
+                    if !input.generics.params.is_empty() {
+                        abort!(
+                            input.generics,
+                            "Can not derive NcType for generic struct with owned fields"
+                        );
+                    }
+                    return impl_owned_compound(name, &input.vis, &ncname, fields.clone()).into();
+                }
+            }
             if !repr_c {
                 abort!(
                     input,
@@ -65,7 +89,7 @@ pub fn derive(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 );
             }
             match fields {
-                Fields::Named(fields) => impl_compound(name, &ncname, fields.clone()),
+                Fields::Named(fields) => impl_compound(&ncname, fields.clone()),
                 Fields::Unnamed(f) => {
                     abort!(f, "Can not derive NcType for struct with unnamed field"; note="#[derive(NcType)]")
                 }
@@ -117,16 +141,36 @@ pub fn derive(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     #body
                 }
             }
+
+            #[automatically_derived]
+            unsafe impl #impl_generics NcOwnedType for #name #ty_generics #where_clause {
+                type Ffi = Self;
+                fn validate(&self) -> netcdf::Result<()> {
+                    Ok(())
+                }
+                fn to_ffi(&self) -> Self {
+                    // The copy is only read by netCDF and is forgotten in `free_written`
+                    unsafe { std::ptr::read(self) }
+                }
+                unsafe fn free_written(ffi: Self) {
+                    let _ = std::mem::ManuallyDrop::new(ffi);
+                }
+                unsafe fn from_read(ffi: Self) -> Self {
+                    ffi
+                }
+            }
         };
     };
     proc_macro::TokenStream::from(expanded)
 }
 
-fn impl_compound(ty: &Ident, ncname: &str, fields: FieldsNamed) -> TokenStream {
-    struct FieldInfo {
-        name: String,
-        typ: Type,
-    }
+struct FieldInfo {
+    ident: Ident,
+    name: String,
+    typ: Type,
+}
+
+fn field_infos(fields: FieldsNamed) -> Vec<FieldInfo> {
     let mut items: Vec<FieldInfo> = vec![];
 
     for field in fields.named {
@@ -147,48 +191,132 @@ fn impl_compound(ty: &Ident, ncname: &str, fields: FieldsNamed) -> TokenStream {
         }
         let name = rename.unwrap_or_else(|| ident.to_string());
         items.push(FieldInfo {
+            ident,
             name,
             typ: field.ty,
         });
     }
+    items
+}
 
-    let fieldnames = items
+/// Types which are not stored inline by `netCDF`
+fn is_owned_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "String" || segment.ident == "Vec"),
+        _ => false,
+    }
+}
+
+fn impl_compound(ncname: &str, fields: FieldsNamed) -> TokenStream {
+    let items = field_infos(fields);
+    let typeids = items
         .iter()
-        .map(|item| item.name.clone())
+        .map(|item| {
+            let typ = &item.typ;
+            quote!(#typ)
+        })
         .collect::<Vec<_>>();
-    let typeids = items
+    compound_descriptor(ncname, &items, &typeids)
+}
+
+/// Body of `type_descriptor` for a compound with fields of type `typeids`,
+/// offsets and size are taken from the layout of `Self`
+fn compound_descriptor(ncname: &str, items: &[FieldInfo], typeids: &[TokenStream]) -> TokenStream {
+    let fieldnames = items
         .iter()
-        .map(|item| item.typ.clone())
+        .map(|item| item.name.clone())
         .collect::<Vec<_>>();
+    let idents = items.iter().map(|item| &item.ident).collect::<Vec<_>>();
     let fieldinfo = quote!(vec![#(
             (
                 (#fieldnames).to_owned(),
+                std::mem::offset_of!(Self, #idents),
                 <#typeids as NcTypeDescriptor>::type_descriptor(),
                 (<#typeids as NcTypeDescriptor>::ARRAY_ELEMENTS).as_dims().map(Vec::from),
             )
             ),*]);
 
     quote! {
-        let mut fields = vec![];
-        let mut offset = 0;
-        for (name, basetype, arraydims) in #fieldinfo {
-            let nelems = arraydims.as_ref().map_or(1, |x| x.iter().copied().product());
-            let thissize = basetype.size() * nelems;
-            fields.push(CompoundTypeField {
+        let fields = #fieldinfo
+            .into_iter()
+            .map(|(name, offset, basetype, arraydims)| CompoundTypeField {
                 name,
                 offset,
                 basetype,
                 arraydims,
-            });
-            offset += thissize;
-        }
-        let compound = NcVariableType::Compound(CompoundType {
+            })
+            .collect();
+        NcVariableType::Compound(CompoundType {
             name: (#ncname).to_owned(),
-            size: offset,
+            size: std::mem::size_of::<Self>(),
             fields,
-        });
-        assert_eq!(compound.size(), std::mem::size_of::<#ty>(), "Compound must be packed");
-        compound
+        })
+    }
+}
+
+/// Implements `NcOwnedType` for a struct with fields which need
+/// conversion, the compound is described by a companion struct
+fn impl_owned_compound(
+    ty: &Ident,
+    vis: &Visibility,
+    ncname: &str,
+    fields: FieldsNamed,
+) -> TokenStream {
+    let items = field_infos(fields);
+    let idents = items.iter().map(|item| &item.ident).collect::<Vec<_>>();
+    let types = items.iter().map(|item| &item.typ).collect::<Vec<_>>();
+    let ffitypes = types
+        .iter()
+        .map(|typ| quote!(<#typ as NcOwnedType>::Ffi))
+        .collect::<Vec<_>>();
+    let ffi = Ident::new("__NcFfi", ty.span());
+    let descriptor = compound_descriptor(ncname, &items, &ffitypes);
+
+    quote! {
+        const _: () = {
+            use netcdf::types::*;
+
+            #[doc(hidden)]
+            #[repr(C)]
+            #vis struct #ffi {
+                #(#idents: #ffitypes,)*
+            }
+
+            #[automatically_derived]
+            unsafe impl NcTypeDescriptor for #ffi {
+                fn type_descriptor() -> NcVariableType {
+                    #descriptor
+                }
+            }
+
+            #[automatically_derived]
+            unsafe impl NcOwnedType for #ty {
+                type Ffi = #ffi;
+                fn validate(&self) -> netcdf::Result<()> {
+                    #(<#types as NcOwnedType>::validate(&self.#idents)?;)*
+                    Ok(())
+                }
+                fn to_ffi(&self) -> #ffi {
+                    #ffi {
+                        #(#idents: <#types as NcOwnedType>::to_ffi(&self.#idents),)*
+                    }
+                }
+                unsafe fn free_written(ffi: #ffi) {
+                    let #ffi { #(#idents,)* } = ffi;
+                    #(<#types as NcOwnedType>::free_written(#idents);)*
+                }
+                unsafe fn from_read(ffi: #ffi) -> Self {
+                    let #ffi { #(#idents,)* } = ffi;
+                    Self {
+                        #(#idents: <#types as NcOwnedType>::from_read(#idents),)*
+                    }
+                }
+            }
+        };
     }
 }
 
@@ -242,3 +370,31 @@ fn impl_enum<'a>(
         })
     }
 }
+
+#[proc_macro_derive(NcDataset, attributes(netcdf))]
+/// Derives `NcDataset` for a struct where each field holds a variable
+///
+/// See the documentation under `netcdf::dataset` for examples
+///
+/// Use `#[netcdf(dims = ["a", "b"])]` to set the dimensions of a variable,
+/// `#[netcdf(rename = "name")]` to rename the variable, `#[netcdf(fill_value = 0.0)]`
+/// to set the fill value and `#[netcdf(key = value)]` to add attributes.
+/// Attributes on the struct are written as global attributes.
+#[proc_macro_error]
+pub fn derive_dataset(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(stream as DeriveInput);
+    dataset::derive(&input).into()
+}
+
+#[proc_macro_derive(NcAttributes, attributes(netcdf))]
+/// Derives `NcAttributes` for a struct where each field holds an attribute
+///
+/// See the documentation under `netcdf::metadata` for examples
+///
+/// Use `#[netcdf(rename = "name")]` to rename the attribute.
+/// Fields of type `Option<T>` are optional.
+#[proc_macro_error]
+pub fn derive_attributes(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(stream as DeriveInput);
+    attributes::derive(&input).into()
+}
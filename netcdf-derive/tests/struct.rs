@@ -130,6 +130,43 @@ fn test_renamed() {
     assert_eq!(Renamed::type_descriptor(), manual_tp);
 }
 
+#[test]
+fn test_impl_padded() {
+    #[repr(C)]
+    #[derive(NcType)]
+    pub struct Padded {
+        a: u8,
+        b: f64,
+        c: [u16; 3],
+    }
+
+    let manual_tp = NcVariableType::Compound(CompoundType {
+        name: "Padded".to_owned(),
+        size: 24,
+        fields: vec![
+            CompoundTypeField {
+                name: "a".to_owned(),
+                basetype: u8::type_descriptor(),
+                arraydims: None,
+                offset: 0,
+            },
+            CompoundTypeField {
+                name: "b".to_owned(),
+                basetype: f64::type_descriptor(),
+                arraydims: None,
+                offset: 8,
+            },
+            CompoundTypeField {
+                name: "c".to_owned(),
+                basetype: u16::type_descriptor(),
+                arraydims: Some(vec![3]),
+                offset: 16,
+            },
+        ],
+    });
+    assert_eq!(Padded::type_descriptor(), manual_tp);
+}
+
 #[test]
 fn test_impl_nested() {
     #[repr(C, packed)]
@@ -0,0 +1,61 @@
+use clap::Parser;
+use netcdf::cf::{CfVersion, Checker};
+
+#[derive(Debug, Parser)]
+struct Opt {
+    path: std::path::PathBuf,
+    /// Version of the CF conventions to check against, e.g. `1.8`
+    #[arg(long, default_value = "1.11")]
+    cf_version: CfVersion,
+    /// Standard name table, either the CF XML table or one name per line
+    #[arg(long)]
+    standard_names: Option<std::path::PathBuf>,
+}
+
+fn main() {
+    let opt = Opt::parse();
+
+    match run(&opt) {
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(2);
+        }
+        Ok(compliant) => {
+            std::process::exit(if compliant { 0 } else { 1 });
+        }
+    }
+}
+
+fn run(opt: &Opt) -> Result<bool, Box<dyn std::error::Error>> {
+    let file = netcdf::open(&opt.path)?;
+
+    let mut checker = Checker::new(opt.cf_version);
+    if let Some(table) = &opt.standard_names {
+        let table = std::fs::read_to_string(table)?;
+        checker = checker.with_standard_names(standard_names(&table));
+    }
+
+    let report = checker.check(&file)?;
+    println!("{}", file.path()?.to_str().unwrap());
+    print!("{report}");
+    Ok(report.is_compliant())
+}
+
+/// Extract names from `<entry id="...">` and `<alias id="...">` elements,
+/// or treat every line as a name when the table is plain text
+fn standard_names(table: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for line in table.lines().map(str::trim) {
+        if let Some(start) = line.find(" id=\"") {
+            if line.starts_with("<entry") || line.starts_with("<alias") {
+                let id = &line[start + 5..];
+                if let Some(end) = id.find('"') {
+                    names.push(id[..end].to_owned());
+                }
+            }
+        } else if !line.is_empty() && !line.starts_with('<') {
+            names.push(line.to_owned());
+        }
+    }
+    names
+}
@@ -0,0 +1,166 @@
+use clap::{Parser, ValueEnum};
+use netcdf::copy::{CopyOptions, Storage};
+use netcdf::{Filter, Options};
+
+/// Copy a netCDF file, converting format, chunking and compression
+#[derive(Debug, Parser)]
+struct Opt {
+    /// Format of the output file, the format of the input if not given
+    #[arg(short = 'k', value_enum)]
+    kind: Option<Kind>,
+    /// Chunk sizes along dimensions, as `dim/size,...`
+    #[arg(short = 'c', value_delimiter = ',', value_parser = parse_chunk)]
+    chunking: Vec<(String, usize)>,
+    /// Deflate level of all variables, 0 removes compression
+    #[arg(short = 'd')]
+    deflate: Option<i32>,
+    /// Shuffle bytes before deflating
+    #[arg(short = 's', requires = "deflate")]
+    shuffle: bool,
+    /// Filter of a variable, as `var,id,param,...` where `*` is all variables
+    #[arg(short = 'F', value_parser = parse_filter)]
+    filters: Vec<(String, Filter)>,
+    /// Variables to copy, by name or full path
+    #[arg(short = 'V', value_delimiter = ',')]
+    variables: Vec<String>,
+    /// Range of indices along a dimension, as `dim/start:end`
+    #[arg(long, value_delimiter = ',', value_parser = parse_slice)]
+    slice: Vec<(String, std::ops::Range<usize>)>,
+    /// Move subgroups into the root group
+    #[arg(long)]
+    flatten: bool,
+    input: std::path::PathBuf,
+    output: std::path::PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Kind {
+    /// Classic format
+    Nc3,
+    /// 64 bit offset format
+    Nc6,
+    /// `netCDF-4` format
+    Nc4,
+    /// `netCDF-4` classic model format
+    Nc7,
+    /// 64 bit data format
+    Cdf5,
+}
+
+impl Kind {
+    /// Format of a file, from its magic number
+    fn of(path: &std::path::Path) -> std::io::Result<Self> {
+        use std::io::Read;
+        let mut magic = [0; 4];
+        std::fs::File::open(path)?.read_exact(&mut magic)?;
+        Ok(match &magic {
+            b"CDF\x01" => Self::Nc3,
+            b"CDF\x02" => Self::Nc6,
+            b"CDF\x05" => Self::Cdf5,
+            _ => Self::Nc4,
+        })
+    }
+
+    fn options(self) -> Options {
+        match self {
+            Self::Nc3 => Options::empty(),
+            Self::Nc6 => Options::_64BIT_OFFSET,
+            Self::Nc4 => Options::NETCDF4,
+            Self::Nc7 => Options::NETCDF4 | Options::CLASSIC,
+            Self::Cdf5 => Options::_64BIT_DATA,
+        }
+    }
+}
+
+fn parse_chunk(s: &str) -> Result<(String, usize), String> {
+    let (dim, size) = s
+        .rsplit_once('/')
+        .ok_or_else(|| format!("expected `dim/size`, found `{s}`"))?;
+    let size = size.parse().map_err(|e| format!("{e}"))?;
+    Ok((dim.to_owned(), size))
+}
+
+fn parse_slice(s: &str) -> Result<(String, std::ops::Range<usize>), String> {
+    let (dim, range) = s
+        .rsplit_once('/')
+        .and_then(|(dim, range)| Some((dim, range.split_once(':')?)))
+        .ok_or_else(|| format!("expected `dim/start:end`, found `{s}`"))?;
+    let start = range.0.parse().map_err(|e| format!("{e}"))?;
+    let end = range.1.parse().map_err(|e| format!("{e}"))?;
+    Ok((dim.to_owned(), start..end))
+}
+
+fn parse_filter(s: &str) -> Result<(String, Filter), String> {
+    let mut items = s.split(',');
+    let var = items.next().unwrap_or_default().to_owned();
+    let id = items
+        .next()
+        .ok_or_else(|| format!("expected `var,id,param,...`, found `{s}`"))?
+        .parse()
+        .map_err(|e| format!("{e}"))?;
+    let params = items
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("{e}"))?;
+    Ok((var, Filter { id, params }))
+}
+
+fn main() {
+    let opt = Opt::parse();
+
+    match run(&opt) {
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+        Ok(()) => {
+            std::process::exit(0);
+        }
+    }
+}
+
+fn run(opt: &Opt) -> Result<(), Box<dyn std::error::Error>> {
+    let kind = match opt.kind {
+        Some(kind) => kind,
+        None => Kind::of(&opt.input)?,
+    };
+    let src = netcdf::open(&opt.input)?;
+    let mut dst = netcdf::create_with(&opt.output, kind.options())?;
+
+    let storage = Storage {
+        deflate: opt.deflate,
+        shuffle: opt.shuffle,
+        filters: None,
+    };
+    let mut options = CopyOptions {
+        chunking: opt.chunking.clone(),
+        storage: storage.clone(),
+        variables: (!opt.variables.is_empty()).then(|| opt.variables.clone()),
+        slices: opt.slice.clone(),
+        flatten: opt.flatten,
+        ..CopyOptions::default()
+    };
+    for (var, filter) in &opt.filters {
+        let storage = if var == "*" {
+            &mut options.storage
+        } else if let Some((_, storage)) = options
+            .variable_storage
+            .iter_mut()
+            .find(|(name, _)| name == var)
+        {
+            storage
+        } else {
+            options
+                .variable_storage
+                .push((var.clone(), storage.clone()));
+            &mut options.variable_storage.last_mut().unwrap().1
+        };
+        storage
+            .filters
+            .get_or_insert_with(Vec::new)
+            .push(filter.clone());
+    }
+
+    netcdf::copy::copy(&src, &mut dst, &options)?;
+    Ok(())
+}
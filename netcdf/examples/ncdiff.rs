@@ -0,0 +1,74 @@
+use clap::Parser;
+use netcdf::diff::{Differ, Tolerance};
+
+/// Compare two netCDF files, exits with 1 if they differ
+#[derive(Debug, Parser)]
+struct Opt {
+    /// Allowed absolute difference of numbers
+    #[arg(short = 'a', long, default_value_t = 0.0)]
+    absolute: f64,
+    /// Allowed relative difference of numbers
+    #[arg(short = 'r', long, default_value_t = 0.0)]
+    relative: f64,
+    /// Tolerance of a variable, as `var=absolute,relative`
+    #[arg(short = 't', long, value_parser = parse_tolerance)]
+    tolerance: Vec<(String, Tolerance)>,
+    /// Only compare metadata, not data
+    #[arg(short = 'm', long)]
+    metadata: bool,
+    /// Print the report as JSON
+    #[cfg(feature = "serde")]
+    #[arg(long)]
+    json: bool,
+    first: std::path::PathBuf,
+    second: std::path::PathBuf,
+}
+
+fn parse_tolerance(s: &str) -> Result<(String, Tolerance), String> {
+    let (var, tolerance) = s
+        .rsplit_once('=')
+        .and_then(|(var, tolerance)| Some((var, tolerance.split_once(',')?)))
+        .ok_or_else(|| format!("expected `var=absolute,relative`, found `{s}`"))?;
+    let absolute = tolerance.0.parse().map_err(|e| format!("{e}"))?;
+    let relative = tolerance.1.parse().map_err(|e| format!("{e}"))?;
+    Ok((var.to_owned(), Tolerance { absolute, relative }))
+}
+
+fn main() {
+    let opt = Opt::parse();
+
+    match run(&opt) {
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(2);
+        }
+        Ok(identical) => {
+            std::process::exit(if identical { 0 } else { 1 });
+        }
+    }
+}
+
+fn run(opt: &Opt) -> Result<bool, Box<dyn std::error::Error>> {
+    let first = netcdf::open(&opt.first)?;
+    let second = netcdf::open(&opt.second)?;
+
+    let mut differ = Differ::new().with_tolerance(Tolerance {
+        absolute: opt.absolute,
+        relative: opt.relative,
+    });
+    for (var, tolerance) in &opt.tolerance {
+        differ = differ.with_variable_tolerance(var, *tolerance);
+    }
+    if opt.metadata {
+        differ = differ.without_data();
+    }
+
+    let report = differ.diff(&first, &second)?;
+    #[cfg(feature = "serde")]
+    if opt.json {
+        println!("{}", serde_json::to_string(&report)?);
+        return Ok(report.is_identical());
+    }
+    print!("{report}");
+    Ok(report.is_identical())
+}
@@ -1,15 +1,32 @@
 use clap::Parser;
-use netcdf::types::NcVariableType;
+use netcdf::cdl::{CdlOptions, Data};
 
+/// Print a netCDF file as CDL
 #[derive(Debug, Parser)]
+#[command(disable_help_flag = true)]
 struct Opt {
+    /// Print help
+    #[arg(long, action = clap::ArgAction::Help)]
+    help: Option<bool>,
+    /// Header only, no data
+    #[arg(short = 'h')]
+    header: bool,
+    /// Data of coordinate variables only
+    #[arg(short = 'c', conflicts_with = "header")]
+    coordinates: bool,
+    /// Data of the given variables only
+    #[arg(short = 'v', value_delimiter = ',', conflicts_with_all = ["header", "coordinates"])]
+    variables: Vec<String>,
+    /// Significant digits of float and double values, as `f` or `f,d`
+    #[arg(short = 'p', value_delimiter = ',', num_args = 1..=2)]
+    precision: Vec<usize>,
     path: std::path::PathBuf,
 }
 
 fn main() {
     let opt = Opt::parse();
 
-    match run(&opt.path) {
+    match run(&opt) {
         Err(e) => {
             println!("{}", e);
             std::process::exit(1);
@@ -20,176 +37,30 @@ fn main() {
     }
 }
 
-fn run(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
-    let file = netcdf::open(path)?;
+fn run(opt: &Opt) -> Result<(), Box<dyn std::error::Error>> {
+    let file = netcdf::open(&opt.path)?;
 
-    println!("{}", file.path()?.to_str().unwrap());
-    print_file(&file)
-}
-
-fn print_file(g: &netcdf::File) -> Result<(), Box<dyn std::error::Error>> {
-    let mut dims = g.dimensions().peekable();
-    if dims.peek().is_some() {
-        println!("Dimensions:");
-        for d in dims {
-            if d.is_unlimited() {
-                println!("\t{} : Unlimited ({})", d.name(), d.len());
-            } else {
-                println!("\t{} : ({})", d.name(), d.len());
-            }
-        }
-    }
-    let types = g.types()?.collect::<Vec<_>>();
-    if !types.is_empty() {
-        println!("Types:");
-        print_types(&types)?;
-    }
-
-    let mut variables = g.variables().peekable();
-    if variables.peek().is_some() {
-        println!("Variables:");
-        for v in variables {
-            print!("\t{}", v.name());
-            print!("(");
-            for d in v.dimensions() {
-                print!(" {} ", d.name());
-            }
-            println!("): {}", type_name(&v.vartype()));
-            for a in v.attributes() {
-                println!("\t\t{} = {:?}", a.name(), a.value()?);
-            }
-        }
-    }
-    let mut attributes = g.attributes().peekable();
-    if attributes.peek().is_some() {
-        println!("Attributes:");
-        for a in g.attributes() {
-            println!("\t\t{} = {:?}", a.name(), a.value()?);
-        }
-    }
-    if let Some(g) = g.root() {
-        for g in g.groups() {
-            println!();
-            print_group(&g)?;
-        }
+    let mut options = CdlOptions {
+        data: if opt.header {
+            Data::Header
+        } else if opt.coordinates {
+            Data::Coordinates
+        } else if !opt.variables.is_empty() {
+            Data::Variables(opt.variables.clone())
+        } else {
+            Data::All
+        },
+        ..CdlOptions::default()
+    };
+    if let Some(&float) = opt.precision.first() {
+        options.float_precision = float;
+        options.double_precision = opt.precision.get(1).copied().unwrap_or(float);
     }
 
+    let name = opt
+        .path
+        .file_stem()
+        .map_or_else(|| "dataset".into(), |name| name.to_string_lossy());
+    print!("{}", netcdf::cdl::to_string(&file, &name, &options)?);
     Ok(())
 }
-
-fn print_group(g: &netcdf::Group) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Group: {}", g.name());
-
-    let mut dims = g.dimensions().peekable();
-    if dims.peek().is_some() {
-        println!("Dimensions:");
-        for d in dims {
-            if d.is_unlimited() {
-                println!("\t{} : Unlimited ({})", d.name(), d.len());
-            } else {
-                println!("\t{} : ({})", d.name(), d.len());
-            }
-        }
-    }
-
-    let types = g.types().collect::<Vec<_>>();
-    if !types.is_empty() {
-        println!("Types:");
-        print_types(&types)?;
-    }
-
-    let mut variables = g.variables().peekable();
-    if variables.peek().is_some() {
-        println!("Variables:");
-        for v in variables {
-            print!("\t{}", type_name(&v.vartype()));
-            print!("(");
-            for d in v.dimensions() {
-                print!(" {} ", d.name());
-            }
-            println!("): {}", type_name(&v.vartype()));
-            for a in v.attributes() {
-                println!("\t\t{} = {:?}", a.name(), a.value()?);
-            }
-        }
-    }
-    let mut attributes = g.attributes().peekable();
-    if attributes.peek().is_some() {
-        println!("Attributes:");
-        for a in g.attributes() {
-            println!("\t\t{} = {:?}", a.name(), a.value()?);
-        }
-    }
-    for g in g.groups() {
-        println!();
-        print_group(&g)?;
-    }
-
-    Ok(())
-}
-
-fn print_types(types: &[NcVariableType]) -> Result<(), Box<dyn std::error::Error>> {
-    for t in types {
-        match t {
-            NcVariableType::Int(_)
-            | NcVariableType::String
-            | NcVariableType::Float(_)
-            | NcVariableType::Char => unreachable!(),
-            NcVariableType::Opaque(o) => {
-                print!("\t{}: ", o.name);
-                println!("Opaque({})", o.size)
-            }
-            NcVariableType::Enum(e) => {
-                print!("\t{}: ", e.name);
-                println!("Enum({})", type_name_enum(e))
-            }
-            NcVariableType::Vlen(v) => {
-                print!("\t{}: ", v.name);
-                println!("Vlen({})", type_name(&v.basetype))
-            }
-            NcVariableType::Compound(c) => {
-                print!("\t{}: ", c.name);
-                print!("Compound({{");
-                for field in &c.fields {
-                    print!(" {}: {} ", field.name, type_name(&field.basetype));
-                }
-                println!("}})");
-            }
-        }
-    }
-    Ok(())
-}
-fn type_name(t: &NcVariableType) -> &str {
-    use netcdf::types::*;
-    match t {
-        NcVariableType::Int(IntType::U8) => "u8",
-        NcVariableType::Int(IntType::I8) => "i8",
-        NcVariableType::Int(IntType::U16) => "u16",
-        NcVariableType::Int(IntType::I16) => "i16",
-        NcVariableType::Int(IntType::U32) => "u32",
-        NcVariableType::Int(IntType::I32) => "i32",
-        NcVariableType::Int(IntType::U64) => "u64",
-        NcVariableType::Int(IntType::I64) => "i64",
-        NcVariableType::Float(FloatType::F32) => "f32",
-        NcVariableType::Float(FloatType::F64) => "f64",
-        NcVariableType::String => "string",
-        NcVariableType::Char => "char",
-        NcVariableType::Opaque(x) => &x.name,
-        NcVariableType::Enum(x) => &x.name,
-        NcVariableType::Vlen(x) => &x.name,
-        NcVariableType::Compound(x) => &x.name,
-    }
-}
-fn type_name_enum(t: &netcdf::types::EnumType) -> &str {
-    use netcdf::types::EnumTypeValues::*;
-    match &t.fieldvalues {
-        U8(_) => "u8",
-        I8(_) => "i8",
-        U16(_) => "u16",
-        I16(_) => "i16",
-        U32(_) => "u32",
-        I32(_) => "i32",
-        U64(_) => "u64",
-        I64(_) => "i64",
-    }
-}
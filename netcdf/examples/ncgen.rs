@@ -0,0 +1,68 @@
+use clap::{Parser, ValueEnum};
+use netcdf::cdl::Schema;
+use netcdf::Options;
+
+/// Create a netCDF file from CDL
+#[derive(Debug, Parser)]
+struct Opt {
+    /// Output file, `<dataset name>.nc` if not given
+    #[arg(short = 'o')]
+    output: Option<std::path::PathBuf>,
+    /// Format of the output file
+    #[arg(short = 'k', value_enum, default_value_t = Kind::Nc4)]
+    kind: Kind,
+    path: std::path::PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Kind {
+    /// Classic format
+    Nc3,
+    /// 64 bit offset format
+    Nc6,
+    /// `netCDF-4` format
+    Nc4,
+    /// `netCDF-4` classic model format
+    Nc7,
+    /// 64 bit data format
+    Cdf5,
+}
+
+impl Kind {
+    fn options(self) -> Options {
+        match self {
+            Self::Nc3 => Options::empty(),
+            Self::Nc6 => Options::_64BIT_OFFSET,
+            Self::Nc4 => Options::NETCDF4,
+            Self::Nc7 => Options::NETCDF4 | Options::CLASSIC,
+            Self::Cdf5 => Options::_64BIT_DATA,
+        }
+    }
+}
+
+fn main() {
+    let opt = Opt::parse();
+
+    match run(&opt) {
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+        Ok(()) => {
+            std::process::exit(0);
+        }
+    }
+}
+
+fn run(opt: &Opt) -> Result<(), Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(&opt.path)?;
+    let schema = Schema::parse(&text).map_err(|e| format!("{}: {e}", opt.path.display()))?;
+
+    let output = opt
+        .output
+        .clone()
+        .unwrap_or_else(|| format!("{}.nc", schema.name).into());
+    let mut file = netcdf::create_with(output, opt.kind.options())?;
+    schema.write(&mut file)?;
+    Ok(())
+}
@@ -0,0 +1,583 @@
+//! Virtual datasets joining many files along a dimension (`NcML` aggregation)
+//!
+//! An archive of one file per forecast can be read as a single dataset:
+//! ```rust,no_run
+//! # fn main() -> Result<(), netcdf::Error> {
+//! use netcdf::aggregation::Aggregation;
+//!
+//! let files = ["forecast_00.nc", "forecast_06.nc", "forecast_12.nc"]
+//!     .iter()
+//!     .map(netcdf::open)
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! let aggregation = Aggregation::join_existing(files, "time")?;
+//! let temperature = aggregation.variable("temperature").unwrap();
+//! // Reads from every file holding one of the requested time steps
+//! let values = temperature.get_values::<f32, _>((5..15, .., ..))?;
+//! # Ok(()) }
+//! ```
+//!
+//! With the `ncml` feature an aggregation can also be described by an
+//! `NcML` document, see [`Aggregation::from_ncml`]
+
+#[cfg(feature = "ncml")]
+mod ncml;
+
+#[cfg(feature = "ndarray")]
+use ndarray::ArrayD;
+
+use crate::attribute::AttributeValue;
+use crate::error;
+use crate::extent::{Extent, Extents, StartCountStrideIterItem};
+use crate::types::{FloatType, NcTypeDescriptor, NcVariableType};
+use crate::value::Value;
+use crate::{Dimension, File, Variable};
+
+/// How the files of an [`Aggregation`] are joined
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Join {
+    /// Along a dimension of the files, where the indices of each file
+    /// follow those of the previous file (`joinExisting`)
+    Existing,
+    /// Along a new dimension, where each file is one index (`joinNew`)
+    New,
+}
+
+/// Files joined along a dimension, read as a single dataset
+///
+/// Variables which are not joined, and the dimensions and attributes of
+/// the dataset, are those of the first file
+pub struct Aggregation {
+    files: Vec<File>,
+    join: Join,
+    dimension: String,
+    /// Length of the joined dimension in each file
+    lengths: Vec<usize>,
+    /// Variables joined along a new dimension
+    variables: Vec<String>,
+    /// Values of the new dimension, one for each file
+    coordinates: Option<Vec<String>>,
+}
+
+/// Name of a file in errors
+fn describe(file: &File, index: usize) -> String {
+    file.path().map_or_else(
+        |_| format!("file {index}"),
+        |path| path.display().to_string(),
+    )
+}
+
+impl Aggregation {
+    /// Join `files` along `dimension`, which must exist in every file and
+    /// be the first dimension of the variables using it
+    ///
+    /// # Errors
+    ///
+    /// No files, or a file without `dimension`
+    pub fn join_existing(files: Vec<File>, dimension: &str) -> error::Result<Self> {
+        if files.is_empty() {
+            return Err("an aggregation needs at least one file".into());
+        }
+        let lengths = files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| {
+                file.try_dimension(dimension)?
+                    .ok_or_else(|| {
+                        error::Error::NotFound(format!(
+                            "dimension {dimension} in {}",
+                            describe(file, i)
+                        ))
+                    })?
+                    .try_len()
+            })
+            .collect::<error::Result<_>>()?;
+        Ok(Self {
+            files,
+            join: Join::Existing,
+            dimension: dimension.to_owned(),
+            lengths,
+            variables: vec![],
+            coordinates: None,
+        })
+    }
+
+    /// Join `variables` of `files` along a new first dimension `dimension`
+    ///
+    /// # Errors
+    ///
+    /// No files, or `dimension` already exists in the first file
+    pub fn join_new<S: AsRef<str>>(
+        files: Vec<File>,
+        dimension: &str,
+        variables: &[S],
+    ) -> error::Result<Self> {
+        if files.is_empty() {
+            return Err("an aggregation needs at least one file".into());
+        }
+        if files[0].try_dimension(dimension)?.is_some() {
+            return Err(error::Error::AlreadyExists);
+        }
+        Ok(Self {
+            lengths: vec![1; files.len()],
+            files,
+            join: Join::New,
+            dimension: dimension.to_owned(),
+            variables: variables.iter().map(|v| v.as_ref().to_owned()).collect(),
+            coordinates: None,
+        })
+    }
+
+    /// Set the values of the new dimension, one for each file
+    /// (`coordValue`)
+    ///
+    /// The values are read from the coordinate variable named after the
+    /// dimension, of type `double` if every value is a number, otherwise
+    /// of type `string`
+    ///
+    /// # Errors
+    ///
+    /// Not one value for each file
+    pub fn with_coordinates(mut self, coordinates: Vec<String>) -> error::Result<Self> {
+        if coordinates.len() != self.files.len() {
+            return Err(error::Error::DimensionMismatch {
+                wanted: self.files.len(),
+                actual: coordinates.len(),
+            });
+        }
+        self.coordinates = Some(coordinates);
+        Ok(self)
+    }
+
+    /// The joined files
+    pub fn files(&self) -> &[File] {
+        &self.files
+    }
+
+    /// How the files are joined
+    pub fn join(&self) -> Join {
+        self.join
+    }
+
+    /// Name of the joined dimension
+    pub fn dimension(&self) -> &str {
+        &self.dimension
+    }
+
+    /// Length of the joined dimension
+    pub fn len(&self) -> usize {
+        self.lengths.iter().sum()
+    }
+
+    /// The joined dimension has no indices
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Values of the new dimension, if given
+    pub fn coordinates(&self) -> Option<&[String]> {
+        self.coordinates.as_deref()
+    }
+
+    /// Get a variable of the root group, `None` if it is not in the first
+    /// file or can not be aggregated, see [`try_variable`](Self::try_variable)
+    pub fn variable(&self, name: &str) -> Option<AggregatedVariable<'_>> {
+        self.try_variable(name).ok().flatten()
+    }
+
+    /// Get a variable of the root group, `None` if not in the first file
+    ///
+    /// # Errors
+    ///
+    /// A joined variable which is missing from a file, has another
+    /// shape in a file, or uses the joined dimension after its first
+    /// dimension
+    pub fn try_variable(&self, name: &str) -> error::Result<Option<AggregatedVariable<'_>>> {
+        if let (Join::New, Some(coordinates)) = (self.join, &self.coordinates) {
+            if name == self.dimension {
+                return Ok(Some(self.coordinate_variable(coordinates)));
+            }
+        }
+        let Some(first) = self.files[0].try_variable(name)? else {
+            return Ok(None);
+        };
+        let mut dimensions = first
+            .dimensions()
+            .iter()
+            .map(Dimension::try_name)
+            .collect::<error::Result<Vec<_>>>()?;
+        let shape_of = |var: &Variable| {
+            var.dimensions()
+                .iter()
+                .map(Dimension::try_len)
+                .collect::<error::Result<Vec<_>>>()
+        };
+        let mut shape = shape_of(&first)?;
+
+        let joined = match self.join {
+            Join::Existing => match dimensions.iter().position(|d| *d == self.dimension) {
+                None => false,
+                Some(0) => true,
+                Some(_) => {
+                    return Err(error::Error::Unsupported(format!(
+                        "variable {name} is joined along {} which is not its first dimension",
+                        self.dimension
+                    )))
+                }
+            },
+            Join::New => self.variables.iter().any(|v| v == name),
+        };
+        if !joined {
+            return Ok(Some(AggregatedVariable {
+                name: name.to_owned(),
+                dimensions,
+                shape,
+                parts: vec![first],
+                lengths: &[],
+                new: false,
+                coordinates: None,
+            }));
+        }
+
+        // Dimensions after the joined one must match in every file
+        let skip = usize::from(self.join == Join::Existing);
+        let mut parts = vec![first];
+        for (i, file) in self.files.iter().enumerate().skip(1) {
+            let var = file.try_variable(name)?.ok_or_else(|| {
+                error::Error::NotFound(format!("variable {name} in {}", describe(file, i)))
+            })?;
+            let other = shape_of(&var)?;
+            if other.get(skip..) != shape.get(skip..) {
+                return Err(format!(
+                    "variable {name} has shape {other:?} in {}, but {shape:?} in {}",
+                    describe(file, i),
+                    describe(&self.files[0], 0)
+                )
+                .into());
+            }
+            parts.push(var);
+        }
+        match self.join {
+            Join::Existing => shape[0] = self.len(),
+            Join::New => {
+                shape.insert(0, self.files.len());
+                dimensions.insert(0, self.dimension.clone());
+            }
+        }
+        Ok(Some(AggregatedVariable {
+            name: name.to_owned(),
+            dimensions,
+            shape,
+            parts,
+            lengths: &self.lengths,
+            new: self.join == Join::New,
+            coordinates: None,
+        }))
+    }
+
+    /// Variable of the new dimension holding the coordinates
+    fn coordinate_variable(&self, coordinates: &[String]) -> AggregatedVariable<'_> {
+        let numbers = coordinates
+            .iter()
+            .map(|x| x.trim().parse::<f64>().ok().map(Value::Float))
+            .collect::<Option<Vec<_>>>();
+        let values =
+            numbers.unwrap_or_else(|| coordinates.iter().map(|x| Value::Str(x.clone())).collect());
+        AggregatedVariable {
+            name: self.dimension.clone(),
+            dimensions: vec![self.dimension.clone()],
+            shape: vec![self.files.len()],
+            parts: vec![],
+            lengths: &self.lengths,
+            new: true,
+            coordinates: Some(values),
+        }
+    }
+
+    /// Iterate over the variables of the root group of the first file,
+    /// and the coordinate variable of a new dimension
+    ///
+    /// Variables which can not be aggregated are skipped,
+    /// see [`try_variables`](Self::try_variables)
+    pub fn variables(&self) -> impl Iterator<Item = AggregatedVariable<'_>> {
+        self.try_variables()
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+    }
+
+    /// Iterate over the variables of the root group of the first file,
+    /// and the coordinate variable of a new dimension
+    pub fn try_variables(
+        &self,
+    ) -> error::Result<impl Iterator<Item = error::Result<AggregatedVariable<'_>>>> {
+        let mut names = self.files[0]
+            .try_variables()?
+            .map(|var| var?.try_name())
+            .collect::<error::Result<Vec<_>>>()?;
+        if self.join == Join::New && self.coordinates.is_some() && !names.contains(&self.dimension)
+        {
+            names.insert(0, self.dimension.clone());
+        }
+        Ok(names.into_iter().map(|name| {
+            self.try_variable(&name)
+                .map(|var| var.expect("variable of the first file"))
+        }))
+    }
+}
+
+/// A variable of an [`Aggregation`], reading from the files holding the
+/// requested indices
+#[derive(Debug, Clone)]
+pub struct AggregatedVariable<'a> {
+    name: String,
+    dimensions: Vec<String>,
+    shape: Vec<usize>,
+    /// The variable in each file, only the first if not joined,
+    /// none for coordinates
+    parts: Vec<Variable<'a>>,
+    /// Length of the joined dimension in each file, empty if not joined
+    lengths: &'a [usize],
+    /// The joined dimension is not a dimension of the files
+    new: bool,
+    /// Values of the coordinate variable of a new dimension
+    coordinates: Option<Vec<Value>>,
+}
+
+impl<'a> AggregatedVariable<'a> {
+    /// Name of the variable
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Names of the dimensions
+    pub fn dimensions(&self) -> &[String] {
+        &self.dimensions
+    }
+
+    /// Length along each dimension
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// The variable has no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The variable is joined along the dimension of the aggregation
+    pub fn is_joined(&self) -> bool {
+        !self.lengths.is_empty()
+    }
+
+    /// The variable in the first file, which gives the type and attributes,
+    /// `None` for the coordinates of a new dimension
+    pub fn first(&self) -> Option<&Variable<'a>> {
+        self.parts.first()
+    }
+
+    /// Get the type of the variable
+    pub fn vartype(&self) -> NcVariableType {
+        match self.coordinates.as_deref() {
+            Some([Value::Float(_), ..]) => NcVariableType::Float(FloatType::F64),
+            Some(_) => NcVariableType::String,
+            None => self.parts[0].vartype(),
+        }
+    }
+
+    /// Get the value of an attribute in the first file
+    pub fn attribute_value(&self, name: &str) -> Option<error::Result<AttributeValue>> {
+        self.parts.first()?.attribute_value(name)
+    }
+
+    /// Resolve `extents` against the shape of the variable
+    fn items(&self, extents: &Extents) -> error::Result<Vec<StartCountStrideIterItem>> {
+        let mut items = extents.items_with_shape(&self.shape)?;
+        for (item, &len) in items.iter_mut().zip(&self.shape) {
+            if item.count <= 1 {
+                item.stride = 1;
+            }
+            let stride = usize::try_from(item.stride).map_err(|_| error::Error::Stride)?;
+            if stride == 0 {
+                return Err(error::Error::Stride);
+            }
+            if item.count > 0 {
+                let last = (item.count - 1)
+                    .checked_mul(stride)
+                    .and_then(|offset| offset.checked_add(item.start));
+                if !matches!(last, Some(last) if last < len) {
+                    return Err(error::Error::IndexMismatch);
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    /// Coordinates of a new dimension at `extents`, `None` for other variables
+    fn coordinate_values(&self, extents: &Extents) -> error::Result<Option<Vec<Value>>> {
+        let Some(coordinates) = &self.coordinates else {
+            return Ok(None);
+        };
+        let item = &self.items(extents)?[0];
+        let stride = item.stride.unsigned_abs();
+        Ok(Some(
+            (0..item.count)
+                .map(|i| coordinates[item.start + i * stride].clone())
+                .collect(),
+        ))
+    }
+
+    /// Read `extents` using `get` on the variable of each file holding some
+    /// of the indices, concatenating the values
+    fn read<T>(
+        &self,
+        extents: &Extents,
+        get: impl Fn(&Variable, Extents) -> error::Result<Vec<T>>,
+    ) -> error::Result<Vec<T>> {
+        if !self.is_joined() {
+            return get(&self.parts[0], extents.clone());
+        }
+        let items = self.items(extents)?;
+        if items.iter().any(|item| item.count == 0) {
+            return Ok(vec![]);
+        }
+        let (first, rest) = items
+            .split_first()
+            .expect("joined variables have a dimension");
+        let stride = first.stride.unsigned_abs();
+
+        let mut values = Vec::with_capacity(items.iter().map(|item| item.count).product());
+        for (part, start, count) in split(self.lengths, first.start, first.count, stride) {
+            let mut local = Vec::with_capacity(items.len());
+            if !self.new {
+                local.push(Extent::SliceCount {
+                    start,
+                    count,
+                    stride: first.stride,
+                });
+            }
+            local.extend(rest.iter().map(|item| Extent::SliceCount {
+                start: item.start,
+                count: item.count,
+                stride: item.stride,
+            }));
+            values.extend(get(&self.parts[part], Extents::from(local))?);
+        }
+        Ok(values)
+    }
+
+    /// Get multiple values, see [`Variable::get_values`]
+    ///
+    /// Numeric coordinates of a new dimension are read as `f64`
+    pub fn get_values<T: NcTypeDescriptor + Copy, E>(&self, extents: E) -> error::Result<Vec<T>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        if let Some(values) = self.coordinate_values(&extents)? {
+            let double = NcVariableType::Float(FloatType::F64);
+            if self.vartype() != double || T::type_descriptor() != double {
+                return Err(error::Error::TypeMismatch);
+            }
+            return Ok(values
+                .into_iter()
+                .map(|value| match value {
+                    // Safety: `T` is described as `f64`
+                    Value::Float(x) => unsafe { std::mem::transmute_copy::<f64, T>(&x) },
+                    _ => unreachable!("numeric coordinates"),
+                })
+                .collect());
+        }
+        self.read(&extents, |var, extents| var.get_values(extents))
+    }
+
+    /// Get values of any type, see [`Variable::get_dynamic_values`]
+    pub fn get_dynamic_values<E>(&self, extents: E) -> error::Result<Vec<Value>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        if let Some(values) = self.coordinate_values(&extents)? {
+            return Ok(values);
+        }
+        self.read(&extents, |var, extents| var.get_dynamic_values(extents))
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Get values, see [`Variable::get`]
+    pub fn get<T: NcTypeDescriptor + Copy, E>(&self, extents: E) -> error::Result<ArrayD<T>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        if !self.is_joined() {
+            return self.parts[0].get(extents);
+        }
+        let shape = self
+            .items(&extents)?
+            .iter()
+            .filter(|item| !item.is_an_index)
+            .map(|item| item.count)
+            .collect::<Vec<_>>();
+        let values = self.get_values(extents)?;
+        ArrayD::from_shape_vec(shape, values).map_err(|e| e.to_string().into())
+    }
+}
+
+/// Reads of `count` indices from `start` by `stride` along dimensions of
+/// `lengths` joined end to end, as the dimension and the start and count
+/// along it
+fn split(
+    lengths: &[usize],
+    start: usize,
+    count: usize,
+    stride: usize,
+) -> Vec<(usize, usize, usize)> {
+    let mut reads = vec![];
+    let mut offset = 0;
+    // Number of indices read so far
+    let mut done = 0;
+    for (part, &len) in lengths.iter().enumerate() {
+        let end = offset + len;
+        let index = start + done * stride;
+        if done < count && index < end {
+            let n = (end - start).div_ceil(stride).min(count) - done;
+            reads.push((part, index - offset, n));
+            done += n;
+        }
+        offset = end;
+    }
+    reads
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_contiguous() {
+        let lengths = [2, 3, 0, 2];
+        assert_eq!(split(&lengths, 0, 7, 1), [(0, 0, 2), (1, 0, 3), (3, 0, 2)]);
+        assert_eq!(split(&lengths, 1, 3, 1), [(0, 1, 1), (1, 0, 2)]);
+        assert_eq!(split(&lengths, 5, 1, 1), [(3, 0, 1)]);
+        assert!(split(&lengths, 3, 0, 1).is_empty());
+    }
+
+    #[test]
+    fn split_strided() {
+        let lengths = [2, 3, 2];
+        // 1, 4, 7 would be past the end
+        assert_eq!(split(&lengths, 1, 2, 3), [(0, 1, 1), (1, 2, 1)]);
+        // 0, 2, 4, 6
+        assert_eq!(split(&lengths, 0, 4, 2), [(0, 0, 1), (1, 0, 2), (2, 1, 1)]);
+        // One index of each new dimension
+        assert_eq!(split(&[1, 1, 1, 1], 1, 2, 2), [(1, 0, 1), (3, 0, 1)]);
+    }
+}
@@ -0,0 +1,239 @@
+//! Aggregations described by `NcML`
+
+use std::path::{Path, PathBuf};
+
+use super::{Aggregation, Join};
+use crate::error;
+
+/// An aggregation read from `NcML`, before opening the files
+#[derive(Debug, PartialEq, Eq)]
+struct Description {
+    join: Join,
+    dimension: String,
+    files: Vec<PathBuf>,
+    /// `coordValue` of each file, the location if not given
+    coordinates: Vec<String>,
+    variables: Vec<String>,
+}
+
+fn attribute<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> error::Result<&'a str> {
+    node.attribute(name).ok_or_else(|| {
+        error::Error::NotFound(format!(
+            "attribute {name} of NcML element {}",
+            node.tag_name().name()
+        ))
+    })
+}
+
+fn unsupported(node: roxmltree::Node) -> error::Error {
+    error::Error::Unsupported(format!("NcML element {}", node.tag_name().name()))
+}
+
+/// Path of a `location`, relative to `base`
+fn resolve(base: &Path, location: &str) -> PathBuf {
+    base.join(location.strip_prefix("file:").unwrap_or(location))
+}
+
+/// Files in `dir` ending with `suffix`, sorted by path
+fn scan(dir: &Path, suffix: &str, subdirs: bool, files: &mut Vec<PathBuf>) -> error::Result<()> {
+    let io_error = |e: std::io::Error| error::Error::Str(format!("{}: {e}", dir.display()));
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(io_error)?
+        .map(|entry| Ok(entry.map_err(io_error)?.path()))
+        .collect::<error::Result<Vec<_>>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            if subdirs {
+                scan(&path, suffix, subdirs, files)?;
+            }
+        } else if path.to_string_lossy().ends_with(suffix) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+impl Description {
+    fn parse(text: &str, base: &Path) -> error::Result<Self> {
+        let document = roxmltree::Document::parse(text).map_err(|e| format!("NcML: {e}"))?;
+        let root = document.root_element();
+        if root.tag_name().name() != "netcdf" {
+            return Err(unsupported(root));
+        }
+        let mut aggregation = None;
+        for node in root.children().filter(roxmltree::Node::is_element) {
+            match node.tag_name().name() {
+                "aggregation" if aggregation.is_none() => aggregation = Some(node),
+                _ => return Err(unsupported(node)),
+            }
+        }
+        let aggregation = aggregation
+            .ok_or_else(|| error::Error::NotFound("NcML element aggregation".to_owned()))?;
+
+        let join = match attribute(aggregation, "type")? {
+            "joinExisting" => Join::Existing,
+            "joinNew" => Join::New,
+            other => {
+                return Err(error::Error::Unsupported(format!(
+                    "NcML aggregation {other}"
+                )))
+            }
+        };
+        let mut description = Self {
+            join,
+            dimension: attribute(aggregation, "dimName")?.to_owned(),
+            files: vec![],
+            coordinates: vec![],
+            variables: vec![],
+        };
+        for node in aggregation.children().filter(roxmltree::Node::is_element) {
+            match node.tag_name().name() {
+                "netcdf" => {
+                    if node.children().any(|child| child.is_element()) {
+                        return Err(error::Error::Unsupported(
+                            "NcML elements in a dataset of an aggregation".to_owned(),
+                        ));
+                    }
+                    let location = attribute(node, "location")?;
+                    description.files.push(resolve(base, location));
+                    description
+                        .coordinates
+                        .push(node.attribute("coordValue").unwrap_or(location).to_owned());
+                }
+                "scan" => {
+                    if let Some(other) = node
+                        .attributes()
+                        .find(|attr| !matches!(attr.name(), "location" | "suffix" | "subdirs"))
+                    {
+                        return Err(error::Error::Unsupported(format!(
+                            "NcML scan attribute {}",
+                            other.name()
+                        )));
+                    }
+                    let mut files = vec![];
+                    scan(
+                        &resolve(base, attribute(node, "location")?),
+                        node.attribute("suffix").unwrap_or_default(),
+                        node.attribute("subdirs") != Some("false"),
+                        &mut files,
+                    )?;
+                    description
+                        .coordinates
+                        .extend(files.iter().map(|path| path.display().to_string()));
+                    description.files.extend(files);
+                }
+                "variableAgg" => description
+                    .variables
+                    .push(attribute(node, "name")?.to_owned()),
+                _ => return Err(unsupported(node)),
+            }
+        }
+        Ok(description)
+    }
+}
+
+impl Aggregation {
+    /// Open the files of the aggregation described by an `NcML` document,
+    /// where relative locations are relative to `base`
+    ///
+    /// ```xml
+    /// <netcdf xmlns="http://www.unidata.ucar.edu/namespaces/netcdf/ncml-2.2">
+    ///   <aggregation dimName="time" type="joinExisting">
+    ///     <netcdf location="forecast_00.nc"/>
+    ///     <scan location="archive/" suffix=".nc" subdirs="false"/>
+    ///   </aggregation>
+    /// </netcdf>
+    /// ```
+    /// The supported elements are a single `joinExisting` or `joinNew`
+    /// `aggregation` of `netcdf` datasets, `scan`s of directories and
+    /// `variableAgg`s. Scanned files are sorted by path. Values of the new
+    /// dimension of `joinNew` are the `coordValue`s of the datasets, or
+    /// their locations if not given
+    ///
+    /// # Errors
+    ///
+    /// Invalid or unsupported `NcML`, or a file which can not be opened or joined
+    pub fn from_ncml<P>(text: &str, base: P) -> error::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let description = Description::parse(text, base.as_ref())?;
+        let files = description
+            .files
+            .iter()
+            .map(crate::open)
+            .collect::<error::Result<Vec<_>>>()?;
+        match description.join {
+            Join::Existing => Self::join_existing(files, &description.dimension),
+            Join::New => Self::join_new(files, &description.dimension, &description.variables)?
+                .with_coordinates(description.coordinates),
+        }
+    }
+
+    /// Open the aggregation described by an `NcML` file, see
+    /// [`from_ncml`](Self::from_ncml)
+    ///
+    /// # Errors
+    ///
+    /// See [`from_ncml`](Self::from_ncml)
+    pub fn open_ncml<P>(path: P) -> error::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| error::Error::Str(format!("{}: {e}", path.display())))?;
+        Self::from_ncml(&text, path.parent().unwrap_or_else(|| Path::new("")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<netcdf xmlns="http://www.unidata.ucar.edu/namespaces/netcdf/ncml-2.2">
+  <aggregation dimName="run" type="joinNew">
+    <variableAgg name="temperature"/>
+    <netcdf location="file:a.nc" coordValue="2024-01-01"/>
+    <netcdf location="/data/b.nc"/>
+  </aggregation>
+</netcdf>"#;
+        let description = Description::parse(text, Path::new("base")).unwrap();
+        assert_eq!(
+            description,
+            Description {
+                join: Join::New,
+                dimension: "run".to_owned(),
+                files: vec!["base/a.nc".into(), "/data/b.nc".into()],
+                coordinates: vec!["2024-01-01".to_owned(), "/data/b.nc".to_owned()],
+                variables: vec!["temperature".to_owned()],
+            }
+        );
+    }
+
+    #[test]
+    fn unsupported_elements() {
+        let text = r#"<netcdf>
+  <aggregation dimName="time" type="union"/>
+</netcdf>"#;
+        assert!(matches!(
+            Description::parse(text, Path::new("")),
+            Err(error::Error::Unsupported(_))
+        ));
+
+        let text = r#"<netcdf>
+  <attribute name="title" value="renamed"/>
+  <aggregation dimName="time" type="joinExisting"/>
+</netcdf>"#;
+        assert!(matches!(
+            Description::parse(text, Path::new("")),
+            Err(error::Error::Unsupported(_))
+        ));
+
+        assert!(Description::parse("<netcdf>", Path::new("")).is_err());
+    }
+}
@@ -9,6 +9,10 @@ use netcdf_sys::*;
 
 use super::error;
 use super::utils::{checked_with_lock, with_lock};
+use crate::types::{
+    CompoundType, EnumType, NcTypeDescriptor, NcVariableType, OpaqueType, VlenType,
+};
+use crate::value::{Encoded, Value};
 
 /// Extra properties of a variable or a group can be represented
 /// with attributes. Primarily added with `add_attribute` on
@@ -41,13 +45,18 @@ impl Attribute<'_> {
     /// # Panics
     /// attribute could have a name containing an invalid utf8-sequence
     pub fn name(&self) -> &str {
+        self.try_name()
+            .expect("Attribute name contains invalid sequence")
+    }
+    /// Get the name of the attribute
+    pub fn try_name(&self) -> error::Result<&str> {
         let zeropos = self
             .name
             .iter()
             .position(|&x| x == 0)
             .unwrap_or(self.name.len());
         std::str::from_utf8(&self.name[..zeropos])
-            .expect("Attribute name contains invalid sequence")
+            .map_err(|e| format!("Attribute name contains invalid sequence: {e}").into())
     }
     /// Number of elements in this attribute
     fn num_elems(&self) -> error::Result<usize> {
@@ -63,7 +72,7 @@ impl Attribute<'_> {
         Ok(nelems as _)
     }
     /// Type of this attribute
-    fn typ(&self) -> error::Result<nc_type> {
+    pub(crate) fn typ(&self) -> error::Result<nc_type> {
         let mut atttype = 0;
         checked_with_lock(|| unsafe {
             nc_inq_atttype(
@@ -356,10 +365,7 @@ impl Attribute<'_> {
                         buf.as_mut_ptr().cast::<u8>().cast::<c_char>(),
                     )
                 })?;
-                let pos = buf.iter().position(|&x| x == 0).unwrap_or(buf.len());
-                Ok(AttributeValue::Str(String::from(String::from_utf8_lossy(
-                    &buf[..pos],
-                ))))
+                Ok(text_value(buf))
             }
             NC_STRING => {
                 let mut buf: Vec<*mut c_char> = vec![std::ptr::null_mut(); attlen];
@@ -384,8 +390,84 @@ impl Attribute<'_> {
                 with_lock(|| unsafe { nc_free_string(attlen, buf.as_mut_ptr()) });
                 Ok(AttributeValue::Strs(result))
             }
-            x => Err(error::Error::TypeUnknown(x)),
+            x => {
+                let typ = crate::types::read_type(self.ncid, x)?;
+                let values = self.dynamic_values(x, &typ, attlen)?;
+                match typ {
+                    NcVariableType::Compound(typ) => Ok(AttributeValue::Compound(typ, values)),
+                    NcVariableType::Enum(typ) => Ok(AttributeValue::Enum(typ, values)),
+                    NcVariableType::Opaque(typ) => Ok(AttributeValue::Opaque(typ, values)),
+                    NcVariableType::Vlen(typ) => Ok(AttributeValue::Vlen(typ, values)),
+                    _ => Err(error::Error::TypeUnknown(x)),
+                }
+            }
+        }
+    }
+
+    /// Read the values of a user defined type
+    fn dynamic_values(
+        &self,
+        typid: nc_type,
+        typ: &NcVariableType,
+        attlen: usize,
+    ) -> error::Result<Vec<Value>> {
+        if !matches!(
+            typ,
+            NcVariableType::Compound(_)
+                | NcVariableType::Enum(_)
+                | NcVariableType::Opaque(_)
+                | NcVariableType::Vlen(_)
+        ) {
+            return Err(error::Error::TypeUnknown(typid));
+        }
+        let mut buffer = vec![0_u8; attlen * typ.size()];
+        checked_with_lock(|| unsafe {
+            nc_get_att(
+                self.ncid,
+                self.varid,
+                self.name.as_ptr().cast(),
+                buffer.as_mut_ptr().cast(),
+            )
+        })?;
+        // Safety: the buffer was filled by netCDF using the type of the attribute
+        unsafe {
+            let values = crate::value::decode(typ, &buffer);
+            crate::value::free(typ, &mut buffer);
+            values
+        }
+    }
+
+    /// Get the values of the attribute as `T`
+    ///
+    /// This supports attributes of user defined types. Types holding strings
+    /// or ragged arrays are read using [`value`](Self::value), which releases
+    /// the memory allocated by `netCDF`
+    ///
+    /// # Errors
+    ///
+    /// The type of the attribute is not `T`, or holds strings or ragged arrays
+    pub fn value_typed<T: NcTypeDescriptor + Copy>(&self) -> error::Result<Vec<T>> {
+        let attlen = self.num_elems()?;
+        let typ = crate::types::read_type(self.ncid, self.typ()?)?;
+        if typ != T::type_descriptor() {
+            return Err(error::Error::TypeMismatch);
+        }
+        if crate::value::allocates(&typ) {
+            return Err(error::Error::Unsupported(
+                "typed attributes holding strings or ragged arrays".to_owned(),
+            ));
         }
+        let mut values = Vec::<T>::with_capacity(attlen);
+        checked_with_lock(|| unsafe {
+            nc_get_att(
+                self.ncid,
+                self.varid,
+                self.name.as_ptr().cast(),
+                values.as_mut_ptr().cast(),
+            )
+        })?;
+        unsafe { values.set_len(attlen) };
+        Ok(values)
     }
 }
 
@@ -445,10 +527,35 @@ impl<'a> Iterator for AttributeIterator<'a> {
     }
 }
 
+/// `NC_CHAR` text as an attribute value, text which can not
+/// be represented exactly as a `String` is kept as bytes
+pub(crate) fn text_value(bytes: Vec<u8>) -> AttributeValue {
+    let len = bytes.iter().rposition(|&x| x != 0).map_or(0, |i| i + 1);
+    if bytes[..len].contains(&0) {
+        return AttributeValue::Chars(bytes);
+    }
+    match std::str::from_utf8(&bytes[..len]) {
+        Ok(text) => AttributeValue::Str(text.to_owned()),
+        Err(_) => AttributeValue::Chars(bytes),
+    }
+}
+
 /// Holds the attribute value which can be inserted and
 /// returned from the file
+///
+/// Text is stored as `NC_CHAR` or `NC_STRING` depending on the variant:
+/// * [`Str`](Self::Str): `NC_CHAR` text which is valid UTF-8, trailing
+///   NUL bytes (e.g. the terminator written by C programs) are removed
+/// * [`Chars`](Self::Chars): `NC_CHAR` text with embedded NUL bytes or invalid UTF-8
+/// * [`Strs`](Self::Strs): `NC_STRING` values, also when there is only one
+///
+/// Reading an attribute gives the variant of the stored type, such that
+/// writing the value back reproduces the attribute exactly.
+/// Use `String::try_from` to get the text regardless of the type
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum AttributeValue {
     Uchar(u8),
     Uchars(Vec<u8>),
@@ -472,6 +579,16 @@ pub enum AttributeValue {
     Doubles(Vec<f64>),
     Str(String),
     Strs(Vec<String>),
+    /// Raw `NC_CHAR` text
+    Chars(Vec<u8>),
+    /// Values of a compound type
+    Compound(CompoundType, Vec<Value>),
+    /// Values of an enum type
+    Enum(EnumType, Vec<Value>),
+    /// Values of an opaque type
+    Opaque(OpaqueType, Vec<Value>),
+    /// Values of a ragged array type
+    Vlen(VlenType, Vec<Value>),
 }
 
 impl Attribute<'_> {
@@ -625,6 +742,15 @@ impl Attribute<'_> {
                     x.as_ptr().cast(),
                 )
             }),
+            AttributeValue::Chars(ref x) => checked_with_lock(|| unsafe {
+                nc_put_att_text(
+                    ncid,
+                    varid,
+                    cname.as_ptr().cast(),
+                    x.len(),
+                    x.as_ptr().cast(),
+                )
+            }),
             AttributeValue::Strs(ref x) => {
                 let cstrings: Vec<CString> = x
                     .iter()
@@ -645,6 +771,18 @@ impl Attribute<'_> {
                     )
                 })
             }
+            AttributeValue::Compound(typ, values) => {
+                put_dynamic(ncid, varid, &cname, NcVariableType::Compound(typ), &values)
+            }
+            AttributeValue::Enum(typ, values) => {
+                put_dynamic(ncid, varid, &cname, NcVariableType::Enum(typ), &values)
+            }
+            AttributeValue::Opaque(typ, values) => {
+                put_dynamic(ncid, varid, &cname, NcVariableType::Opaque(typ), &values)
+            }
+            AttributeValue::Vlen(typ, values) => {
+                put_dynamic(ncid, varid, &cname, NcVariableType::Vlen(typ), &values)
+            }
         }?;
 
         Ok(Self {
@@ -655,6 +793,33 @@ impl Attribute<'_> {
         })
     }
 
+    /// Put values of type `T`, adding the type to the group if missing
+    pub(crate) fn put_typed<T: NcTypeDescriptor>(
+        ncid: nc_type,
+        varid: nc_type,
+        name: &str,
+        values: &[T],
+    ) -> error::Result<Self> {
+        let cname = super::utils::short_name_to_bytes(name)?;
+        let typid = crate::types::find_or_add_type(ncid, &T::type_descriptor())?;
+        checked_with_lock(|| unsafe {
+            nc_put_att(
+                ncid,
+                varid,
+                cname.as_ptr().cast(),
+                typid,
+                values.len(),
+                values.as_ptr().cast(),
+            )
+        })?;
+        Ok(Self {
+            name: cname,
+            ncid,
+            varid,
+            _marker: PhantomData,
+        })
+    }
+
     pub(crate) fn find_from_name(
         ncid: nc_type,
         varid: Option<nc_type>,
@@ -691,6 +856,28 @@ impl Attribute<'_> {
     }
 }
 
+/// Encode and put values of a user defined type, adding the type if missing
+fn put_dynamic(
+    ncid: nc_type,
+    varid: nc_type,
+    cname: &[u8],
+    typ: NcVariableType,
+    values: &[Value],
+) -> error::Result<()> {
+    let encoded = Encoded::new(&typ, values)?;
+    let typid = crate::types::find_or_add_type(ncid, &typ)?;
+    checked_with_lock(|| unsafe {
+        nc_put_att(
+            ncid,
+            varid,
+            cname.as_ptr().cast(),
+            typid,
+            values.len(),
+            encoded.bytes.as_ptr().cast(),
+        )
+    })
+}
+
 // Boring implementations
 impl From<u8> for AttributeValue {
     fn from(x: u8) -> Self {
@@ -999,9 +1186,21 @@ impl TryFrom<AttributeValue> for f64 {
 
 impl TryFrom<AttributeValue> for String {
     type Error = error::Error;
+    /// Text stored as `NC_CHAR` or a single `NC_STRING`.
+    /// Trailing NUL bytes of `NC_CHAR` text are removed
     fn try_from(attr: AttributeValue) -> Result<Self, Self::Error> {
         match attr {
             AttributeValue::Str(s) => Ok(s),
+            AttributeValue::Strs(mut s) if s.len() == 1 => Ok(s.remove(0)),
+            AttributeValue::Chars(mut c) => {
+                while c.last() == Some(&0) {
+                    c.pop();
+                }
+                if c.contains(&0) {
+                    return Err("Text contains NUL bytes".into());
+                }
+                Ok(String::from_utf8(c)?)
+            }
             _ => Err("Conversion not supported".into()),
         }
     }
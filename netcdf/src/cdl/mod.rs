@@ -0,0 +1,605 @@
+//! Read and write files as CDL, the text representation used by `ncdump` and `ncgen`
+//!
+//! ```rust,no_run
+//! # fn main() -> Result<(), netcdf::Error> {
+//! use netcdf::cdl::{CdlOptions, Data};
+//!
+//! let file = netcdf::open("simple_xy.nc")?;
+//! let options = CdlOptions {
+//!     data: Data::Coordinates,
+//!     ..CdlOptions::default()
+//! };
+//! print!("{}", netcdf::cdl::to_string(&file, "simple_xy", &options)?);
+//! # Ok(()) }
+//! ```
+//!
+//! CDL is parsed into a [`Schema`], which can be written to a file:
+//! ```rust,no_run
+//! # fn main() -> Result<(), netcdf::Error> {
+//! let schema = netcdf::cdl::Schema::parse(
+//!     r#"
+//! netcdf simple {
+//! dimensions:
+//!     x = 3 ;
+//! variables:
+//!     float x(x) ;
+//!         x:units = "m" ;
+//! data:
+//!     x = 1, 2, 3 ;
+//! }"#,
+//! )?;
+//! let mut file = netcdf::create("simple.nc")?;
+//! schema.write(&mut file)?;
+//! # Ok(()) }
+//! ```
+
+mod parse;
+pub use parse::{DimensionDef, GroupDef, ParseError, Schema, VariableDef};
+
+use crate::attribute::{Attribute, AttributeValue};
+use crate::error;
+use crate::types::{EnumType, EnumTypeValues, FloatType, NcVariableType};
+use crate::value::Value;
+use crate::{File, Group, Variable};
+
+/// Which variables to write the data of
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Data {
+    /// Only the header (`ncdump -h`)
+    #[default]
+    Header,
+    /// Coordinate variables, which have the same name as one of their
+    /// dimensions (`ncdump -c`)
+    Coordinates,
+    /// All variables
+    All,
+    /// Variables given by name or full path (`ncdump -v`)
+    Variables(Vec<String>),
+}
+
+/// Options for writing CDL
+#[derive(Debug, Clone)]
+pub struct CdlOptions {
+    /// Which variables to write the data of
+    pub data: Data,
+    /// Significant digits of `float` values
+    pub float_precision: usize,
+    /// Significant digits of `double` values
+    pub double_precision: usize,
+}
+
+impl Default for CdlOptions {
+    fn default() -> Self {
+        Self {
+            data: Data::Header,
+            float_precision: 7,
+            double_precision: 15,
+        }
+    }
+}
+
+/// Write `file` as CDL, using `name` as the name of the dataset
+pub fn to_string(file: &File, name: &str, options: &CdlOptions) -> error::Result<String> {
+    let mut writer = Writer {
+        out: String::new(),
+        options,
+    };
+    writer.line("", &format!("netcdf {} {{", escape_name(name)));
+    writer.group(&file.root(), "")?;
+    writer.line("", "}");
+    Ok(writer.out)
+}
+
+/// Names with special characters are escaped using backslashes
+fn escape_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for (i, c) in name.chars().enumerate() {
+        let special = !(c.is_alphanumeric() || c == '_' || (i > 0 && matches!(c, '.' | '-' | '+')));
+        if special {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Push a character of a CDL string, escaping if necessary
+fn push_escaped(quoted: &mut String, c: char) {
+    match c {
+        '"' => quoted.push_str("\\\""),
+        '\\' => quoted.push_str("\\\\"),
+        '\n' => quoted.push_str("\\n"),
+        '\t' => quoted.push_str("\\t"),
+        '\r' => quoted.push_str("\\r"),
+        c if c.is_ascii_control() => quoted.push_str(&format!("\\{:03o}", u32::from(c))),
+        c => quoted.push(c),
+    }
+}
+
+/// Quote text as a CDL string
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        push_escaped(&mut quoted, c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quote `NC_CHAR` text which is not necessarily valid UTF-8
+fn quote_bytes(bytes: &[u8]) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return quote(text);
+    }
+    let mut quoted = String::from("\"");
+    for &b in bytes {
+        if b.is_ascii() {
+            push_escaped(&mut quoted, char::from(b));
+        } else {
+            quoted.push_str(&format!("\\{b:03o}"));
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Remove trailing zeros of a decimal fraction
+fn trim_zeros(number: &str) -> &str {
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    }
+}
+
+/// Format `x` with `digits` significant digits, like `%g` in C
+fn format_g(x: f64, digits: usize) -> String {
+    if x == 0.0 {
+        return if x.is_sign_negative() { "-0" } else { "0" }.to_owned();
+    }
+    let digits = digits.clamp(1, 17);
+    let scientific = format!("{:.*e}", digits - 1, x);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let digits = digits as i32;
+    if exponent < -4 || exponent >= digits {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{sign}{:02}", trim_zeros(mantissa), exponent.abs())
+    } else {
+        #[allow(clippy::cast_sign_loss)]
+        let decimals = (digits - 1 - exponent) as usize;
+        trim_zeros(&format!("{x:.decimals$}")).to_owned()
+    }
+}
+
+/// Name of the integer type of the members of an enum
+fn enum_basetype(enumtype: &EnumType) -> &'static str {
+    match enumtype.fieldvalues {
+        EnumTypeValues::U8(_) => "ubyte",
+        EnumTypeValues::I8(_) => "byte",
+        EnumTypeValues::U16(_) => "ushort",
+        EnumTypeValues::I16(_) => "short",
+        EnumTypeValues::U32(_) => "uint",
+        EnumTypeValues::I32(_) => "int",
+        EnumTypeValues::U64(_) => "uint64",
+        EnumTypeValues::I64(_) => "int64",
+    }
+}
+
+/// Values of the members of an enum
+fn enum_values(enumtype: &EnumType) -> Vec<String> {
+    fn strings<T: ToString>(xs: &[T]) -> Vec<String> {
+        xs.iter().map(ToString::to_string).collect()
+    }
+    match &enumtype.fieldvalues {
+        EnumTypeValues::U8(x) => strings(x),
+        EnumTypeValues::I8(x) => strings(x),
+        EnumTypeValues::U16(x) => strings(x),
+        EnumTypeValues::I16(x) => strings(x),
+        EnumTypeValues::U32(x) => strings(x),
+        EnumTypeValues::I32(x) => strings(x),
+        EnumTypeValues::U64(x) => strings(x),
+        EnumTypeValues::I64(x) => strings(x),
+    }
+}
+
+struct Writer<'o> {
+    out: String,
+    options: &'o CdlOptions,
+}
+
+impl Writer<'_> {
+    fn line(&mut self, indent: &str, text: &str) {
+        self.out.push_str(indent);
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    /// Floating point number in a data section
+    fn float(&self, x: f64, typ: FloatType) -> String {
+        let single = typ == FloatType::F32;
+        let suffix = if single { "f" } else { "" };
+        if x.is_nan() {
+            format!("NaN{suffix}")
+        } else if x.is_infinite() {
+            let sign = if x < 0.0 { "-" } else { "" };
+            format!("{sign}Infinity{suffix}")
+        } else if single {
+            format_g(x, self.options.float_precision)
+        } else {
+            format_g(x, self.options.double_precision)
+        }
+    }
+
+    /// Floating point number in an attribute, which must be
+    /// recognisable as `float` or `double`
+    fn float_literal(&self, x: f64, typ: FloatType) -> String {
+        let mut literal = self.float(x, typ);
+        if x.is_finite() {
+            if !literal.contains(['.', 'e']) {
+                literal.push('.');
+            }
+            if typ == FloatType::F32 {
+                literal.push('f');
+            }
+        }
+        literal
+    }
+
+    /// Single value of the type `typ`
+    fn value(&self, typ: &NcVariableType, value: &Value) -> String {
+        match (typ, value) {
+            (_, Value::Int(x)) => x.to_string(),
+            (_, Value::UInt(x)) => x.to_string(),
+            (NcVariableType::Float(float), Value::Float(x)) => self.float(*x, *float),
+            (_, Value::Float(x)) => self.float(*x, FloatType::F64),
+            (_, Value::Char(c)) => quote_bytes(&[*c]),
+            (_, Value::Str(s)) => quote(s),
+            (_, Value::Enum(member)) => escape_name(member),
+            (_, Value::Opaque(bytes)) => {
+                let hex = bytes.iter().map(|b| format!("{b:02X}")).collect::<String>();
+                format!("0X{hex}")
+            }
+            (NcVariableType::Compound(compound), Value::Compound(fields)) => {
+                let fields = compound
+                    .fields
+                    .iter()
+                    .zip(fields)
+                    .map(|(field, (_, value))| self.value(&field.basetype, value))
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", fields.join(", "))
+            }
+            (NcVariableType::Vlen(vlen), Value::Vlen(values)) => {
+                format!("{{{}}}", self.values(&vlen.basetype, values).join(", "))
+            }
+            (typ, Value::Array(values) | Value::Vlen(values)) => {
+                format!("{{{}}}", self.values(typ, values).join(", "))
+            }
+            (typ, Value::Compound(fields)) => {
+                let fields = fields
+                    .iter()
+                    .map(|(_, value)| self.value(typ, value))
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", fields.join(", "))
+            }
+        }
+    }
+
+    fn values(&self, typ: &NcVariableType, values: &[Value]) -> Vec<String> {
+        values.iter().map(|value| self.value(typ, value)).collect()
+    }
+
+    /// Type prefix and values of an attribute
+    fn attribute(&self, value: &AttributeValue) -> (Option<String>, Vec<String>) {
+        fn suffixed<T: ToString>(xs: &[T], suffix: &str) -> Vec<String> {
+            xs.iter()
+                .map(|x| format!("{}{suffix}", x.to_string()))
+                .collect()
+        }
+        let floats = |xs: &mut dyn Iterator<Item = f64>, typ| {
+            xs.map(|x| self.float_literal(x, typ)).collect::<Vec<_>>()
+        };
+        let values = match value {
+            AttributeValue::Uchar(x) => suffixed(&[*x], "UB"),
+            AttributeValue::Uchars(x) => suffixed(x, "UB"),
+            AttributeValue::Schar(x) => suffixed(&[*x], "b"),
+            AttributeValue::Schars(x) => suffixed(x, "b"),
+            AttributeValue::Ushort(x) => suffixed(&[*x], "US"),
+            AttributeValue::Ushorts(x) => suffixed(x, "US"),
+            AttributeValue::Short(x) => suffixed(&[*x], "s"),
+            AttributeValue::Shorts(x) => suffixed(x, "s"),
+            AttributeValue::Uint(x) => suffixed(&[*x], "U"),
+            AttributeValue::Uints(x) => suffixed(x, "U"),
+            AttributeValue::Int(x) => suffixed(&[*x], ""),
+            AttributeValue::Ints(x) => suffixed(x, ""),
+            AttributeValue::Ulonglong(x) => suffixed(&[*x], "ULL"),
+            AttributeValue::Ulonglongs(x) => suffixed(x, "ULL"),
+            AttributeValue::Longlong(x) => suffixed(&[*x], "LL"),
+            AttributeValue::Longlongs(x) => suffixed(x, "LL"),
+            AttributeValue::Float(x) => floats(&mut std::iter::once(f64::from(*x)), FloatType::F32),
+            AttributeValue::Floats(x) => {
+                floats(&mut x.iter().map(|&x| f64::from(x)), FloatType::F32)
+            }
+            AttributeValue::Double(x) => floats(&mut std::iter::once(*x), FloatType::F64),
+            AttributeValue::Doubles(x) => floats(&mut x.iter().copied(), FloatType::F64),
+            AttributeValue::Str(x) => vec![quote(x)],
+            AttributeValue::Chars(x) => vec![quote_bytes(x)],
+            AttributeValue::Strs(x) => {
+                return (
+                    Some("string".to_owned()),
+                    x.iter().map(|x| quote(x)).collect(),
+                )
+            }
+            AttributeValue::Compound(typ, values) => {
+                let typ = NcVariableType::Compound(typ.clone());
+                return (Some(typ.name().to_owned()), self.values(&typ, values));
+            }
+            AttributeValue::Enum(typ, values) => {
+                let typ = NcVariableType::Enum(typ.clone());
+                return (Some(typ.name().to_owned()), self.values(&typ, values));
+            }
+            AttributeValue::Opaque(typ, values) => {
+                let typ = NcVariableType::Opaque(typ.clone());
+                return (Some(typ.name().to_owned()), self.values(&typ, values));
+            }
+            AttributeValue::Vlen(typ, values) => {
+                let typ = NcVariableType::Vlen(typ.clone());
+                return (Some(typ.name().to_owned()), self.values(&typ, values));
+            }
+        };
+        (None, values)
+    }
+
+    fn attributes<'a>(
+        &mut self,
+        indent: &str,
+        owner: &str,
+        attributes: impl Iterator<Item = error::Result<Attribute<'a>>>,
+    ) -> error::Result<()> {
+        for attr in attributes {
+            let attr = attr?;
+            let (typ, values) = self.attribute(&attr.value()?);
+            let typ = typ.map(|typ| format!("{} ", escape_name(&typ)));
+            self.line(
+                indent,
+                &format!(
+                    "\t\t{}{owner}:{} = {} ;",
+                    typ.unwrap_or_default(),
+                    escape_name(attr.try_name()?),
+                    values.join(", ")
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    fn typedef(&mut self, indent: &str, typ: &NcVariableType) {
+        match typ {
+            NcVariableType::Enum(enumtype) => {
+                let members = enumtype
+                    .fieldnames
+                    .iter()
+                    .zip(enum_values(enumtype))
+                    .map(|(name, value)| format!("{} = {value}", escape_name(name)))
+                    .collect::<Vec<_>>();
+                self.line(
+                    indent,
+                    &format!(
+                        "  {} enum {} {{{}}} ;",
+                        enum_basetype(enumtype),
+                        escape_name(&enumtype.name),
+                        members.join(", ")
+                    ),
+                );
+            }
+            NcVariableType::Opaque(opaque) => self.line(
+                indent,
+                &format!("  opaque({}) {} ;", opaque.size, escape_name(&opaque.name)),
+            ),
+            NcVariableType::Vlen(vlen) => self.line(
+                indent,
+                &format!(
+                    "  {}(*) {} ;",
+                    escape_name(vlen.basetype.name()),
+                    escape_name(&vlen.name)
+                ),
+            ),
+            NcVariableType::Compound(compound) => {
+                let name = escape_name(&compound.name);
+                self.line(indent, &format!("  compound {name} {{"));
+                for field in &compound.fields {
+                    let dims = field.arraydims.as_ref().map_or(String::new(), |dims| {
+                        let dims = dims.iter().map(ToString::to_string).collect::<Vec<_>>();
+                        format!("({})", dims.join(", "))
+                    });
+                    self.line(
+                        indent,
+                        &format!(
+                            "    {} {}{dims} ;",
+                            escape_name(field.basetype.name()),
+                            escape_name(&field.name)
+                        ),
+                    );
+                }
+                self.line(indent, &format!("  }}; // {name}"));
+            }
+            _ => {}
+        }
+    }
+
+    /// Should the data of the variable be written
+    fn wants_data(&self, var: &Variable, path: &str) -> error::Result<bool> {
+        let name = var.try_name()?;
+        Ok(match &self.options.data {
+            Data::Header => false,
+            Data::All => true,
+            Data::Coordinates => var
+                .dimensions()
+                .iter()
+                .any(|dim| dim.try_name().is_ok_and(|dim| dim == name)),
+            Data::Variables(names) => names.iter().any(|n| {
+                let n = n.trim_start_matches('/');
+                n == name || n == path.trim_start_matches('/')
+            }),
+        })
+    }
+
+    fn data(&mut self, indent: &str, var: &Variable) -> error::Result<()> {
+        let typ = var.try_vartype()?;
+        let values = var.get_dynamic_values(..)?;
+        let items = if typ == NcVariableType::Char {
+            // Text is given per row of the last dimension
+            let bytes = values
+                .iter()
+                .map(|value| match value {
+                    Value::Char(c) => *c,
+                    _ => 0,
+                })
+                .collect::<Vec<_>>();
+            let row = var.dimensions().last().map_or(bytes.len(), |dim| dim.len());
+            bytes
+                .chunks(row.max(1))
+                .map(|row| {
+                    let len = row.iter().rposition(|&c| c != 0).map_or(0, |i| i + 1);
+                    quote_bytes(&row[..len])
+                })
+                .collect()
+        } else {
+            self.values(&typ, &values)
+        };
+
+        let mut line = format!(" {} = ", escape_name(&var.try_name()?));
+        let mut first = true;
+        for item in items {
+            if !first {
+                line.push(',');
+                if line.len() + item.len() + 3 > 80 {
+                    self.line(indent, &line);
+                    line = String::from("   ");
+                }
+                line.push(' ');
+            }
+            first = false;
+            line.push_str(&item);
+        }
+        line.push_str(" ;");
+        self.line(indent, &line);
+        self.line("", "");
+        Ok(())
+    }
+
+    fn group(&mut self, group: &Group, indent: &str) -> error::Result<()> {
+        let types = group.try_types()?.collect::<error::Result<Vec<_>>>()?;
+        if !types.is_empty() {
+            self.line(indent, "types:");
+            for typ in &types {
+                self.typedef(indent, typ);
+            }
+        }
+
+        let dims = group.try_dimensions()?.collect::<error::Result<Vec<_>>>()?;
+        if !dims.is_empty() {
+            self.line(indent, "dimensions:");
+            for dim in &dims {
+                let name = escape_name(&dim.try_name()?);
+                let len = dim.try_len()?;
+                if dim.is_unlimited() {
+                    self.line(
+                        indent,
+                        &format!("\t{name} = UNLIMITED ; // ({len} currently)"),
+                    );
+                } else {
+                    self.line(indent, &format!("\t{name} = {len} ;"));
+                }
+            }
+        }
+
+        let vars = group.try_variables()?.collect::<error::Result<Vec<_>>>()?;
+        if !vars.is_empty() {
+            self.line(indent, "variables:");
+            for var in &vars {
+                let name = escape_name(&var.try_name()?);
+                let dims = var
+                    .dimensions()
+                    .iter()
+                    .map(|dim| dim.try_name().map(|name| escape_name(&name)))
+                    .collect::<error::Result<Vec<_>>>()?;
+                let dims = if dims.is_empty() {
+                    String::new()
+                } else {
+                    format!("({})", dims.join(", "))
+                };
+                let typ = var.try_vartype()?;
+                self.line(
+                    indent,
+                    &format!("\t{} {name}{dims} ;", escape_name(typ.name())),
+                );
+                self.attributes(indent, &name, var.try_attributes()?)?;
+            }
+        }
+
+        let mut attributes = group.try_attributes()?.peekable();
+        if attributes.peek().is_some() {
+            self.line("", "");
+            if group.parent()?.is_none() {
+                self.line(indent, "// global attributes:");
+            } else {
+                self.line(indent, "// group attributes:");
+            }
+            self.attributes(indent, "", attributes)?;
+        }
+
+        let path = group.full_path()?;
+        let mut data = vec![];
+        for var in vars {
+            let name = var.try_name()?;
+            let varpath = format!("{}/{name}", path.trim_end_matches('/'));
+            if var.len() > 0 && self.wants_data(&var, &varpath)? {
+                data.push(var);
+            }
+        }
+        if !data.is_empty() {
+            self.line(indent, "data:");
+            self.line("", "");
+            for var in &data {
+                self.data(indent, var)?;
+            }
+        }
+
+        for subgroup in group.try_groups()? {
+            let subgroup = subgroup?;
+            let name = escape_name(&subgroup.try_name()?);
+            self.line("", "");
+            self.line(indent, &format!("group: {name} {{"));
+            let inner = format!("{indent}  ");
+            self.group(&subgroup, &inner)?;
+            self.line(&inner, &format!("}} // group {name}"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_floats() {
+        assert_eq!(format_g(1.0, 7), "1");
+        assert_eq!(format_g(0.1, 7), "0.1");
+        assert_eq!(format_g(1.5e-5, 7), "1.5e-05");
+        assert_eq!(format_g(123_456_789.0, 7), "1.234568e+08");
+        assert_eq!(format_g(-2.25, 15), "-2.25");
+        assert_eq!(format_g(f64::from(0.1_f32), 7), "0.1");
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(escape_name("air temperature"), "air\\ temperature");
+        assert_eq!(escape_name("t2m.max"), "t2m.max");
+        assert_eq!(quote("a \"b\"\n"), "\"a \\\"b\\\"\\n\"");
+        assert_eq!(quote_bytes(b"a\0"), "\"a\\000\"");
+        assert_eq!(quote_bytes(b"\xff"), "\"\\377\"");
+    }
+}
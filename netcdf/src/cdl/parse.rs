@@ -0,0 +1,1497 @@
+//! Parse CDL into a [`Schema`]
+
+use crate::attribute::{text_value, AttributeValue};
+use crate::error;
+use crate::types::{
+    CompoundType, CompoundTypeField, EnumType, EnumTypeValues, FloatType, IntType, NcVariableType,
+    OpaqueType, VlenType,
+};
+use crate::value::Value;
+use crate::{Extents, FileMut, GroupMut};
+
+/// Error in CDL text, with the position where it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the error, starting at 1
+    pub line: usize,
+    /// Column of the error, starting at 1
+    pub column: usize,
+    /// Description of the error
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for error::Error {
+    fn from(e: ParseError) -> Self {
+        Self::Str(e.to_string())
+    }
+}
+
+/// A dataset described by CDL
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    /// Name of the dataset
+    pub name: String,
+    /// Root group
+    pub root: GroupDef,
+}
+
+/// A group described by CDL
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupDef {
+    /// Name of the group
+    pub name: String,
+    /// User defined types, in order of definition
+    pub types: Vec<NcVariableType>,
+    /// Dimensions
+    pub dimensions: Vec<DimensionDef>,
+    /// Variables
+    pub variables: Vec<VariableDef>,
+    /// Attributes of the group
+    pub attributes: Vec<(String, AttributeValue)>,
+    /// Subgroups
+    pub groups: Vec<GroupDef>,
+}
+
+/// A dimension described by CDL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DimensionDef {
+    /// Name of the dimension
+    pub name: String,
+    /// Length of the dimension, `None` if unlimited
+    pub len: Option<usize>,
+}
+
+/// A variable described by CDL
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableDef {
+    /// Name of the variable
+    pub name: String,
+    /// Type of the variable
+    pub vartype: NcVariableType,
+    /// Names of the dimensions
+    pub dimensions: Vec<String>,
+    /// Attributes of the variable
+    pub attributes: Vec<(String, AttributeValue)>,
+    /// Values from the `data:` section
+    pub data: Option<Vec<Value>>,
+}
+
+impl Schema {
+    /// Parse CDL text
+    pub fn parse(text: &str) -> std::result::Result<Self, ParseError> {
+        let tokens = lex(text)?;
+        Parser {
+            tokens,
+            pos: 0,
+            stack: vec![],
+        }
+        .schema()
+    }
+
+    /// Write the groups, types, dimensions, variables, attributes and
+    /// data to `file`
+    pub fn write(&self, file: &mut FileMut) -> error::Result<()> {
+        write_group(&mut file.root_mut(), &self.root)
+    }
+}
+
+fn write_group(group: &mut GroupMut, def: &GroupDef) -> error::Result<()> {
+    for typ in &def.types {
+        group.add_type_from_descriptor(typ.clone())?;
+    }
+    for dim in &def.dimensions {
+        match dim.len {
+            Some(len) => group.add_dimension(&dim.name, len)?,
+            None => group.add_unlimited_dimension(&dim.name)?,
+        };
+    }
+    for var in &def.variables {
+        let dims = var
+            .dimensions
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let mut variable = group.add_variable_with_type(&var.name, &dims, &var.vartype)?;
+        for (name, value) in &var.attributes {
+            variable.put_attribute(name, value.clone())?;
+        }
+    }
+    for (name, value) in &def.attributes {
+        group.add_attribute(name, value.clone())?;
+    }
+    for var in &def.variables {
+        let Some(data) = &var.data else {
+            continue;
+        };
+        if data.is_empty() {
+            continue;
+        }
+        let mut variable = group
+            .variable_mut(&var.name)
+            .ok_or_else(|| error::Error::NotFound(format!("variable {}", var.name)))?;
+        // Unlimited dimensions are given the number of records in the data
+        let fixed = variable
+            .dimensions()
+            .iter()
+            .filter(|dim| !dim.is_unlimited())
+            .map(crate::Dimension::len)
+            .product::<usize>();
+        let mut first_unlimited = true;
+        let count = variable
+            .dimensions()
+            .iter()
+            .map(|dim| {
+                if !dim.is_unlimited() {
+                    dim.len()
+                } else if std::mem::take(&mut first_unlimited) {
+                    data.len().div_ceil(fixed.max(1))
+                } else {
+                    1
+                }
+            })
+            .collect::<Vec<_>>();
+        let len = count.iter().product::<usize>();
+        if data.len() > len {
+            return Err(format!(
+                "variable {}: {} values given, but it holds {len}",
+                var.name,
+                data.len()
+            )
+            .into());
+        }
+        let mut data = data.clone();
+        if data.len() < len {
+            let fill = fill_value(var).ok_or_else(|| {
+                format!(
+                    "variable {}: {} values given, but it holds {len}",
+                    var.name,
+                    data.len()
+                )
+            })?;
+            data.resize(len, fill);
+        }
+        let start = vec![0; count.len()];
+        let extents = Extents::try_from((start, count))?;
+        variable.put_dynamic_values(&data, extents)?;
+    }
+    for subgroup in &def.groups {
+        let mut child = group.add_group(&subgroup.name)?;
+        write_group(&mut child, subgroup)?;
+    }
+    Ok(())
+}
+
+/// Value of `_` in the data of the variable
+fn fill_value(var: &VariableDef) -> Option<Value> {
+    let fill = var
+        .attributes
+        .iter()
+        .find(|(name, _)| name == "_FillValue")
+        .map(|(_, value)| value);
+    if let Some(fill) = fill {
+        return match fill {
+            AttributeValue::Uchar(x) => Some(Value::UInt(u64::from(*x))),
+            AttributeValue::Schar(x) => Some(Value::Int(i64::from(*x))),
+            AttributeValue::Ushort(x) => Some(Value::UInt(u64::from(*x))),
+            AttributeValue::Short(x) => Some(Value::Int(i64::from(*x))),
+            AttributeValue::Uint(x) => Some(Value::UInt(u64::from(*x))),
+            AttributeValue::Int(x) => Some(Value::Int(i64::from(*x))),
+            AttributeValue::Ulonglong(x) => Some(Value::UInt(*x)),
+            AttributeValue::Longlong(x) => Some(Value::Int(*x)),
+            AttributeValue::Float(x) => Some(Value::Float(f64::from(*x))),
+            AttributeValue::Double(x) => Some(Value::Float(*x)),
+            AttributeValue::Str(x) => x.bytes().next().map(Value::Char),
+            AttributeValue::Chars(x) => x.first().copied().map(Value::Char),
+            AttributeValue::Strs(x) => x.first().cloned().map(Value::Str),
+            AttributeValue::Compound(_, x)
+            | AttributeValue::Enum(_, x)
+            | AttributeValue::Opaque(_, x)
+            | AttributeValue::Vlen(_, x) => x.first().cloned(),
+            _ => None,
+        };
+    }
+    use netcdf_sys::*;
+    Some(match &var.vartype {
+        NcVariableType::Int(IntType::U8) => Value::UInt(NC_FILL_UBYTE.into()),
+        NcVariableType::Int(IntType::I8) => Value::Int(NC_FILL_BYTE.into()),
+        NcVariableType::Int(IntType::U16) => Value::UInt(NC_FILL_USHORT.into()),
+        NcVariableType::Int(IntType::I16) => Value::Int(NC_FILL_SHORT.into()),
+        NcVariableType::Int(IntType::U32) => Value::UInt(NC_FILL_UINT.into()),
+        NcVariableType::Int(IntType::I32) => Value::Int(NC_FILL_INT.into()),
+        NcVariableType::Int(IntType::U64) => Value::UInt(NC_FILL_UINT64),
+        NcVariableType::Int(IntType::I64) => Value::Int(NC_FILL_INT64),
+        NcVariableType::Float(FloatType::F32) => Value::Float(NC_FILL_FLOAT.into()),
+        NcVariableType::Float(FloatType::F64) => Value::Float(NC_FILL_DOUBLE),
+        NcVariableType::Char => Value::Char(NC_FILL_CHAR),
+        NcVariableType::String => Value::Str(String::new()),
+        NcVariableType::Vlen(_) => Value::Vlen(vec![]),
+        NcVariableType::Compound(_) | NcVariableType::Enum(_) | NcVariableType::Opaque(_) => {
+            return None
+        }
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Str(Vec<u8>),
+    Punct(char),
+    Eof,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Ident(x) | Self::Number(x) => write!(f, "`{x}`"),
+            Self::Str(_) => write!(f, "string"),
+            Self::Punct(c) => write!(f, "`{c}`"),
+            Self::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Lexed {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+/// Characters with line and column numbers
+struct Chars<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl Chars<'_> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '\\' || !c.is_ascii()
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '@' | '+' | '-' | '\\') || !c.is_ascii()
+}
+
+fn lex(text: &str) -> Result<Vec<Lexed>> {
+    let mut chars = Chars {
+        chars: text.chars().peekable(),
+        line: 1,
+        column: 1,
+    };
+    let mut tokens = vec![];
+    loop {
+        while chars.peek().is_some_and(char::is_whitespace) {
+            chars.next();
+        }
+        let (line, column) = (chars.line, chars.column);
+        let Some(c) = chars.peek() else {
+            tokens.push(Lexed {
+                token: Token::Eof,
+                line,
+                column,
+            });
+            return Ok(tokens);
+        };
+        let token = match c {
+            '/' => {
+                chars.next();
+                if chars.next() != Some('/') {
+                    return Err(chars.error("expected a comment (`//`)"));
+                }
+                while chars.peek().is_some_and(|c| c != '\n') {
+                    chars.next();
+                }
+                continue;
+            }
+            '"' => {
+                chars.next();
+                Token::Str(lex_string(&mut chars)?)
+            }
+            '{' | '}' | '(' | ')' | ',' | ';' | '=' | ':' | '*' => {
+                chars.next();
+                Token::Punct(c)
+            }
+            c if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => {
+                let mut number = String::new();
+                while let Some(c) = chars.peek() {
+                    let exponent_sign = matches!(c, '-' | '+')
+                        && (number.is_empty()
+                            || (number.ends_with(['e', 'E'])
+                                && !number.to_ascii_lowercase().starts_with("0x")));
+                    if c.is_ascii_alphanumeric() || c == '.' || exponent_sign {
+                        number.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                Token::Number(number)
+            }
+            c if is_name_start(c) => {
+                let mut name = String::new();
+                while let Some(c) = chars.peek() {
+                    if !is_name_char(c) {
+                        break;
+                    }
+                    chars.next();
+                    if c == '\\' {
+                        match chars.next() {
+                            Some(c) => name.push(c),
+                            None => return Err(chars.error("unexpected end of input")),
+                        }
+                    } else {
+                        name.push(c);
+                    }
+                }
+                Token::Ident(name)
+            }
+            c => return Err(chars.error(format!("unexpected character `{c}`"))),
+        };
+        tokens.push(Lexed {
+            token,
+            line,
+            column,
+        });
+    }
+}
+
+/// Read a string after the opening quote
+fn lex_string(chars: &mut Chars) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    loop {
+        let Some(c) = chars.next() else {
+            return Err(chars.error("unterminated string"));
+        };
+        let c = match c {
+            '"' => return Ok(bytes),
+            '\\' => match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('r') => '\r',
+                Some('a') => '\x07',
+                Some('b') => '\x08',
+                Some('f') => '\x0c',
+                Some('v') => '\x0b',
+                Some('x') => {
+                    let mut value = 0_u8;
+                    for _ in 0..2 {
+                        match chars.peek().and_then(|c| c.to_digit(16)) {
+                            Some(d) => {
+                                chars.next();
+                                value = value * 16 + u8::try_from(d).unwrap();
+                            }
+                            None => break,
+                        }
+                    }
+                    bytes.push(value);
+                    continue;
+                }
+                Some(c @ '0'..='7') => {
+                    let mut value = c.to_digit(8).unwrap();
+                    for _ in 0..2 {
+                        match chars.peek().and_then(|c| c.to_digit(8)) {
+                            Some(d) => {
+                                chars.next();
+                                value = value * 8 + d;
+                            }
+                            None => break,
+                        }
+                    }
+                    bytes.push(
+                        u8::try_from(value).map_err(|_| chars.error("invalid octal escape"))?,
+                    );
+                    continue;
+                }
+                Some(c) => c,
+                None => return Err(chars.error("unterminated string")),
+            },
+            c => c,
+        };
+        let mut buf = [0; 4];
+        bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+    }
+}
+
+/// Name of a basic type
+fn basic_type(name: &str) -> Option<NcVariableType> {
+    Some(match name {
+        "char" => NcVariableType::Char,
+        "byte" => NcVariableType::Int(IntType::I8),
+        "ubyte" => NcVariableType::Int(IntType::U8),
+        "short" => NcVariableType::Int(IntType::I16),
+        "ushort" => NcVariableType::Int(IntType::U16),
+        "int" | "long" | "integer" => NcVariableType::Int(IntType::I32),
+        "uint" => NcVariableType::Int(IntType::U32),
+        "int64" => NcVariableType::Int(IntType::I64),
+        "uint64" => NcVariableType::Int(IntType::U64),
+        "float" | "real" => NcVariableType::Float(FloatType::F32),
+        "double" => NcVariableType::Float(FloatType::F64),
+        "string" => NcVariableType::String,
+        _ => return None,
+    })
+}
+
+fn is_section(name: &str) -> bool {
+    matches!(
+        name,
+        "types" | "dimensions" | "variables" | "data" | "group"
+    )
+}
+
+/// Alignment of a type in memory, used for the layout of compounds
+fn alignment(typ: &NcVariableType) -> usize {
+    match typ {
+        NcVariableType::Compound(compound) => compound
+            .fields
+            .iter()
+            .map(|field| alignment(&field.basetype))
+            .max()
+            .unwrap_or(1),
+        NcVariableType::Opaque(_) | NcVariableType::Char => 1,
+        NcVariableType::Vlen(_) => std::mem::align_of::<netcdf_sys::nc_vlen_t>(),
+        NcVariableType::String => std::mem::align_of::<*const std::ffi::c_char>(),
+        typ => typ.size(),
+    }
+}
+
+/// A number given in CDL
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    Int(i128, IntType),
+    Float(f64, FloatType),
+}
+
+impl Number {
+    fn as_f64(self) -> f64 {
+        match self {
+            #[allow(clippy::cast_precision_loss)]
+            Self::Int(x, _) => x as f64,
+            Self::Float(x, _) => x,
+        }
+    }
+}
+
+/// Parse a number, where the suffix gives the type
+fn parse_number(text: &str) -> Option<Number> {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let special = match unsigned {
+        "NaN" | "nan" => Some((f64::NAN, FloatType::F64)),
+        "NaNf" | "nanf" => Some((f64::NAN, FloatType::F32)),
+        "Infinity" | "inf" => Some((f64::INFINITY, FloatType::F64)),
+        "Infinityf" | "inff" => Some((f64::INFINITY, FloatType::F32)),
+        _ => None,
+    };
+    if let Some((x, typ)) = special {
+        return Some(Number::Float(sign * x, typ));
+    }
+    if let Some(hex) = unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"))
+    {
+        let x = i128::from_str_radix(hex, 16).ok()?;
+        return Some(Number::Int(if sign < 0.0 { -x } else { x }, IntType::I32));
+    }
+    let digits = text.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &text[digits.len()..];
+    let is_float = digits.contains(['.', 'e', 'E']);
+    // An exponent without digits belongs to the suffix
+    let int_type = match suffix.to_ascii_lowercase().as_str() {
+        "" if is_float => {
+            return digits
+                .parse()
+                .ok()
+                .map(|x| Number::Float(x, FloatType::F64))
+        }
+        "f" => {
+            return digits
+                .parse()
+                .ok()
+                .map(|x| Number::Float(x, FloatType::F32))
+        }
+        "d" => {
+            return digits
+                .parse()
+                .ok()
+                .map(|x| Number::Float(x, FloatType::F64))
+        }
+        "" => IntType::I32,
+        "b" => IntType::I8,
+        "ub" => IntType::U8,
+        "s" => IntType::I16,
+        "us" => IntType::U16,
+        "u" => IntType::U32,
+        "l" | "ll" => IntType::I64,
+        "ul" | "ull" => IntType::U64,
+        _ => return None,
+    };
+    if is_float {
+        return None;
+    }
+    digits.parse().ok().map(|x| Number::Int(x, int_type))
+}
+
+/// Convert an integer to a value of `typ`, checking the range
+fn int_value(x: i128, typ: IntType) -> Option<Value> {
+    let fits = match typ {
+        IntType::U8 => u8::try_from(x).is_ok(),
+        IntType::I8 => i8::try_from(x).is_ok(),
+        IntType::U16 => u16::try_from(x).is_ok(),
+        IntType::I16 => i16::try_from(x).is_ok(),
+        IntType::U32 => u32::try_from(x).is_ok(),
+        IntType::I32 => i32::try_from(x).is_ok(),
+        IntType::U64 => u64::try_from(x).is_ok(),
+        IntType::I64 => i64::try_from(x).is_ok(),
+    };
+    if !fits {
+        return None;
+    }
+    match typ {
+        IntType::U8 | IntType::U16 | IntType::U32 | IntType::U64 => {
+            u64::try_from(x).ok().map(Value::UInt)
+        }
+        _ => i64::try_from(x).ok().map(Value::Int),
+    }
+}
+
+/// Attribute of basic numeric type from values
+fn numeric_attribute(typ: &NcVariableType, values: &[Value]) -> Option<AttributeValue> {
+    macro_rules! collect {
+        ($ty: ty, $one: ident, $many: ident) => {{
+            let mut xs = values
+                .iter()
+                .map(|value| match value {
+                    Value::Int(x) => <$ty>::try_from(*x).ok(),
+                    Value::UInt(x) => <$ty>::try_from(*x).ok(),
+                    _ => None,
+                })
+                .collect::<Option<Vec<$ty>>>()?;
+            if xs.len() == 1 {
+                AttributeValue::$one(xs.remove(0))
+            } else {
+                AttributeValue::$many(xs)
+            }
+        }};
+    }
+    let floats = values
+        .iter()
+        .map(|value| match value {
+            #[allow(clippy::cast_precision_loss)]
+            Value::Int(x) => Some(*x as f64),
+            #[allow(clippy::cast_precision_loss)]
+            Value::UInt(x) => Some(*x as f64),
+            Value::Float(x) => Some(*x),
+            _ => None,
+        })
+        .collect::<Option<Vec<f64>>>();
+    Some(match typ {
+        NcVariableType::Int(IntType::U8) => collect!(u8, Uchar, Uchars),
+        NcVariableType::Int(IntType::I8) => collect!(i8, Schar, Schars),
+        NcVariableType::Int(IntType::U16) => collect!(u16, Ushort, Ushorts),
+        NcVariableType::Int(IntType::I16) => collect!(i16, Short, Shorts),
+        NcVariableType::Int(IntType::U32) => collect!(u32, Uint, Uints),
+        NcVariableType::Int(IntType::I32) => collect!(i32, Int, Ints),
+        NcVariableType::Int(IntType::U64) => collect!(u64, Ulonglong, Ulonglongs),
+        NcVariableType::Int(IntType::I64) => collect!(i64, Longlong, Longlongs),
+        NcVariableType::Float(FloatType::F32) => {
+            #[allow(clippy::cast_possible_truncation)]
+            let mut xs = floats?.into_iter().map(|x| x as f32).collect::<Vec<_>>();
+            if xs.len() == 1 {
+                AttributeValue::Float(xs.remove(0))
+            } else {
+                AttributeValue::Floats(xs)
+            }
+        }
+        NcVariableType::Float(FloatType::F64) => {
+            let mut xs = floats?;
+            if xs.len() == 1 {
+                AttributeValue::Double(xs.remove(0))
+            } else {
+                AttributeValue::Doubles(xs)
+            }
+        }
+        _ => return None,
+    })
+}
+
+struct Parser {
+    tokens: Vec<Lexed>,
+    pos: usize,
+    /// Groups being parsed, the innermost last
+    stack: Vec<GroupDef>,
+}
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        self.peek_at(0)
+    }
+    fn peek_at(&self, n: usize) -> &Token {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.pos + n).min(last)].token
+    }
+    fn next(&mut self) -> Lexed {
+        let lexed = self.tokens[self.pos].clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        lexed
+    }
+    /// Error at the current token
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        let lexed = &self.tokens[self.pos];
+        ParseError {
+            line: lexed.line,
+            column: lexed.column,
+            message: message.into(),
+        }
+    }
+    fn unexpected(&self, expected: &str) -> ParseError {
+        self.error(format!("expected {expected}, found {}", self.peek()))
+    }
+    fn is_punct(&self, c: char) -> bool {
+        *self.peek() == Token::Punct(c)
+    }
+    fn eat_punct(&mut self, c: char) -> bool {
+        let found = self.is_punct(c);
+        if found {
+            self.next();
+        }
+        found
+    }
+    fn expect_punct(&mut self, c: char) -> Result<()> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{c}`")))
+        }
+    }
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Token::Ident(x) if x == name)
+    }
+    fn expect_ident(&mut self, what: &str) -> Result<String> {
+        match self.peek() {
+            Token::Ident(x) => {
+                let x = x.clone();
+                self.next();
+                Ok(x)
+            }
+            _ => Err(self.unexpected(what)),
+        }
+    }
+    fn expect_usize(&mut self) -> Result<usize> {
+        match self.peek() {
+            Token::Number(x) => {
+                let len = x.parse().map_err(|_| self.unexpected("a length"))?;
+                self.next();
+                Ok(len)
+            }
+            _ => Err(self.unexpected("a length")),
+        }
+    }
+
+    /// Is the next token a section header such as `variables:`
+    ///
+    /// A name directly followed by a colon is an attribute, e.g. `data:units`
+    fn at_section(&self) -> bool {
+        let (Token::Ident(name), Token::Punct(':')) = (self.peek(), self.peek_at(1)) else {
+            return false;
+        };
+        if !is_section(name) {
+            return false;
+        }
+        let colon = &self.tokens[(self.pos + 1).min(self.tokens.len() - 1)];
+        let after = &self.tokens[(self.pos + 2).min(self.tokens.len() - 1)];
+        !(after.line == colon.line && after.column == colon.column + 1)
+    }
+
+    fn group(&mut self) -> &mut GroupDef {
+        self.stack.last_mut().unwrap()
+    }
+
+    fn find_type(&self, name: &str) -> Option<NcVariableType> {
+        basic_type(name).or_else(|| {
+            self.stack
+                .iter()
+                .rev()
+                .flat_map(|group| group.types.iter().rev())
+                .find(|typ| typ.name() == name)
+                .cloned()
+        })
+    }
+
+    fn find_dimension(&self, name: &str) -> Option<&DimensionDef> {
+        self.stack
+            .iter()
+            .rev()
+            .flat_map(|group| group.dimensions.iter())
+            .find(|dim| dim.name == name)
+    }
+
+    fn type_ref(&mut self) -> Result<NcVariableType> {
+        let name = self.expect_ident("a type")?;
+        self.find_type(&name).ok_or_else(|| {
+            self.pos -= 1;
+            let e = self.error(format!("unknown type `{name}`"));
+            self.pos += 1;
+            e
+        })
+    }
+
+    fn schema(mut self) -> Result<Schema> {
+        if !self.is_ident("netcdf") {
+            return Err(self.unexpected("`netcdf`"));
+        }
+        self.next();
+        let name = match self.peek().clone() {
+            Token::Ident(x) | Token::Number(x) => {
+                self.next();
+                x
+            }
+            _ => return Err(self.unexpected("the name of the dataset")),
+        };
+        self.expect_punct('{')?;
+        self.stack.push(GroupDef::default());
+        self.group_body()?;
+        self.expect_punct('}')?;
+        if *self.peek() != Token::Eof {
+            return Err(self.unexpected("end of input"));
+        }
+        let root = self.stack.pop().unwrap();
+        Ok(Schema { name, root })
+    }
+
+    fn group_body(&mut self) -> Result<()> {
+        loop {
+            if self.is_punct('}') {
+                return Ok(());
+            }
+            if !self.at_section() {
+                return Err(self.unexpected("a section such as `variables:`"));
+            }
+            let section = self.expect_ident("a section")?;
+            self.expect_punct(':')?;
+            match section.as_str() {
+                "types" => self.types()?,
+                "dimensions" => self.dimensions()?,
+                "variables" => self.variables()?,
+                "data" => self.data()?,
+                "group" => self.subgroup()?,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    fn section_done(&self) -> bool {
+        self.is_punct('}') || self.at_section() || *self.peek() == Token::Eof
+    }
+
+    fn types(&mut self) -> Result<()> {
+        while !self.section_done() {
+            let typ = if self.is_ident("compound") {
+                self.next();
+                self.compound()?
+            } else if self.is_ident("opaque") {
+                self.next();
+                self.expect_punct('(')?;
+                let size = self.expect_usize()?;
+                self.expect_punct(')')?;
+                let name = self.expect_ident("the name of the type")?;
+                self.expect_punct(';')?;
+                NcVariableType::Opaque(OpaqueType { name, size })
+            } else {
+                let basetype = self.type_ref()?;
+                if self.is_ident("enum") {
+                    self.next();
+                    self.enumeration(&basetype)?
+                } else {
+                    self.expect_punct('(')?;
+                    self.expect_punct('*')?;
+                    self.expect_punct(')')?;
+                    let name = self.expect_ident("the name of the type")?;
+                    self.expect_punct(';')?;
+                    NcVariableType::Vlen(VlenType {
+                        name,
+                        basetype: Box::new(basetype),
+                    })
+                }
+            };
+            self.group().types.push(typ);
+        }
+        Ok(())
+    }
+
+    fn enumeration(&mut self, basetype: &NcVariableType) -> Result<NcVariableType> {
+        let NcVariableType::Int(inttype) = basetype else {
+            return Err(self.error("the base type of an enum must be an integer"));
+        };
+        let name = self.expect_ident("the name of the type")?;
+        self.expect_punct('{')?;
+        let mut fieldnames = vec![];
+        let mut values = vec![];
+        loop {
+            fieldnames.push(self.expect_ident("the name of a member")?);
+            self.expect_punct('=')?;
+            let value = self.number()?;
+            let Number::Int(x, _) = value else {
+                return Err(self.error("enum members must be integers"));
+            };
+            values.push(x);
+            if !self.eat_punct(',') {
+                break;
+            }
+        }
+        self.expect_punct('}')?;
+        self.expect_punct(';')?;
+
+        macro_rules! members {
+            ($ty: ty) => {
+                values
+                    .iter()
+                    .map(|&x| <$ty>::try_from(x))
+                    .collect::<std::result::Result<Vec<$ty>, _>>()
+                    .map_err(|_| self.error("enum member is out of range"))?
+                    .into()
+            };
+        }
+        let fieldvalues: EnumTypeValues = match inttype {
+            IntType::U8 => members!(u8),
+            IntType::I8 => members!(i8),
+            IntType::U16 => members!(u16),
+            IntType::I16 => members!(i16),
+            IntType::U32 => members!(u32),
+            IntType::I32 => members!(i32),
+            IntType::U64 => members!(u64),
+            IntType::I64 => members!(i64),
+        };
+        Ok(NcVariableType::Enum(EnumType {
+            name,
+            fieldnames,
+            fieldvalues,
+        }))
+    }
+
+    fn compound(&mut self) -> Result<NcVariableType> {
+        let name = self.expect_ident("the name of the type")?;
+        self.expect_punct('{')?;
+        let mut fields = vec![];
+        let mut offset = 0_usize;
+        let mut align = 1;
+        while !self.eat_punct('}') {
+            let basetype = self.type_ref()?;
+            loop {
+                let name = self.expect_ident("the name of a field")?;
+                let mut arraydims = None;
+                if self.eat_punct('(') {
+                    let mut dims = vec![self.expect_usize()?];
+                    while self.eat_punct(',') {
+                        dims.push(self.expect_usize()?);
+                    }
+                    self.expect_punct(')')?;
+                    arraydims = Some(dims);
+                }
+                let field_align = alignment(&basetype);
+                align = align.max(field_align);
+                offset = offset.div_ceil(field_align) * field_align;
+                let len = arraydims
+                    .as_ref()
+                    .map_or(1, |dims: &Vec<usize>| dims.iter().product());
+                fields.push(CompoundTypeField {
+                    name,
+                    basetype: basetype.clone(),
+                    arraydims,
+                    offset,
+                });
+                offset += basetype.size() * len;
+                if !self.eat_punct(',') {
+                    break;
+                }
+            }
+            self.expect_punct(';')?;
+        }
+        self.eat_punct(';');
+        Ok(NcVariableType::Compound(CompoundType {
+            name,
+            size: offset.div_ceil(align) * align,
+            fields,
+        }))
+    }
+
+    fn dimensions(&mut self) -> Result<()> {
+        while !self.section_done() {
+            loop {
+                let name = self.expect_ident("the name of a dimension")?;
+                self.expect_punct('=')?;
+                let len = if self.is_ident("UNLIMITED") || self.is_ident("unlimited") {
+                    self.next();
+                    None
+                } else {
+                    Some(self.expect_usize()?)
+                };
+                self.group().dimensions.push(DimensionDef { name, len });
+                if !self.eat_punct(',') {
+                    break;
+                }
+            }
+            self.expect_punct(';')?;
+        }
+        Ok(())
+    }
+
+    fn variables(&mut self) -> Result<()> {
+        while !self.section_done() {
+            match (self.peek().clone(), self.peek_at(1).clone()) {
+                // Global attribute
+                (Token::Punct(':'), _) => {
+                    self.next();
+                    self.attribute(None, None)?;
+                }
+                (Token::Ident(name), Token::Punct(':')) => {
+                    self.next();
+                    self.next();
+                    match self.find_type(&name) {
+                        // Global attribute of a given type
+                        Some(typ) if !self.group().variables.iter().any(|v| v.name == name) => {
+                            self.attribute(None, Some(typ))?;
+                        }
+                        _ => self.attribute(Some(name), None)?,
+                    }
+                }
+                (Token::Ident(_), Token::Ident(_)) => {
+                    let typ = self.type_ref()?;
+                    if *self.peek_at(1) == Token::Punct(':') {
+                        let var = self.expect_ident("the name of a variable")?;
+                        self.next();
+                        self.attribute(Some(var), Some(typ))?;
+                    } else {
+                        self.declarations(&typ)?;
+                    }
+                }
+                _ => return Err(self.unexpected("a variable or attribute")),
+            }
+        }
+        Ok(())
+    }
+
+    fn declarations(&mut self, typ: &NcVariableType) -> Result<()> {
+        loop {
+            let name = self.expect_ident("the name of a variable")?;
+            let mut dimensions = vec![];
+            if self.eat_punct('(') {
+                loop {
+                    let dim = self.expect_ident("the name of a dimension")?;
+                    if self.find_dimension(&dim).is_none() {
+                        self.pos -= 1;
+                        return Err(self.error(format!("unknown dimension `{dim}`")));
+                    }
+                    dimensions.push(dim);
+                    if !self.eat_punct(',') {
+                        break;
+                    }
+                }
+                self.expect_punct(')')?;
+            }
+            self.group().variables.push(VariableDef {
+                name,
+                vartype: typ.clone(),
+                dimensions,
+                attributes: vec![],
+                data: None,
+            });
+            if !self.eat_punct(',') {
+                break;
+            }
+        }
+        self.expect_punct(';')
+    }
+
+    /// Attribute after the colon, of a variable or of the group
+    fn attribute(&mut self, var: Option<String>, typ: Option<NcVariableType>) -> Result<()> {
+        let name = self.expect_ident("the name of an attribute")?;
+        self.expect_punct('=')?;
+        let value = match typ {
+            Some(typ) => self.typed_attribute(&typ)?,
+            None => self.untyped_attribute()?,
+        };
+        self.expect_punct(';')?;
+        let attributes = match var {
+            None => &mut self.group().attributes,
+            Some(var) => {
+                let pos = self.group().variables.iter().position(|v| v.name == var);
+                let Some(pos) = pos else {
+                    return Err(self.error(format!("unknown variable `{var}`")));
+                };
+                &mut self.group().variables[pos].attributes
+            }
+        };
+        attributes.push((name, value));
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<Number> {
+        match self.peek() {
+            Token::Number(x) | Token::Ident(x) => {
+                let number = parse_number(x).ok_or_else(|| self.unexpected("a number"))?;
+                self.next();
+                Ok(number)
+            }
+            _ => Err(self.unexpected("a number")),
+        }
+    }
+
+    /// Attribute with the type given by its values
+    fn untyped_attribute(&mut self) -> Result<AttributeValue> {
+        if let Token::Str(_) = self.peek() {
+            // Strings are concatenated
+            let mut text = vec![];
+            loop {
+                match self.next().token {
+                    Token::Str(x) => text.extend(x),
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.unexpected("a string"));
+                    }
+                }
+                if !self.eat_punct(',') {
+                    break;
+                }
+            }
+            return Ok(text_value(text));
+        }
+        let mut numbers = vec![self.number()?];
+        while self.eat_punct(',') {
+            numbers.push(self.number()?);
+        }
+        let typ = if numbers
+            .iter()
+            .any(|x| matches!(x, Number::Float(_, FloatType::F64)))
+        {
+            NcVariableType::Float(FloatType::F64)
+        } else if numbers.iter().any(|x| matches!(x, Number::Float(..))) {
+            NcVariableType::Float(FloatType::F32)
+        } else {
+            match numbers[0] {
+                Number::Int(_, typ) => NcVariableType::Int(typ),
+                Number::Float(_, typ) => NcVariableType::Float(typ),
+            }
+        };
+        let values = numbers
+            .iter()
+            .map(|&x| match (x, &typ) {
+                (Number::Int(x, _), NcVariableType::Int(typ)) => int_value(x, *typ),
+                (x, _) => Some(Value::Float(x.as_f64())),
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| self.error("attribute value is out of range"))?;
+        numeric_attribute(&typ, &values).ok_or_else(|| self.error("invalid attribute value"))
+    }
+
+    fn typed_attribute(&mut self, typ: &NcVariableType) -> Result<AttributeValue> {
+        let values = self.values(typ)?;
+        let value = match typ {
+            NcVariableType::Char => text_value(
+                values
+                    .into_iter()
+                    .filter_map(|v| match v {
+                        Value::Char(c) => Some(c),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            NcVariableType::String => AttributeValue::Strs(
+                values
+                    .into_iter()
+                    .filter_map(|v| match v {
+                        Value::Str(s) => Some(s),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            NcVariableType::Compound(x) => AttributeValue::Compound(x.clone(), values),
+            NcVariableType::Enum(x) => AttributeValue::Enum(x.clone(), values),
+            NcVariableType::Opaque(x) => AttributeValue::Opaque(x.clone(), values),
+            NcVariableType::Vlen(x) => AttributeValue::Vlen(x.clone(), values),
+            typ => numeric_attribute(typ, &values)
+                .ok_or_else(|| self.error("attribute value is out of range"))?,
+        };
+        Ok(value)
+    }
+
+    /// Comma separated values of `typ`, strings of `char` give one value per byte
+    fn values(&mut self, typ: &NcVariableType) -> Result<Vec<Value>> {
+        let mut values = vec![];
+        if self.is_punct(';') {
+            return Ok(values);
+        }
+        loop {
+            self.value_into(typ, &mut values)?;
+            if !self.eat_punct(',') {
+                return Ok(values);
+            }
+        }
+    }
+
+    fn value_into(&mut self, typ: &NcVariableType, values: &mut Vec<Value>) -> Result<()> {
+        if *typ == NcVariableType::Char {
+            if let Token::Str(text) = self.peek() {
+                values.extend(text.iter().copied().map(Value::Char));
+                self.next();
+                return Ok(());
+            }
+        }
+        let value = self.value(typ)?;
+        values.push(value);
+        Ok(())
+    }
+
+    fn value(&mut self, typ: &NcVariableType) -> Result<Value> {
+        if self.is_ident("_") {
+            return Err(self.error("fill values (`_`) are only supported in the data section"));
+        }
+        let value = match typ {
+            NcVariableType::Int(inttype) => match self.number()? {
+                Number::Int(x, _) => int_value(x, *inttype),
+                Number::Float(..) => None,
+            },
+            NcVariableType::Float(_) => Some(Value::Float(self.number()?.as_f64())),
+            NcVariableType::Char => match self.peek().clone() {
+                Token::Str(x) if x.len() == 1 => {
+                    self.next();
+                    Some(Value::Char(x[0]))
+                }
+                _ => match self.number()? {
+                    Number::Int(x, _) => u8::try_from(x).ok().map(Value::Char),
+                    Number::Float(..) => None,
+                },
+            },
+            NcVariableType::String => match self.next().token {
+                Token::Str(x) => Some(Value::Str(
+                    String::from_utf8(x).map_err(|_| self.error("string is not valid UTF-8"))?,
+                )),
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.unexpected("a string"));
+                }
+            },
+            NcVariableType::Enum(enumtype) => match self.peek().clone() {
+                Token::Ident(member) if enumtype.fieldnames.contains(&member) => {
+                    self.next();
+                    Some(Value::Enum(member))
+                }
+                Token::Ident(member) => {
+                    return Err(
+                        self.error(format!("`{member}` is not a member of {}", enumtype.name))
+                    )
+                }
+                _ => match self.number()? {
+                    Number::Int(x, _) => int_value(x, IntType::I64),
+                    Number::Float(..) => None,
+                },
+            },
+            NcVariableType::Opaque(opaque) => match self.peek().clone() {
+                Token::Number(x)
+                    if x.len() > 2 && x.get(..2).is_some_and(|p| p.eq_ignore_ascii_case("0x")) =>
+                {
+                    let hex = &x[2..];
+                    let mut bytes = hex
+                        .as_bytes()
+                        .chunks(2)
+                        .map(|pair| {
+                            std::str::from_utf8(pair)
+                                .ok()
+                                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| self.unexpected("hexadecimal bytes"))?;
+                    if bytes.len() > opaque.size {
+                        return Err(self
+                            .error(format!("opaque value is longer than {} bytes", opaque.size)));
+                    }
+                    bytes.resize(opaque.size, 0);
+                    self.next();
+                    Some(Value::Opaque(bytes))
+                }
+                _ => return Err(self.unexpected("hexadecimal bytes (`0X...`)")),
+            },
+            NcVariableType::Vlen(vlen) => {
+                self.expect_punct('{')?;
+                let mut values = vec![];
+                if !self.is_punct('}') {
+                    loop {
+                        self.value_into(&vlen.basetype, &mut values)?;
+                        if !self.eat_punct(',') {
+                            break;
+                        }
+                    }
+                }
+                self.expect_punct('}')?;
+                Some(Value::Vlen(values))
+            }
+            NcVariableType::Compound(compound) => {
+                self.expect_punct('{')?;
+                let mut fields = vec![];
+                for (i, field) in compound.fields.iter().enumerate() {
+                    if i > 0 {
+                        self.expect_punct(',')?;
+                    }
+                    let value = match &field.arraydims {
+                        None => self.value(&field.basetype)?,
+                        Some(dims) => {
+                            let len = dims.iter().product::<usize>();
+                            let braced = self.eat_punct('{');
+                            let mut values = vec![];
+                            while values.len() < len {
+                                if !values.is_empty() {
+                                    self.expect_punct(',')?;
+                                }
+                                self.value_into(&field.basetype, &mut values)?;
+                            }
+                            if field.basetype == NcVariableType::Char {
+                                values.resize(len, Value::Char(0));
+                            }
+                            if braced {
+                                self.expect_punct('}')?;
+                            }
+                            Value::Array(values)
+                        }
+                    };
+                    fields.push((field.name.clone(), value));
+                }
+                self.expect_punct('}')?;
+                Some(Value::Compound(fields))
+            }
+        };
+        value.ok_or_else(|| {
+            self.pos -= 1;
+            let e = self.error(format!("invalid value for {}", typ.name()));
+            self.pos += 1;
+            e
+        })
+    }
+
+    fn data(&mut self) -> Result<()> {
+        while !self.section_done() {
+            let name = self.expect_ident("the name of a variable")?;
+            let Some(pos) = self.group().variables.iter().position(|v| v.name == name) else {
+                self.pos -= 1;
+                return Err(self.error(format!("unknown variable `{name}`")));
+            };
+            self.expect_punct('=')?;
+            let var = self.group().variables[pos].clone();
+            let mut values = vec![];
+            if !self.is_punct(';') {
+                loop {
+                    if self.is_ident("_") {
+                        self.next();
+                        let fill = fill_value(&var)
+                            .ok_or_else(|| self.error("the variable has no fill value"))?;
+                        values.push(fill);
+                    } else if var.vartype == NcVariableType::Char {
+                        self.char_data(&var, &mut values)?;
+                    } else {
+                        values.push(self.value(&var.vartype)?);
+                    }
+                    if !self.eat_punct(',') {
+                        break;
+                    }
+                }
+            }
+            self.expect_punct(';')?;
+            self.group().variables[pos].data = Some(values);
+        }
+        Ok(())
+    }
+
+    /// Text of a `char` variable, where each string fills whole rows
+    /// of the last dimension
+    fn char_data(&mut self, var: &VariableDef, values: &mut Vec<Value>) -> Result<()> {
+        let Token::Str(text) = self.peek().clone() else {
+            let value = self.value(&NcVariableType::Char)?;
+            values.push(value);
+            return Ok(());
+        };
+        self.next();
+        let row = match var.dimensions.last() {
+            Some(dim) if var.dimensions.len() > 1 => self
+                .find_dimension(dim)
+                .and_then(|dim| dim.len)
+                .unwrap_or(1),
+            _ => 1,
+        };
+        let start = values.len();
+        let len = text.len().div_ceil(row).max(1) * row;
+        values.extend(text.into_iter().map(Value::Char));
+        values.resize(start + len, Value::Char(0));
+        Ok(())
+    }
+
+    fn subgroup(&mut self) -> Result<()> {
+        let name = self.expect_ident("the name of the group")?;
+        self.expect_punct('{')?;
+        self.stack.push(GroupDef {
+            name,
+            ..GroupDef::default()
+        });
+        self.group_body()?;
+        self.expect_punct('}')?;
+        let group = self.stack.pop().unwrap();
+        self.group().groups.push(group);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert!(matches!(
+            parse_number("1"),
+            Some(Number::Int(1, IntType::I32))
+        ));
+        assert!(matches!(
+            parse_number("-2b"),
+            Some(Number::Int(-2, IntType::I8))
+        ));
+        assert!(matches!(
+            parse_number("3UB"),
+            Some(Number::Int(3, IntType::U8))
+        ));
+        assert!(matches!(
+            parse_number("4ull"),
+            Some(Number::Int(4, IntType::U64))
+        ));
+        assert!(matches!(
+            parse_number("0x1F"),
+            Some(Number::Int(31, IntType::I32))
+        ));
+        assert!(matches!(parse_number("1.5f"), Some(Number::Float(x, FloatType::F32)) if x == 1.5));
+        assert!(
+            matches!(parse_number("2.e-1"), Some(Number::Float(x, FloatType::F64)) if x == 0.2)
+        );
+        assert!(
+            matches!(parse_number("-Infinity"), Some(Number::Float(x, _)) if x == f64::NEG_INFINITY)
+        );
+        assert!(parse_number("1.5s").is_none());
+        assert!(parse_number("abc").is_none());
+    }
+
+    #[test]
+    fn sections() {
+        let schema = Schema::parse(
+            r#"netcdf test {
+types:
+  ubyte enum flag {off = 0, on = 1} ;
+dimensions:
+  x = 2, time = UNLIMITED ;
+variables:
+  flag data(x) ;
+    data:units = "1" ;
+  float t(time) ;
+data:
+  data = off, on ;
+  t = 1, _ ;
+}"#,
+        )
+        .unwrap();
+        let root = &schema.root;
+        assert_eq!(root.types.len(), 1);
+        assert_eq!(root.dimensions[1].len, None);
+        assert_eq!(
+            root.variables[0].attributes,
+            vec![("units".to_owned(), AttributeValue::Str("1".to_owned()))]
+        );
+        assert_eq!(
+            root.variables[0].data,
+            Some(vec![
+                Value::Enum("off".to_owned()),
+                Value::Enum("on".to_owned())
+            ])
+        );
+        assert_eq!(
+            root.variables[1].data,
+            Some(vec![
+                Value::Float(1.0),
+                Value::Float(netcdf_sys::NC_FILL_FLOAT.into())
+            ])
+        );
+    }
+
+    #[test]
+    fn error_position() {
+        let e = Schema::parse("netcdf test {\ndimensions:\n  x = 2 ;\nvariables:\n  int v(y) ;\n}")
+            .unwrap_err();
+        assert_eq!((e.line, e.column), (5, 9));
+        assert_eq!(e.message, "unknown dimension `y`");
+
+        let e =
+            Schema::parse("netcdf test {\nvariables:\n  int v ;\n  v:a = \"x ;\n}").unwrap_err();
+        assert_eq!(e.line, 5);
+        assert_eq!(e.message, "unterminated string");
+    }
+
+    #[test]
+    fn non_ascii() {
+        assert!(
+            Schema::parse("netcdf test {\nvariables:\n  int v ;\ndata:\n  v = 1é ;\n}").is_err()
+        );
+        assert!(Schema::parse(
+            "netcdf test {\ntypes:\n  opaque(2) blob ;\nvariables:\n  blob b ;\ndata:\n  b = 0X1é ;\n}"
+        )
+        .is_err());
+
+        let schema = Schema::parse(
+            r#"netcdf test {
+dimensions:
+  x = 1 ;
+variables:
+  char c(x) ;
+    c:_FillValue = "\0" ;
+data:
+  c = _ ;
+}"#,
+        )
+        .unwrap();
+        assert_eq!(schema.root.variables[0].data, Some(vec![Value::Char(0)]));
+    }
+}
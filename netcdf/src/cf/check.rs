@@ -0,0 +1,651 @@
+//! Compliance checking against the CF conventions
+
+use std::collections::HashSet;
+use std::fmt;
+
+use netcdf_sys::*;
+
+use super::CfVersion;
+use crate::attribute::{Attribute, AttributeValue};
+use crate::error;
+use crate::file::File;
+use crate::variable::Variable;
+
+/// How serious a [`Diagnostic`] is
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Informational, no action is required
+    Info,
+    /// A recommendation of the conventions is not followed
+    Warning,
+    /// A requirement of the conventions is violated
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Info => write!(f, "INFO"),
+            Self::Warning => write!(f, "WARN"),
+            Self::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+/// Which rule a [`Diagnostic`] originates from
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// The global `Conventions` attribute is missing or names another version
+    Conventions,
+    /// A variable lacks the `units` attribute
+    MissingUnits,
+    /// `standard_name` is malformed or not part of the standard name table
+    InvalidStandardName,
+    /// `_FillValue` has a different type than the variable
+    FillValueType,
+    /// An attribute has a type which is not allowed, e.g. a number
+    /// where text is expected
+    AttributeType,
+    /// `coordinates` names a variable which does not exist or
+    /// which does not share the dimensions of the variable
+    BadCoordinates,
+    /// `bounds` names a variable which does not exist or has the wrong shape
+    BadBounds,
+    /// A coordinate variable is not strictly monotonic
+    NonMonotonic,
+}
+
+/// A single finding of the [`Checker`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the finding is
+    pub severity: Severity,
+    /// The rule which was violated
+    pub kind: DiagnosticKind,
+    /// Full path of the offending item, e.g. `/group/variable`,
+    /// or `/` for the root group
+    pub location: String,
+    /// Human readable description of the finding
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.location, self.message)
+    }
+}
+
+/// The result of checking a file, see [`Checker::check`]
+#[derive(Debug, Clone)]
+pub struct Report {
+    version: CfVersion,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    /// The version of the conventions the file was checked against
+    pub fn version(&self) -> CfVersion {
+        self.version
+    }
+    /// All findings, in the order they were encountered
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+    /// Number of findings with the given severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+    /// The file does not violate any requirements of the conventions
+    pub fn is_compliant(&self) -> bool {
+        self.count(Severity::Error) == 0
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} compliance: {} errors, {} warnings, {} info",
+            self.version,
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.count(Severity::Info),
+        )?;
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Modifiers which may follow a standard name (CF Appendix C)
+const STANDARD_NAME_MODIFIERS: &[&str] = &[
+    "detection_minimum",
+    "number_of_observations",
+    "standard_error",
+    "status_flag",
+];
+
+/// Checks files for compliance with the CF conventions
+///
+/// Without a standard name table only the syntax of `standard_name`
+/// is validated, use [`with_standard_names`](Self::with_standard_names)
+/// to also validate the names themselves.
+#[derive(Debug, Clone, Default)]
+pub struct Checker {
+    version: CfVersion,
+    standard_names: Option<HashSet<String>>,
+}
+
+/// Check a file against the given version of the conventions
+pub fn check(file: &File, version: CfVersion) -> error::Result<Report> {
+    Checker::new(version).check(file)
+}
+
+impl Checker {
+    /// Create a checker for the given version of the conventions
+    pub fn new(version: CfVersion) -> Self {
+        Self {
+            version,
+            standard_names: None,
+        }
+    }
+
+    /// Validate `standard_name` against the given standard name table
+    #[must_use]
+    pub fn with_standard_names<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.standard_names = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Walk through all groups, variables and attributes of the
+    /// file and report any violations of the conventions
+    pub fn check(&self, file: &File) -> error::Result<Report> {
+        let root = file.ncid();
+        let mut context = Context {
+            checker: self,
+            diagnostics: Vec::new(),
+        };
+        context.check_conventions(root)?;
+        context.check_group(root, "/")?;
+
+        Ok(Report {
+            version: self.version,
+            diagnostics: context.diagnostics,
+        })
+    }
+}
+
+struct Context<'c> {
+    checker: &'c Checker,
+    diagnostics: Vec<Diagnostic>,
+}
+
+fn is_numeric(xtype: nc_type) -> bool {
+    matches!(
+        xtype,
+        NC_BYTE
+            | NC_SHORT
+            | NC_INT
+            | NC_FLOAT
+            | NC_DOUBLE
+            | NC_UBYTE
+            | NC_USHORT
+            | NC_UINT
+            | NC_INT64
+            | NC_UINT64
+    )
+}
+
+fn type_name(xtype: nc_type) -> String {
+    match xtype {
+        NC_BYTE => "byte".into(),
+        NC_CHAR => "char".into(),
+        NC_SHORT => "short".into(),
+        NC_INT => "int".into(),
+        NC_FLOAT => "float".into(),
+        NC_DOUBLE => "double".into(),
+        NC_UBYTE => "ubyte".into(),
+        NC_USHORT => "ushort".into(),
+        NC_UINT => "uint".into(),
+        NC_INT64 => "int64".into(),
+        NC_UINT64 => "uint64".into(),
+        NC_STRING => "string".into(),
+        x => format!("user type {x}"),
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.ends_with('/') {
+        format!("{path}{name}")
+    } else {
+        format!("{path}/{name}")
+    }
+}
+
+fn valid_standard_name_syntax(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+impl Context<'_> {
+    fn report(
+        &mut self,
+        severity: Severity,
+        kind: DiagnosticKind,
+        location: &str,
+        message: impl Into<String>,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            kind,
+            location: location.to_owned(),
+            message: message.into(),
+        });
+    }
+
+    /// Get an attribute which must contain text
+    fn text_attribute(
+        &mut self,
+        ncid: nc_type,
+        varid: Option<nc_type>,
+        name: &str,
+        location: &str,
+    ) -> error::Result<Option<String>> {
+        let Some(attr) = Attribute::find_from_name(ncid, varid, name)? else {
+            return Ok(None);
+        };
+        let text = match attr.value()? {
+            // String attributes are only allowed from CF-1.8
+            AttributeValue::Strs(_) if self.checker.version < CfVersion::V1_8 => None,
+            value => String::try_from(value).ok(),
+        };
+        if text.is_none() {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::AttributeType,
+                location,
+                format!("Attribute {name} must be a string"),
+            );
+        }
+        Ok(text)
+    }
+
+    fn check_conventions(&mut self, root: nc_type) -> error::Result<()> {
+        let expected = self.checker.version.to_string();
+        let Some(conventions) = self.text_attribute(root, None, "Conventions", "/")? else {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::Conventions,
+                "/",
+                format!("Global attribute Conventions is missing, expected {expected}"),
+            );
+            return Ok(());
+        };
+        let mut declared = conventions
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|c| c.starts_with("CF-"));
+        match declared.next() {
+            None => self.report(
+                Severity::Error,
+                DiagnosticKind::Conventions,
+                "/",
+                format!("Conventions \"{conventions}\" does not contain {expected}"),
+            ),
+            Some(version) if version != expected => self.report(
+                Severity::Info,
+                DiagnosticKind::Conventions,
+                "/",
+                format!("File declares {version}, but is checked against {expected}"),
+            ),
+            Some(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Check a group and all subgroups
+    fn check_group(&mut self, ncid: nc_type, path: &str) -> error::Result<()> {
+        let variables =
+            crate::variable::variables_at_ncid(ncid)?.collect::<error::Result<Vec<_>>>()?;
+
+        // Bounds variables inherit units and standard_name from their parent
+        let mut bounds = HashSet::new();
+        for var in &variables {
+            for name in ["bounds", "climatology"] {
+                if let Some(attr) = Attribute::find_from_name(ncid, Some(var.varid), name)? {
+                    if let Ok(s) = String::try_from(attr.value()?) {
+                        bounds.insert(s);
+                    }
+                }
+            }
+        }
+
+        for var in &variables {
+            let name = var.name();
+            let location = join(path, &name);
+            let is_bounds = bounds.contains(&name);
+            self.check_variable(var, &name, &location, is_bounds)?;
+        }
+
+        for group in crate::group::groups_at_ncid(ncid)? {
+            let location = join(path, &group.name());
+            self.check_group(group.ncid, &location)?;
+        }
+        Ok(())
+    }
+
+    fn check_variable(
+        &mut self,
+        var: &Variable,
+        name: &str,
+        location: &str,
+        is_bounds: bool,
+    ) -> error::Result<()> {
+        let is_coordinate = var.dimensions.len() == 1
+            && var.dimensions[0].name() == name
+            && is_numeric(var.vartype);
+
+        self.check_units(var, location, is_coordinate, is_bounds)?;
+        self.check_standard_name(var, location)?;
+        self.check_value_types(var, location)?;
+        self.check_coordinates(var, location)?;
+        for attr in ["bounds", "climatology"] {
+            self.check_bounds(var, attr, location)?;
+        }
+        if is_coordinate {
+            self.check_monotonic(var, location)?;
+        }
+        Ok(())
+    }
+
+    fn check_units(
+        &mut self,
+        var: &Variable,
+        location: &str,
+        is_coordinate: bool,
+        is_bounds: bool,
+    ) -> error::Result<()> {
+        let units = self.text_attribute(var.ncid, Some(var.varid), "units", location)?;
+        if units.is_some() || !is_numeric(var.vartype) || is_bounds {
+            return Ok(());
+        }
+        // Variables which are dimensionless by construction
+        for exempt in [
+            "flag_values",
+            "flag_masks",
+            "grid_mapping_name",
+            "cf_role",
+            "sample_dimension",
+            "instance_dimension",
+            "compress",
+            "formula_terms",
+        ] {
+            if Attribute::find_from_name(var.ncid, Some(var.varid), exempt)?.is_some() {
+                return Ok(());
+            }
+        }
+        let has_axis = Attribute::find_from_name(var.ncid, Some(var.varid), "axis")?.is_some();
+        if is_coordinate || has_axis {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::MissingUnits,
+                location,
+                "Coordinate variable is missing the units attribute",
+            );
+        } else {
+            self.report(
+                Severity::Warning,
+                DiagnosticKind::MissingUnits,
+                location,
+                "Variable is missing the units attribute",
+            );
+        }
+        Ok(())
+    }
+
+    fn check_standard_name(&mut self, var: &Variable, location: &str) -> error::Result<()> {
+        let Some(standard_name) =
+            self.text_attribute(var.ncid, Some(var.varid), "standard_name", location)?
+        else {
+            return Ok(());
+        };
+        let mut parts = standard_name.split_whitespace();
+        let Some(name) = parts.next() else {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::InvalidStandardName,
+                location,
+                "standard_name is empty",
+            );
+            return Ok(());
+        };
+        if let Some(modifier) = parts.next() {
+            if !STANDARD_NAME_MODIFIERS.contains(&modifier) {
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::InvalidStandardName,
+                    location,
+                    format!("Unknown standard_name modifier \"{modifier}\""),
+                );
+            }
+        }
+        if parts.next().is_some() {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::InvalidStandardName,
+                location,
+                format!("standard_name \"{standard_name}\" has too many parts"),
+            );
+        }
+        if !valid_standard_name_syntax(name) {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::InvalidStandardName,
+                location,
+                format!("standard_name \"{name}\" may only contain lowercase letters, digits and underscores"),
+            );
+        } else if let Some(table) = &self.checker.standard_names {
+            if !table.contains(name) {
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::InvalidStandardName,
+                    location,
+                    format!("standard_name \"{name}\" is not in the standard name table"),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn check_value_types(&mut self, var: &Variable, location: &str) -> error::Result<()> {
+        if let Some(attr) = Attribute::find_from_name(var.ncid, Some(var.varid), "_FillValue")? {
+            let typ = attr.typ()?;
+            if typ != var.vartype {
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::FillValueType,
+                    location,
+                    format!(
+                        "_FillValue has type {}, but the variable has type {}",
+                        type_name(typ),
+                        type_name(var.vartype)
+                    ),
+                );
+            }
+        }
+
+        // Packed data may use the type of the packing attributes instead
+        let mut packed_type = None;
+        for packing in ["scale_factor", "add_offset"] {
+            if let Some(attr) = Attribute::find_from_name(var.ncid, Some(var.varid), packing)? {
+                packed_type = Some(attr.typ()?);
+            }
+        }
+        for name in ["missing_value", "valid_min", "valid_max", "valid_range"] {
+            let Some(attr) = Attribute::find_from_name(var.ncid, Some(var.varid), name)? else {
+                continue;
+            };
+            let typ = attr.typ()?;
+            if typ != var.vartype && Some(typ) != packed_type {
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::AttributeType,
+                    location,
+                    format!(
+                        "{name} has type {}, but the variable has type {}",
+                        type_name(typ),
+                        type_name(var.vartype)
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Find a variable referenced from another variable in the group `ncid`
+    fn resolve<'a>(&self, ncid: nc_type, name: &str) -> error::Result<Option<Variable<'a>>> {
+        // Plain names are searched for in the ancestors since CF-1.8
+        crate::hierarchy::find_referenced_variable(
+            ncid,
+            name,
+            self.checker.version >= CfVersion::V1_8,
+        )
+    }
+
+    fn check_coordinates(&mut self, var: &Variable, location: &str) -> error::Result<()> {
+        let Some(coordinates) =
+            self.text_attribute(var.ncid, Some(var.varid), "coordinates", location)?
+        else {
+            return Ok(());
+        };
+        let dims = var
+            .dimensions
+            .iter()
+            .map(crate::Dimension::name)
+            .collect::<Vec<_>>();
+
+        for coordinate in coordinates.split_whitespace() {
+            if coordinate.contains('/') && self.checker.version < CfVersion::V1_8 {
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::BadCoordinates,
+                    location,
+                    format!("Coordinate \"{coordinate}\" uses a group path, which requires CF-1.8"),
+                );
+                continue;
+            }
+            let Some(aux) = self.resolve(var.ncid, coordinate)? else {
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::BadCoordinates,
+                    location,
+                    format!("Coordinate \"{coordinate}\" does not exist"),
+                );
+                continue;
+            };
+            // The string length dimension of labels is not shared
+            let aux_dims = match aux.vartype {
+                NC_CHAR => &aux.dimensions[..aux.dimensions.len().saturating_sub(1)],
+                _ => &aux.dimensions[..],
+            };
+            for dim in aux_dims {
+                let dim = dim.name();
+                if !dims.contains(&dim) {
+                    self.report(
+                        Severity::Error,
+                        DiagnosticKind::BadCoordinates,
+                        location,
+                        format!(
+                            "Coordinate \"{coordinate}\" has dimension {dim}, which the variable does not have"
+                        ),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_bounds(&mut self, var: &Variable, attr: &str, location: &str) -> error::Result<()> {
+        let Some(name) = self.text_attribute(var.ncid, Some(var.varid), attr, location)? else {
+            return Ok(());
+        };
+        let Some(bounds) = self.resolve(var.ncid, &name)? else {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::BadBounds,
+                location,
+                format!("{attr} variable \"{name}\" does not exist"),
+            );
+            return Ok(());
+        };
+
+        let parent = &var.dimensions;
+        let shape_matches = bounds.dimensions.len() == parent.len() + 1
+            && parent
+                .iter()
+                .zip(&bounds.dimensions)
+                .all(|(p, b)| p.name() == b.name() && p.len() == b.len());
+        if !shape_matches {
+            let shape = |dims: &[crate::Dimension]| {
+                dims.iter()
+                    .map(crate::Dimension::name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            self.report(
+                Severity::Error,
+                DiagnosticKind::BadBounds,
+                location,
+                format!(
+                    "{attr} variable \"{name}\" has dimensions ({}), expected ({}, <vertices>)",
+                    shape(&bounds.dimensions),
+                    shape(parent),
+                ),
+            );
+        } else if parent.len() == 1 && bounds.dimensions[1].len() != 2 {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::BadBounds,
+                location,
+                format!(
+                    "{attr} variable \"{name}\" of a one-dimensional coordinate must have two vertices, found {}",
+                    bounds.dimensions[1].len()
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    fn check_monotonic(&mut self, var: &Variable, location: &str) -> error::Result<()> {
+        if var.len() < 2 {
+            return Ok(());
+        }
+        let values = var.get_values::<f64, _>(..)?;
+        // NaN compares as neither, and is therefore also a violation
+        let direction = values[1].partial_cmp(&values[0]);
+        let violation = values.windows(2).position(|w| {
+            let step = w[1].partial_cmp(&w[0]);
+            step != direction || step == Some(std::cmp::Ordering::Equal)
+        });
+        if let Some(index) = violation {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::NonMonotonic,
+                location,
+                format!(
+                    "Coordinate variable is not strictly monotonic at index {}",
+                    index + 1
+                ),
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,541 @@
+//! Discrete sampling geometries stored as ragged arrays (CF chapter 9)
+//!
+//! Collections of features with a varying number of elements, such as
+//! station time series or trajectories, are packed along a single
+//! sample dimension. A count variable (contiguous ragged array) or an
+//! index variable (indexed ragged array) maps each element to its feature.
+
+use std::ops::Range;
+
+use netcdf_sys::nc_type;
+
+use crate::attribute::Attribute;
+use crate::error;
+use crate::extent::{Extent, Extents};
+use crate::file::{File, FileMut};
+use crate::types::NcTypeDescriptor;
+use crate::variable::{Variable, VariableMut};
+
+/// The `featureType` of a discrete sampling geometry
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FeatureType {
+    /// `point`
+    Point,
+    /// `timeSeries`
+    TimeSeries,
+    /// `trajectory`
+    Trajectory,
+    /// `profile`
+    Profile,
+    /// `timeSeriesProfile`
+    TimeSeriesProfile,
+    /// `trajectoryProfile`
+    TrajectoryProfile,
+}
+
+impl std::fmt::Display for FeatureType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Point => "point",
+            Self::TimeSeries => "timeSeries",
+            Self::Trajectory => "trajectory",
+            Self::Profile => "profile",
+            Self::TimeSeriesProfile => "timeSeriesProfile",
+            Self::TrajectoryProfile => "trajectoryProfile",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl std::str::FromStr for FeatureType {
+    type Err = error::Error;
+    /// The comparison is case insensitive, as required by the conventions
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_ascii_lowercase().as_str() {
+            "point" => Self::Point,
+            "timeseries" => Self::TimeSeries,
+            "trajectory" => Self::Trajectory,
+            "profile" => Self::Profile,
+            "timeseriesprofile" => Self::TimeSeriesProfile,
+            "trajectoryprofile" => Self::TrajectoryProfile,
+            _ => return Err(format!("Unknown featureType {s}").into()),
+        })
+    }
+}
+
+/// How the elements of the features are stored
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RaggedLayout {
+    /// The elements of each feature are stored contiguously, the number of
+    /// elements is given by a count variable with a `sample_dimension` attribute
+    Contiguous,
+    /// Each element refers to its feature through an index variable with
+    /// an `instance_dimension` attribute
+    Indexed,
+}
+
+#[derive(Debug, Clone)]
+enum Lookup {
+    Contiguous(Vec<Range<usize>>),
+    Indexed(Vec<Vec<usize>>),
+}
+
+/// A ragged array read from a file, used to split data
+/// variables along the sample dimension into features
+///
+/// # Example
+/// ```rust,no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = netcdf::open("stations.nc")?;
+/// let temperature = file.variable("temperature").expect("no such variable");
+/// let ragged = netcdf::cf::RaggedArray::for_variable(&temperature)?.expect("not a ragged array");
+/// for (i, series) in ragged.features::<f32>(&temperature)?.iter().enumerate() {
+///     println!("Station {i} has {} observations", series.len());
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RaggedArray {
+    feature_type: Option<FeatureType>,
+    instance_dimension: String,
+    sample_dimension: String,
+    variable: String,
+    lookup: Lookup,
+}
+
+fn to_indices(values: Vec<i64>, variable: &str) -> error::Result<Vec<usize>> {
+    values
+        .into_iter()
+        .map(|x| {
+            usize::try_from(x)
+                .map_err(|_| format!("{variable} contains the negative value {x}").into())
+        })
+        .collect()
+}
+
+/// The `featureType` global attribute of the file holding the group `ncid`
+fn feature_type(ncid: nc_type) -> error::Result<Option<FeatureType>> {
+    let root = crate::hierarchy::root(ncid)?;
+    Attribute::find_from_name(root, None, "featureType")?
+        .map(|attr| -> error::Result<FeatureType> { String::try_from(attr.value()?)?.parse() })
+        .transpose()
+}
+
+impl RaggedArray {
+    /// Find every ragged array of a file, one for each variable with a
+    /// `sample_dimension` or `instance_dimension` attribute
+    pub fn from_file(file: &File) -> error::Result<Vec<Self>> {
+        let feature_type = feature_type(file.ncid())?;
+        let mut arrays = vec![];
+        let mut groups = vec![file.ncid()];
+        while let Some(ncid) = groups.pop() {
+            for var in crate::variable::variables_at_ncid(ncid)? {
+                if let Some(array) = Self::from_variable(feature_type, &var?)? {
+                    arrays.push(array);
+                }
+            }
+            groups.extend(crate::group::groups_at_ncid(ncid)?.map(|group| group.ncid));
+        }
+        Ok(arrays)
+    }
+
+    /// Find the ragged array a data variable is stored along, the one
+    /// whose sample dimension is the first dimension of `var`
+    ///
+    /// The count or index variable is searched for in the group of `var`
+    /// and then in its ancestors. Returns `None` if there is none
+    pub fn for_variable(var: &Variable) -> error::Result<Option<Self>> {
+        let Some(sample) = var.dimensions().first() else {
+            return Ok(None);
+        };
+        let sample = sample.try_name()?;
+        let feature_type = feature_type(var.ncid)?;
+        let mut ncid = Some(var.ncid);
+        while let Some(group) = ncid {
+            for candidate in crate::variable::variables_at_ncid(group)? {
+                if let Some(array) = Self::from_variable(feature_type, &candidate?)? {
+                    if array.sample_dimension == sample {
+                        return Ok(Some(array));
+                    }
+                }
+            }
+            ncid = crate::hierarchy::parent(group)?;
+        }
+        Ok(None)
+    }
+
+    /// The ragged array described by a count or index variable
+    fn from_variable(
+        feature_type: Option<FeatureType>,
+        var: &Variable,
+    ) -> error::Result<Option<Self>> {
+        if let Some(sample) = var.attribute_value("sample_dimension").transpose()? {
+            return Self::contiguous(feature_type, var, String::try_from(sample)?).map(Some);
+        }
+        if let Some(instance) = var.attribute_value("instance_dimension").transpose()? {
+            return Self::indexed(feature_type, var, String::try_from(instance)?).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn contiguous(
+        feature_type: Option<FeatureType>,
+        count: &Variable,
+        sample_dimension: String,
+    ) -> error::Result<Self> {
+        let variable = count.name();
+        let [instance] = count.dimensions() else {
+            return Err(format!("count variable {variable} must be one-dimensional").into());
+        };
+        let sample_len = crate::dimension::from_name(count.ncid, &sample_dimension)?
+            .ok_or_else(|| error::Error::NotFound(format!("dimension {sample_dimension}")))?
+            .len();
+        if instance.name() == sample_dimension {
+            return Err(format!("{variable} is counted along its own dimension").into());
+        }
+        let counts = to_indices(count.get_values::<i64, _>(..)?, &variable)?;
+        if counts.iter().sum::<usize>() > sample_len {
+            return Err(format!("{variable} counts more than {sample_len} elements").into());
+        }
+
+        let mut start = 0;
+        let ranges = counts
+            .into_iter()
+            .map(|count| {
+                let range = start..start + count;
+                start += count;
+                range
+            })
+            .collect();
+
+        Ok(Self {
+            feature_type,
+            instance_dimension: instance.name(),
+            sample_dimension,
+            variable,
+            lookup: Lookup::Contiguous(ranges),
+        })
+    }
+
+    fn indexed(
+        feature_type: Option<FeatureType>,
+        index: &Variable,
+        instance_dimension: String,
+    ) -> error::Result<Self> {
+        let variable = index.name();
+        let [sample] = index.dimensions() else {
+            return Err(format!("index variable {variable} must be one-dimensional").into());
+        };
+        if sample.name() == instance_dimension {
+            return Err(format!("{variable} indexes along its own dimension").into());
+        }
+        let num_instances = crate::dimension::from_name(index.ncid, &instance_dimension)?
+            .ok_or_else(|| error::Error::NotFound(format!("dimension {instance_dimension}")))?
+            .len();
+        let index = to_indices(index.get_values::<i64, _>(..)?, &variable)?;
+
+        let mut elements = vec![Vec::new(); num_instances];
+        for (element, instance) in index.into_iter().enumerate() {
+            elements
+                .get_mut(instance)
+                .ok_or_else(|| format!("{variable} refers to the missing instance {instance}"))?
+                .push(element);
+        }
+
+        Ok(Self {
+            feature_type,
+            instance_dimension,
+            sample_dimension: sample.name(),
+            variable,
+            lookup: Lookup::Indexed(elements),
+        })
+    }
+
+    /// The `featureType` global attribute, if present
+    pub fn feature_type(&self) -> Option<FeatureType> {
+        self.feature_type
+    }
+    /// How the features are laid out
+    pub fn layout(&self) -> RaggedLayout {
+        match self.lookup {
+            Lookup::Contiguous(_) => RaggedLayout::Contiguous,
+            Lookup::Indexed(_) => RaggedLayout::Indexed,
+        }
+    }
+    /// Name of the dimension with one entry per feature
+    pub fn instance_dimension(&self) -> &str {
+        &self.instance_dimension
+    }
+    /// Name of the dimension all elements are stored along
+    pub fn sample_dimension(&self) -> &str {
+        &self.sample_dimension
+    }
+    /// Name of the count or index variable
+    pub fn variable(&self) -> &str {
+        &self.variable
+    }
+    /// Number of features
+    pub fn len(&self) -> usize {
+        match &self.lookup {
+            Lookup::Contiguous(x) => x.len(),
+            Lookup::Indexed(x) => x.len(),
+        }
+    }
+    /// There are no features
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Number of elements of the given feature
+    pub fn feature_len(&self, feature: usize) -> Option<usize> {
+        match &self.lookup {
+            Lookup::Contiguous(x) => x.get(feature).map(ExactSizeIterator::len),
+            Lookup::Indexed(x) => x.get(feature).map(Vec::len),
+        }
+    }
+
+    /// Number of values per element, the product of all
+    /// dimensions following the sample dimension
+    fn element_size(&self, var: &Variable) -> error::Result<usize> {
+        match var.dimensions().split_first() {
+            Some((first, rest)) if first.name() == self.sample_dimension => {
+                Ok(rest.iter().map(crate::Dimension::len).product())
+            }
+            _ => Err(format!(
+                "variable {} does not have {} as the first dimension",
+                var.name(),
+                self.sample_dimension
+            )
+            .into()),
+        }
+    }
+
+    /// Get the values of a data variable belonging to a single feature
+    ///
+    /// Dimensions following the sample dimension are flattened
+    pub fn feature<T: NcTypeDescriptor + Copy>(
+        &self,
+        var: &Variable,
+        feature: usize,
+    ) -> error::Result<Vec<T>> {
+        let element_size = self.element_size(var)?;
+        match &self.lookup {
+            Lookup::Contiguous(ranges) => {
+                let range = ranges.get(feature).ok_or(error::Error::IndexMismatch)?;
+                if range.is_empty() {
+                    return Ok(Vec::new());
+                }
+                var.get_values(sample_extents(var, range))
+            }
+            Lookup::Indexed(elements) => {
+                let elements = elements.get(feature).ok_or(error::Error::IndexMismatch)?;
+                // Elements are in increasing order, only the span holding them is read
+                let (Some(&first), Some(&last)) = (elements.first(), elements.last()) else {
+                    return Ok(Vec::new());
+                };
+                let values = var.get_values::<T, _>(sample_extents(var, &(first..last + 1)))?;
+                let elements = elements.iter().map(|i| i - first).collect::<Vec<_>>();
+                Ok(gather(&values, &elements, element_size))
+            }
+        }
+    }
+
+    /// Get the values of a data variable split into features
+    ///
+    /// Dimensions following the sample dimension are flattened
+    pub fn features<T: NcTypeDescriptor + Copy>(
+        &self,
+        var: &Variable,
+    ) -> error::Result<Vec<Vec<T>>> {
+        let element_size = self.element_size(var)?;
+        let values = var.get_values::<T, _>(..)?;
+        match &self.lookup {
+            Lookup::Contiguous(ranges) => ranges
+                .iter()
+                .map(|range| {
+                    values
+                        .get(range.start * element_size..range.end * element_size)
+                        .map(<[T]>::to_vec)
+                        .ok_or_else(|| {
+                            format!("{} exceeds the sample dimension", self.variable).into()
+                        })
+                })
+                .collect(),
+            Lookup::Indexed(elements) => Ok(elements
+                .iter()
+                .map(|elements| gather(&values, elements, element_size))
+                .collect()),
+        }
+    }
+}
+
+/// The elements `range` along the sample dimension, the first dimension of `var`
+fn sample_extents(var: &Variable, range: &Range<usize>) -> Extents {
+    let mut extents = vec![Extent::SliceCount {
+        start: range.start,
+        count: range.len(),
+        stride: 1,
+    }];
+    extents.resize(
+        var.dimensions().len(),
+        Extent::Slice {
+            start: 0,
+            stride: 1,
+        },
+    );
+    Extents::from(extents)
+}
+
+fn gather<T: Copy>(values: &[T], elements: &[usize], element_size: usize) -> Vec<T> {
+    elements
+        .iter()
+        .flat_map(|&i| &values[i * element_size..(i + 1) * element_size])
+        .copied()
+        .collect()
+}
+
+/// Writes data variables as ragged arrays
+///
+/// The first call to [`write`](Self::write) defines the instance and sample
+/// dimensions, the count or index variable and the `featureType` attribute.
+/// Further calls add more data variables with the same feature lengths.
+/// Variables with one value per feature (e.g. station names) can be added
+/// along the instance dimension using the ordinary variable methods.
+///
+/// # Example
+/// ```rust,no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use netcdf::cf::{FeatureType, RaggedArrayWriter, RaggedLayout};
+/// let mut file = netcdf::create("stations.nc")?;
+/// let writer = RaggedArrayWriter::new(FeatureType::TimeSeries, RaggedLayout::Contiguous)
+///     .instance_dimension("station");
+/// let temperature = vec![vec![280.0_f32, 281.5], vec![290.0]];
+/// writer.write(&mut file, "temperature", &temperature)?
+///     .put_attribute("units", "K")?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RaggedArrayWriter<'a> {
+    feature_type: FeatureType,
+    layout: RaggedLayout,
+    instance_dimension: &'a str,
+    sample_dimension: &'a str,
+    variable: &'a str,
+}
+
+impl<'a> RaggedArrayWriter<'a> {
+    /// Create a writer using the dimensions `instance` and `obs`, and the
+    /// variable `row_size` (contiguous) or `instance_index` (indexed)
+    pub fn new(feature_type: FeatureType, layout: RaggedLayout) -> Self {
+        Self {
+            feature_type,
+            layout,
+            instance_dimension: "instance",
+            sample_dimension: "obs",
+            variable: match layout {
+                RaggedLayout::Contiguous => "row_size",
+                RaggedLayout::Indexed => "instance_index",
+            },
+        }
+    }
+    /// Name of the dimension with one entry per feature
+    #[must_use]
+    pub fn instance_dimension(mut self, name: &'a str) -> Self {
+        self.instance_dimension = name;
+        self
+    }
+    /// Name of the dimension all elements are stored along
+    #[must_use]
+    pub fn sample_dimension(mut self, name: &'a str) -> Self {
+        self.sample_dimension = name;
+        self
+    }
+    /// Name of the count or index variable
+    #[must_use]
+    pub fn variable(mut self, name: &'a str) -> Self {
+        self.variable = name;
+        self
+    }
+
+    /// The values of the count or index variable for the given feature lengths
+    fn layout_values(&self, lengths: &[usize]) -> error::Result<Vec<i32>> {
+        let values: Result<Vec<i32>, _> = match self.layout {
+            RaggedLayout::Contiguous => lengths.iter().map(|&len| i32::try_from(len)).collect(),
+            RaggedLayout::Indexed => lengths
+                .iter()
+                .enumerate()
+                .flat_map(|(instance, &len)| std::iter::repeat(instance).take(len))
+                .map(i32::try_from)
+                .collect(),
+        };
+        values.map_err(error::Error::Conversion)
+    }
+
+    /// Define the ragged array in the file, or verify that an existing
+    /// definition matches the feature lengths
+    fn define(&self, file: &mut FileMut, lengths: &[usize]) -> error::Result<()> {
+        let total = lengths.iter().sum();
+        let values = self.layout_values(lengths)?;
+
+        if file.dimension(self.instance_dimension).is_some() {
+            let existing = file
+                .variable(self.variable)
+                .ok_or_else(|| error::Error::NotFound(format!("variable {}", self.variable)))?
+                .get_values::<i32, _>(..)?;
+            if file.dimension_len(self.instance_dimension) != Some(lengths.len())
+                || file.dimension_len(self.sample_dimension) != Some(total)
+                || existing != values
+            {
+                return Err("features do not match the existing ragged array".into());
+            }
+            return Ok(());
+        }
+
+        // Dimensions of length zero would be created as unlimited dimensions
+        if total == 0 {
+            return Err("a ragged array must have at least one element".into());
+        }
+        file.add_dimension(self.instance_dimension, lengths.len())?;
+        file.add_dimension(self.sample_dimension, total)?;
+        let mut var = match self.layout {
+            RaggedLayout::Contiguous => {
+                let mut var =
+                    file.add_variable::<i32>(self.variable, &[self.instance_dimension])?;
+                var.put_attribute("sample_dimension", self.sample_dimension)?;
+                var.put_attribute("long_name", "number of observations per feature")?;
+                var
+            }
+            RaggedLayout::Indexed => {
+                let mut var = file.add_variable::<i32>(self.variable, &[self.sample_dimension])?;
+                var.put_attribute("instance_dimension", self.instance_dimension)?;
+                var.put_attribute("long_name", "index of the feature of each observation")?;
+                var
+            }
+        };
+        var.put_values(&values, ..)?;
+        if file.attribute("featureType").is_none() {
+            file.add_attribute("featureType", self.feature_type.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Write a data variable along the sample dimension, with the
+    /// values of feature `i` given by `features[i]`
+    ///
+    /// The features written first must have at least one element
+    pub fn write<'f, T: NcTypeDescriptor + Copy>(
+        &self,
+        file: &'f mut FileMut,
+        name: &str,
+        features: &[Vec<T>],
+    ) -> error::Result<VariableMut<'f>> {
+        let lengths = features.iter().map(Vec::len).collect::<Vec<_>>();
+        self.define(file, &lengths)?;
+
+        let values = features.concat();
+        let mut var = file.add_variable::<T>(name, &[self.sample_dimension])?;
+        if !values.is_empty() {
+            var.put_values(&values, ..)?;
+        }
+        Ok(var)
+    }
+}
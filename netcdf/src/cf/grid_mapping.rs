@@ -0,0 +1,1159 @@
+//! Typed description of CF `grid_mapping` variables
+//!
+//! A data variable on a projected grid refers to a grid mapping variable
+//! through the `grid_mapping` attribute. The attributes of the grid mapping
+//! variable describe the coordinate reference system (CF Appendix F).
+
+use std::fmt::Write;
+
+use crate::error;
+use crate::variable::{Variable, VariableMut};
+
+/// Figure of the earth
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Ellipsoid {
+    /// Spherical earth, given by `earth_radius`
+    Sphere {
+        /// Radius in metres
+        earth_radius: f64,
+    },
+    /// Ellipsoid given by `semi_major_axis` and `inverse_flattening`
+    InverseFlattening {
+        /// Equatorial radius in metres
+        semi_major_axis: f64,
+        /// Inverse flattening, `a / (a - b)`
+        inverse_flattening: f64,
+    },
+    /// Ellipsoid given by `semi_major_axis` and `semi_minor_axis`
+    SemiMinorAxis {
+        /// Equatorial radius in metres
+        semi_major_axis: f64,
+        /// Polar radius in metres
+        semi_minor_axis: f64,
+    },
+}
+
+impl Ellipsoid {
+    /// The WGS 84 ellipsoid
+    pub const WGS84: Self = Self::InverseFlattening {
+        semi_major_axis: 6_378_137.0,
+        inverse_flattening: 298.257_223_563,
+    };
+
+    /// Equatorial radius in metres
+    pub fn semi_major_axis(&self) -> f64 {
+        match *self {
+            Self::Sphere { earth_radius } => earth_radius,
+            Self::InverseFlattening {
+                semi_major_axis, ..
+            }
+            | Self::SemiMinorAxis {
+                semi_major_axis, ..
+            } => semi_major_axis,
+        }
+    }
+
+    /// Inverse flattening, zero for a sphere
+    pub fn inverse_flattening(&self) -> f64 {
+        match *self {
+            Self::Sphere { .. } => 0.0,
+            Self::InverseFlattening {
+                inverse_flattening, ..
+            } => inverse_flattening,
+            Self::SemiMinorAxis {
+                semi_major_axis,
+                semi_minor_axis,
+            } => {
+                if semi_major_axis == semi_minor_axis {
+                    0.0
+                } else {
+                    semi_major_axis / (semi_major_axis - semi_minor_axis)
+                }
+            }
+        }
+    }
+
+    fn from_variable(var: &Variable) -> error::Result<Option<Self>> {
+        let earth_radius = optional(var, "earth_radius")?;
+        let semi_major_axis = optional(var, "semi_major_axis")?;
+        let semi_minor_axis = optional(var, "semi_minor_axis")?;
+        let inverse_flattening = optional(var, "inverse_flattening")?;
+
+        Ok(match (earth_radius, semi_major_axis) {
+            (Some(earth_radius), _) => Some(Self::Sphere { earth_radius }),
+            (None, Some(semi_major_axis)) => match (inverse_flattening, semi_minor_axis) {
+                (Some(inverse_flattening), _) if inverse_flattening != 0.0 => {
+                    Some(Self::InverseFlattening {
+                        semi_major_axis,
+                        inverse_flattening,
+                    })
+                }
+                (_, Some(semi_minor_axis)) => Some(Self::SemiMinorAxis {
+                    semi_major_axis,
+                    semi_minor_axis,
+                }),
+                _ => Some(Self::Sphere {
+                    earth_radius: semi_major_axis,
+                }),
+            },
+            (None, None) => None,
+        })
+    }
+
+    fn put_attributes(&self, var: &mut VariableMut) -> error::Result<()> {
+        match *self {
+            Self::Sphere { earth_radius } => {
+                var.put_attribute("earth_radius", earth_radius)?;
+            }
+            Self::InverseFlattening {
+                semi_major_axis,
+                inverse_flattening,
+            } => {
+                var.put_attribute("semi_major_axis", semi_major_axis)?;
+                var.put_attribute("inverse_flattening", inverse_flattening)?;
+            }
+            Self::SemiMinorAxis {
+                semi_major_axis,
+                semi_minor_axis,
+            } => {
+                var.put_attribute("semi_major_axis", semi_major_axis)?;
+                var.put_attribute("semi_minor_axis", semi_minor_axis)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn to_proj(self) -> String {
+        match self {
+            Self::Sphere { earth_radius } => format!(" +R={earth_radius}"),
+            Self::InverseFlattening {
+                semi_major_axis,
+                inverse_flattening,
+            } => format!(" +a={semi_major_axis} +rf={inverse_flattening}"),
+            Self::SemiMinorAxis {
+                semi_major_axis,
+                semi_minor_axis,
+            } => format!(" +a={semi_major_axis} +b={semi_minor_axis}"),
+        }
+    }
+}
+
+/// Where a conformal projection has unit scale
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TrueScale {
+    /// Latitude where the scale is true (`standard_parallel`)
+    StandardParallel(f64),
+    /// Scale factor at the projection origin (`scale_factor_at_projection_origin`)
+    ScaleFactor(f64),
+}
+
+/// Axis swept by a geostationary imager
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SweepAxis {
+    /// Sweeps along x, as used by GOES
+    X,
+    /// Sweeps along y, as used by Meteosat
+    Y,
+}
+
+/// A coordinate reference system as described by a CF grid mapping variable
+///
+/// Lengths are in metres and angles in degrees.
+/// `ellipsoid` is `None` when the figure of the earth is not specified,
+/// in which case [`to_proj`](Self::to_proj) and [`to_wkt2`](Self::to_wkt2)
+/// assume WGS 84.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum GridMapping {
+    /// `latitude_longitude`
+    LatitudeLongitude { ellipsoid: Option<Ellipsoid> },
+    /// `rotated_latitude_longitude`
+    RotatedLatitudeLongitude {
+        grid_north_pole_latitude: f64,
+        grid_north_pole_longitude: f64,
+        north_pole_grid_longitude: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+    /// `lambert_conformal_conic` with one or two standard parallels
+    LambertConformalConic {
+        standard_parallel: (f64, Option<f64>),
+        longitude_of_central_meridian: f64,
+        latitude_of_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+    /// `albers_conical_equal_area` with one or two standard parallels
+    AlbersConicalEqualArea {
+        standard_parallel: (f64, Option<f64>),
+        longitude_of_central_meridian: f64,
+        latitude_of_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+    /// `polar_stereographic`
+    PolarStereographic {
+        straight_vertical_longitude_from_pole: f64,
+        /// Either `90` or `-90`
+        latitude_of_projection_origin: f64,
+        scale: TrueScale,
+        false_easting: f64,
+        false_northing: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+    /// `stereographic`
+    Stereographic {
+        longitude_of_projection_origin: f64,
+        latitude_of_projection_origin: f64,
+        scale_factor_at_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+    /// `transverse_mercator`
+    TransverseMercator {
+        scale_factor_at_central_meridian: f64,
+        longitude_of_central_meridian: f64,
+        latitude_of_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+    /// `mercator`
+    Mercator {
+        longitude_of_projection_origin: f64,
+        scale: TrueScale,
+        false_easting: f64,
+        false_northing: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+    /// `lambert_azimuthal_equal_area`
+    LambertAzimuthalEqualArea {
+        longitude_of_projection_origin: f64,
+        latitude_of_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+    /// `orthographic`
+    Orthographic {
+        longitude_of_projection_origin: f64,
+        latitude_of_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+    /// `geostationary`
+    Geostationary {
+        longitude_of_projection_origin: f64,
+        perspective_point_height: f64,
+        sweep_angle_axis: SweepAxis,
+        false_easting: f64,
+        false_northing: f64,
+        ellipsoid: Option<Ellipsoid>,
+    },
+}
+
+fn optional(var: &Variable, name: &str) -> error::Result<Option<f64>> {
+    var.attribute_value(name)
+        .transpose()?
+        .map(f64::try_from)
+        .transpose()
+}
+
+fn required(var: &Variable, name: &str) -> error::Result<f64> {
+    optional(var, name)?.ok_or_else(|| error::Error::NotFound(format!("attribute {name}")))
+}
+
+fn text(var: &Variable, name: &str) -> error::Result<Option<String>> {
+    var.attribute_value(name)
+        .transpose()?
+        .map(String::try_from)
+        .transpose()
+}
+
+fn standard_parallel(var: &Variable) -> error::Result<Option<Vec<f64>>> {
+    var.attribute_value("standard_parallel")
+        .transpose()?
+        .map(|value| {
+            super::attribute_numbers(value)
+                .ok_or_else(|| "standard_parallel must be numeric".into())
+        })
+        .transpose()
+}
+
+fn two_parallels(var: &Variable) -> error::Result<(f64, Option<f64>)> {
+    match standard_parallel(var)?.as_deref() {
+        Some(&[first]) => Ok((first, None)),
+        Some(&[first, second]) => Ok((first, Some(second))),
+        Some(_) => Err("standard_parallel must have one or two values".into()),
+        None => Err(error::Error::NotFound("attribute standard_parallel".into())),
+    }
+}
+
+fn true_scale(var: &Variable) -> error::Result<TrueScale> {
+    if let Some(parallel) = standard_parallel(var)? {
+        match parallel.as_slice() {
+            &[parallel] => Ok(TrueScale::StandardParallel(parallel)),
+            _ => Err("standard_parallel must have a single value".into()),
+        }
+    } else if let Some(scale) = optional(var, "scale_factor_at_projection_origin")? {
+        Ok(TrueScale::ScaleFactor(scale))
+    } else {
+        Err(error::Error::NotFound(
+            "attribute standard_parallel or scale_factor_at_projection_origin".into(),
+        ))
+    }
+}
+
+impl GridMapping {
+    /// Parse the attributes of a grid mapping variable
+    ///
+    /// # Errors
+    ///
+    /// The `grid_mapping_name` is not supported or a
+    /// required attribute is missing
+    pub fn from_variable(var: &Variable) -> error::Result<Self> {
+        let name = text(var, "grid_mapping_name")?
+            .ok_or_else(|| error::Error::NotFound("attribute grid_mapping_name".into()))?;
+        let ellipsoid = Ellipsoid::from_variable(var)?;
+        let false_easting = optional(var, "false_easting")?.unwrap_or(0.0);
+        let false_northing = optional(var, "false_northing")?.unwrap_or(0.0);
+
+        Ok(match name.as_str() {
+            "latitude_longitude" => Self::LatitudeLongitude { ellipsoid },
+            "rotated_latitude_longitude" => Self::RotatedLatitudeLongitude {
+                grid_north_pole_latitude: required(var, "grid_north_pole_latitude")?,
+                grid_north_pole_longitude: required(var, "grid_north_pole_longitude")?,
+                north_pole_grid_longitude: optional(var, "north_pole_grid_longitude")?
+                    .unwrap_or(0.0),
+                ellipsoid,
+            },
+            "lambert_conformal_conic" => Self::LambertConformalConic {
+                standard_parallel: two_parallels(var)?,
+                longitude_of_central_meridian: required(var, "longitude_of_central_meridian")?,
+                latitude_of_projection_origin: required(var, "latitude_of_projection_origin")?,
+                false_easting,
+                false_northing,
+                ellipsoid,
+            },
+            "albers_conical_equal_area" => Self::AlbersConicalEqualArea {
+                standard_parallel: two_parallels(var)?,
+                longitude_of_central_meridian: required(var, "longitude_of_central_meridian")?,
+                latitude_of_projection_origin: required(var, "latitude_of_projection_origin")?,
+                false_easting,
+                false_northing,
+                ellipsoid,
+            },
+            "polar_stereographic" => Self::PolarStereographic {
+                straight_vertical_longitude_from_pole: required(
+                    var,
+                    "straight_vertical_longitude_from_pole",
+                )?,
+                latitude_of_projection_origin: required(var, "latitude_of_projection_origin")?,
+                scale: true_scale(var)?,
+                false_easting,
+                false_northing,
+                ellipsoid,
+            },
+            "stereographic" => Self::Stereographic {
+                longitude_of_projection_origin: required(var, "longitude_of_projection_origin")?,
+                latitude_of_projection_origin: required(var, "latitude_of_projection_origin")?,
+                scale_factor_at_projection_origin: optional(
+                    var,
+                    "scale_factor_at_projection_origin",
+                )?
+                .unwrap_or(1.0),
+                false_easting,
+                false_northing,
+                ellipsoid,
+            },
+            "transverse_mercator" => Self::TransverseMercator {
+                scale_factor_at_central_meridian: required(
+                    var,
+                    "scale_factor_at_central_meridian",
+                )?,
+                longitude_of_central_meridian: required(var, "longitude_of_central_meridian")?,
+                latitude_of_projection_origin: required(var, "latitude_of_projection_origin")?,
+                false_easting,
+                false_northing,
+                ellipsoid,
+            },
+            "mercator" => Self::Mercator {
+                longitude_of_projection_origin: required(var, "longitude_of_projection_origin")?,
+                scale: true_scale(var)?,
+                false_easting,
+                false_northing,
+                ellipsoid,
+            },
+            "lambert_azimuthal_equal_area" => Self::LambertAzimuthalEqualArea {
+                longitude_of_projection_origin: required(var, "longitude_of_projection_origin")?,
+                latitude_of_projection_origin: required(var, "latitude_of_projection_origin")?,
+                false_easting,
+                false_northing,
+                ellipsoid,
+            },
+            "orthographic" => Self::Orthographic {
+                longitude_of_projection_origin: required(var, "longitude_of_projection_origin")?,
+                latitude_of_projection_origin: required(var, "latitude_of_projection_origin")?,
+                false_easting,
+                false_northing,
+                ellipsoid,
+            },
+            "geostationary" => {
+                let sweep_angle_axis = match (
+                    text(var, "sweep_angle_axis")?.as_deref(),
+                    text(var, "fixed_angle_axis")?.as_deref(),
+                ) {
+                    (Some("x"), _) | (None, Some("y")) => SweepAxis::X,
+                    (Some("y"), _) | (None, Some("x")) => SweepAxis::Y,
+                    _ => return Err("geostationary requires sweep_angle_axis of x or y".into()),
+                };
+                Self::Geostationary {
+                    longitude_of_projection_origin: required(
+                        var,
+                        "longitude_of_projection_origin",
+                    )?,
+                    perspective_point_height: required(var, "perspective_point_height")?,
+                    sweep_angle_axis,
+                    false_easting,
+                    false_northing,
+                    ellipsoid,
+                }
+            }
+            name => return Err(format!("grid_mapping_name {name} is not supported").into()),
+        })
+    }
+
+    /// Get the grid mapping referenced by the `grid_mapping` attribute of
+    /// a data variable. The grid mapping variable is searched for in the
+    /// group of the data variable and its ancestors.
+    ///
+    /// Returns `None` if the variable has no `grid_mapping` attribute.
+    /// For the extended form (`"crs: x y"`) the first mapping is used.
+    pub fn for_variable(var: &Variable) -> error::Result<Option<Self>> {
+        let Some(grid_mapping) = text(var, "grid_mapping")? else {
+            return Ok(None);
+        };
+        let name = grid_mapping
+            .split_whitespace()
+            .next()
+            .map(|name| name.trim_end_matches(':'))
+            .ok_or_else(|| error::Error::Str("grid_mapping attribute is empty".into()))?;
+        let mapping = super::find_variable(var.ncid, name)?
+            .ok_or_else(|| error::Error::NotFound(format!("grid mapping variable {name}")))?;
+        Self::from_variable(&mapping).map(Some)
+    }
+
+    /// The CF `grid_mapping_name` of this mapping
+    pub fn grid_mapping_name(&self) -> &'static str {
+        match self {
+            Self::LatitudeLongitude { .. } => "latitude_longitude",
+            Self::RotatedLatitudeLongitude { .. } => "rotated_latitude_longitude",
+            Self::LambertConformalConic { .. } => "lambert_conformal_conic",
+            Self::AlbersConicalEqualArea { .. } => "albers_conical_equal_area",
+            Self::PolarStereographic { .. } => "polar_stereographic",
+            Self::Stereographic { .. } => "stereographic",
+            Self::TransverseMercator { .. } => "transverse_mercator",
+            Self::Mercator { .. } => "mercator",
+            Self::LambertAzimuthalEqualArea { .. } => "lambert_azimuthal_equal_area",
+            Self::Orthographic { .. } => "orthographic",
+            Self::Geostationary { .. } => "geostationary",
+        }
+    }
+
+    /// The figure of the earth, if specified
+    pub fn ellipsoid(&self) -> Option<&Ellipsoid> {
+        match self {
+            Self::LatitudeLongitude { ellipsoid }
+            | Self::RotatedLatitudeLongitude { ellipsoid, .. }
+            | Self::LambertConformalConic { ellipsoid, .. }
+            | Self::AlbersConicalEqualArea { ellipsoid, .. }
+            | Self::PolarStereographic { ellipsoid, .. }
+            | Self::Stereographic { ellipsoid, .. }
+            | Self::TransverseMercator { ellipsoid, .. }
+            | Self::Mercator { ellipsoid, .. }
+            | Self::LambertAzimuthalEqualArea { ellipsoid, .. }
+            | Self::Orthographic { ellipsoid, .. }
+            | Self::Geostationary { ellipsoid, .. } => ellipsoid.as_ref(),
+        }
+    }
+
+    fn false_origin(&self) -> Option<(f64, f64)> {
+        match *self {
+            Self::LatitudeLongitude { .. } | Self::RotatedLatitudeLongitude { .. } => None,
+            Self::LambertConformalConic {
+                false_easting,
+                false_northing,
+                ..
+            }
+            | Self::AlbersConicalEqualArea {
+                false_easting,
+                false_northing,
+                ..
+            }
+            | Self::PolarStereographic {
+                false_easting,
+                false_northing,
+                ..
+            }
+            | Self::Stereographic {
+                false_easting,
+                false_northing,
+                ..
+            }
+            | Self::TransverseMercator {
+                false_easting,
+                false_northing,
+                ..
+            }
+            | Self::Mercator {
+                false_easting,
+                false_northing,
+                ..
+            }
+            | Self::LambertAzimuthalEqualArea {
+                false_easting,
+                false_northing,
+                ..
+            }
+            | Self::Orthographic {
+                false_easting,
+                false_northing,
+                ..
+            }
+            | Self::Geostationary {
+                false_easting,
+                false_northing,
+                ..
+            } => Some((false_easting, false_northing)),
+        }
+    }
+
+    /// Write the attributes describing this mapping to a
+    /// grid mapping variable
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use netcdf::cf::GridMapping;
+    /// let mut file = netcdf::create("projected.nc")?;
+    /// let mut crs = file.add_variable::<i32>("crs", &[])?;
+    /// GridMapping::LatitudeLongitude { ellipsoid: None }.put_attributes(&mut crs)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn put_attributes(&self, var: &mut VariableMut) -> error::Result<()> {
+        var.put_attribute("grid_mapping_name", self.grid_mapping_name())?;
+        match *self {
+            Self::LatitudeLongitude { .. } => {}
+            Self::RotatedLatitudeLongitude {
+                grid_north_pole_latitude,
+                grid_north_pole_longitude,
+                north_pole_grid_longitude,
+                ..
+            } => {
+                var.put_attribute("grid_north_pole_latitude", grid_north_pole_latitude)?;
+                var.put_attribute("grid_north_pole_longitude", grid_north_pole_longitude)?;
+                var.put_attribute("north_pole_grid_longitude", north_pole_grid_longitude)?;
+            }
+            Self::LambertConformalConic {
+                standard_parallel,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                ..
+            }
+            | Self::AlbersConicalEqualArea {
+                standard_parallel,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                ..
+            } => {
+                match standard_parallel {
+                    (first, None) => var.put_attribute("standard_parallel", first)?,
+                    (first, Some(second)) => {
+                        var.put_attribute("standard_parallel", vec![first, second])?
+                    }
+                };
+                var.put_attribute(
+                    "longitude_of_central_meridian",
+                    longitude_of_central_meridian,
+                )?;
+                var.put_attribute(
+                    "latitude_of_projection_origin",
+                    latitude_of_projection_origin,
+                )?;
+            }
+            Self::PolarStereographic {
+                straight_vertical_longitude_from_pole,
+                latitude_of_projection_origin,
+                scale,
+                ..
+            } => {
+                var.put_attribute(
+                    "straight_vertical_longitude_from_pole",
+                    straight_vertical_longitude_from_pole,
+                )?;
+                var.put_attribute(
+                    "latitude_of_projection_origin",
+                    latitude_of_projection_origin,
+                )?;
+                put_true_scale(var, scale)?;
+            }
+            Self::Stereographic {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                scale_factor_at_projection_origin,
+                ..
+            } => {
+                var.put_attribute(
+                    "longitude_of_projection_origin",
+                    longitude_of_projection_origin,
+                )?;
+                var.put_attribute(
+                    "latitude_of_projection_origin",
+                    latitude_of_projection_origin,
+                )?;
+                var.put_attribute(
+                    "scale_factor_at_projection_origin",
+                    scale_factor_at_projection_origin,
+                )?;
+            }
+            Self::TransverseMercator {
+                scale_factor_at_central_meridian,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                ..
+            } => {
+                var.put_attribute(
+                    "scale_factor_at_central_meridian",
+                    scale_factor_at_central_meridian,
+                )?;
+                var.put_attribute(
+                    "longitude_of_central_meridian",
+                    longitude_of_central_meridian,
+                )?;
+                var.put_attribute(
+                    "latitude_of_projection_origin",
+                    latitude_of_projection_origin,
+                )?;
+            }
+            Self::Mercator {
+                longitude_of_projection_origin,
+                scale,
+                ..
+            } => {
+                var.put_attribute(
+                    "longitude_of_projection_origin",
+                    longitude_of_projection_origin,
+                )?;
+                put_true_scale(var, scale)?;
+            }
+            Self::LambertAzimuthalEqualArea {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                ..
+            }
+            | Self::Orthographic {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                ..
+            } => {
+                var.put_attribute(
+                    "longitude_of_projection_origin",
+                    longitude_of_projection_origin,
+                )?;
+                var.put_attribute(
+                    "latitude_of_projection_origin",
+                    latitude_of_projection_origin,
+                )?;
+            }
+            Self::Geostationary {
+                longitude_of_projection_origin,
+                perspective_point_height,
+                sweep_angle_axis,
+                ..
+            } => {
+                var.put_attribute(
+                    "longitude_of_projection_origin",
+                    longitude_of_projection_origin,
+                )?;
+                var.put_attribute("latitude_of_projection_origin", 0.0)?;
+                var.put_attribute("perspective_point_height", perspective_point_height)?;
+                let axis = match sweep_angle_axis {
+                    SweepAxis::X => "x",
+                    SweepAxis::Y => "y",
+                };
+                var.put_attribute("sweep_angle_axis", axis)?;
+            }
+        }
+        if let Some((false_easting, false_northing)) = self.false_origin() {
+            var.put_attribute("false_easting", false_easting)?;
+            var.put_attribute("false_northing", false_northing)?;
+        }
+        if let Some(ellipsoid) = self.ellipsoid() {
+            ellipsoid.put_attributes(var)?;
+        }
+        Ok(())
+    }
+
+    /// Equivalent PROJ string, e.g. `+proj=lcc +lat_1=25 ...`
+    pub fn to_proj(&self) -> String {
+        let mut proj = match *self {
+            Self::LatitudeLongitude { .. } => "+proj=longlat".to_owned(),
+            Self::RotatedLatitudeLongitude {
+                grid_north_pole_latitude,
+                grid_north_pole_longitude,
+                north_pole_grid_longitude,
+                ..
+            } => format!(
+                "+proj=ob_tran +o_proj=longlat +o_lon_p={north_pole_grid_longitude} +o_lat_p={grid_north_pole_latitude} +lon_0={}",
+                normalise_longitude(180.0 + grid_north_pole_longitude)
+            ),
+            Self::LambertConformalConic {
+                standard_parallel: (lat_1, lat_2),
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                ..
+            } => format!(
+                "+proj=lcc +lat_1={lat_1} +lat_2={} +lat_0={latitude_of_projection_origin} +lon_0={longitude_of_central_meridian}",
+                lat_2.unwrap_or(lat_1)
+            ),
+            Self::AlbersConicalEqualArea {
+                standard_parallel: (lat_1, lat_2),
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                ..
+            } => format!(
+                "+proj=aea +lat_1={lat_1} +lat_2={} +lat_0={latitude_of_projection_origin} +lon_0={longitude_of_central_meridian}",
+                lat_2.unwrap_or(lat_1)
+            ),
+            Self::PolarStereographic {
+                straight_vertical_longitude_from_pole,
+                latitude_of_projection_origin,
+                scale,
+                ..
+            } => {
+                let scale = match scale {
+                    TrueScale::StandardParallel(lat) => format!("+lat_ts={lat}"),
+                    TrueScale::ScaleFactor(k) => format!("+k_0={k}"),
+                };
+                format!(
+                    "+proj=stere +lat_0={latitude_of_projection_origin} {scale} +lon_0={straight_vertical_longitude_from_pole}"
+                )
+            }
+            Self::Stereographic {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                scale_factor_at_projection_origin,
+                ..
+            } => format!(
+                "+proj=stere +lat_0={latitude_of_projection_origin} +lon_0={longitude_of_projection_origin} +k_0={scale_factor_at_projection_origin}"
+            ),
+            Self::TransverseMercator {
+                scale_factor_at_central_meridian,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                ..
+            } => format!(
+                "+proj=tmerc +lat_0={latitude_of_projection_origin} +lon_0={longitude_of_central_meridian} +k_0={scale_factor_at_central_meridian}"
+            ),
+            Self::Mercator {
+                longitude_of_projection_origin,
+                scale,
+                ..
+            } => {
+                let scale = match scale {
+                    TrueScale::StandardParallel(lat) => format!("+lat_ts={lat}"),
+                    TrueScale::ScaleFactor(k) => format!("+k_0={k}"),
+                };
+                format!("+proj=merc +lon_0={longitude_of_projection_origin} {scale}")
+            }
+            Self::LambertAzimuthalEqualArea {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                ..
+            } => format!(
+                "+proj=laea +lat_0={latitude_of_projection_origin} +lon_0={longitude_of_projection_origin}"
+            ),
+            Self::Orthographic {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                ..
+            } => format!(
+                "+proj=ortho +lat_0={latitude_of_projection_origin} +lon_0={longitude_of_projection_origin}"
+            ),
+            Self::Geostationary {
+                longitude_of_projection_origin,
+                perspective_point_height,
+                sweep_angle_axis,
+                ..
+            } => {
+                let sweep = match sweep_angle_axis {
+                    SweepAxis::X => "x",
+                    SweepAxis::Y => "y",
+                };
+                format!(
+                    "+proj=geos +h={perspective_point_height} +lon_0={longitude_of_projection_origin} +sweep={sweep}"
+                )
+            }
+        };
+        if let Some((x_0, y_0)) = self.false_origin() {
+            write!(proj, " +x_0={x_0} +y_0={y_0}").unwrap();
+        }
+        match self.ellipsoid() {
+            Some(ellipsoid) => proj.push_str(&ellipsoid.to_proj()),
+            None => proj.push_str(" +ellps=WGS84"),
+        }
+        if self.false_origin().is_some() {
+            proj.push_str(" +units=m");
+        }
+        proj.push_str(" +no_defs");
+        proj
+    }
+
+    /// Equivalent OGC WKT2 (ISO 19162:2019) description
+    pub fn to_wkt2(&self) -> String {
+        let ellipsoid = self.ellipsoid().copied().unwrap_or(Ellipsoid::WGS84);
+        let datum = format!(
+            "DATUM[\"unknown\",ELLIPSOID[\"unknown\",{},{},LENGTHUNIT[\"metre\",1]]],PRIMEM[\"Greenwich\",0,{DEGREE}]",
+            ellipsoid.semi_major_axis(),
+            ellipsoid.inverse_flattening(),
+        );
+        let geographic_cs = format!(
+            "CS[ellipsoidal,2],AXIS[\"latitude\",north,ORDER[1],{DEGREE}],AXIS[\"longitude\",east,ORDER[2],{DEGREE}]"
+        );
+
+        let (method, parameters) = match *self {
+            Self::LatitudeLongitude { .. } => {
+                return format!("GEOGCRS[\"unknown\",{datum},{geographic_cs}]");
+            }
+            Self::RotatedLatitudeLongitude {
+                grid_north_pole_latitude,
+                grid_north_pole_longitude,
+                north_pole_grid_longitude,
+                ..
+            } => {
+                let parameters = [
+                    ("o_lon_p", north_pole_grid_longitude),
+                    ("o_lat_p", grid_north_pole_latitude),
+                    (
+                        "lon_0",
+                        normalise_longitude(180.0 + grid_north_pole_longitude),
+                    ),
+                ]
+                .iter()
+                .map(|(name, value)| format!("PARAMETER[\"{name}\",{value},{DEGREE}]"))
+                .collect::<Vec<_>>()
+                .join(",");
+                return format!(
+                    "DERIVEDGEOGCRS[\"unknown\",BASEGEOGCRS[\"unknown\",{datum}],DERIVINGCONVERSION[\"unknown\",METHOD[\"PROJ ob_tran o_proj=longlat\"],{parameters}],{geographic_cs}]"
+                );
+            }
+            Self::LambertConformalConic {
+                standard_parallel: (lat_1, lat_2),
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+                ..
+            } => (
+                method("Lambert Conic Conformal (2SP)", 9802),
+                vec![
+                    angle(
+                        "Latitude of false origin",
+                        latitude_of_projection_origin,
+                        8821,
+                    ),
+                    angle(
+                        "Longitude of false origin",
+                        longitude_of_central_meridian,
+                        8822,
+                    ),
+                    angle("Latitude of 1st standard parallel", lat_1, 8823),
+                    angle(
+                        "Latitude of 2nd standard parallel",
+                        lat_2.unwrap_or(lat_1),
+                        8824,
+                    ),
+                    length("Easting at false origin", false_easting, 8826),
+                    length("Northing at false origin", false_northing, 8827),
+                ],
+            ),
+            Self::AlbersConicalEqualArea {
+                standard_parallel: (lat_1, lat_2),
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+                ..
+            } => (
+                method("Albers Equal Area", 9822),
+                vec![
+                    angle(
+                        "Latitude of false origin",
+                        latitude_of_projection_origin,
+                        8821,
+                    ),
+                    angle(
+                        "Longitude of false origin",
+                        longitude_of_central_meridian,
+                        8822,
+                    ),
+                    angle("Latitude of 1st standard parallel", lat_1, 8823),
+                    angle(
+                        "Latitude of 2nd standard parallel",
+                        lat_2.unwrap_or(lat_1),
+                        8824,
+                    ),
+                    length("Easting at false origin", false_easting, 8826),
+                    length("Northing at false origin", false_northing, 8827),
+                ],
+            ),
+            Self::PolarStereographic {
+                straight_vertical_longitude_from_pole,
+                latitude_of_projection_origin,
+                scale,
+                false_easting,
+                false_northing,
+                ..
+            } => match scale {
+                TrueScale::ScaleFactor(k) => (
+                    method("Polar Stereographic (variant A)", 9810),
+                    vec![
+                        angle(
+                            "Latitude of natural origin",
+                            latitude_of_projection_origin,
+                            8801,
+                        ),
+                        angle(
+                            "Longitude of natural origin",
+                            straight_vertical_longitude_from_pole,
+                            8802,
+                        ),
+                        scale_factor("Scale factor at natural origin", k, 8805),
+                        length("False easting", false_easting, 8806),
+                        length("False northing", false_northing, 8807),
+                    ],
+                ),
+                TrueScale::StandardParallel(lat) => (
+                    method("Polar Stereographic (variant B)", 9829),
+                    vec![
+                        angle("Latitude of standard parallel", lat, 8832),
+                        angle(
+                            "Longitude of origin",
+                            straight_vertical_longitude_from_pole,
+                            8833,
+                        ),
+                        length("False easting", false_easting, 8806),
+                        length("False northing", false_northing, 8807),
+                    ],
+                ),
+            },
+            Self::Stereographic {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                scale_factor_at_projection_origin,
+                false_easting,
+                false_northing,
+                ..
+            } => (
+                "METHOD[\"Stereographic\"]".to_owned(),
+                vec![
+                    angle(
+                        "Latitude of natural origin",
+                        latitude_of_projection_origin,
+                        8801,
+                    ),
+                    angle(
+                        "Longitude of natural origin",
+                        longitude_of_projection_origin,
+                        8802,
+                    ),
+                    scale_factor(
+                        "Scale factor at natural origin",
+                        scale_factor_at_projection_origin,
+                        8805,
+                    ),
+                    length("False easting", false_easting, 8806),
+                    length("False northing", false_northing, 8807),
+                ],
+            ),
+            Self::TransverseMercator {
+                scale_factor_at_central_meridian,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+                ..
+            } => (
+                method("Transverse Mercator", 9807),
+                vec![
+                    angle(
+                        "Latitude of natural origin",
+                        latitude_of_projection_origin,
+                        8801,
+                    ),
+                    angle(
+                        "Longitude of natural origin",
+                        longitude_of_central_meridian,
+                        8802,
+                    ),
+                    scale_factor(
+                        "Scale factor at natural origin",
+                        scale_factor_at_central_meridian,
+                        8805,
+                    ),
+                    length("False easting", false_easting, 8806),
+                    length("False northing", false_northing, 8807),
+                ],
+            ),
+            Self::Mercator {
+                longitude_of_projection_origin,
+                scale,
+                false_easting,
+                false_northing,
+                ..
+            } => match scale {
+                TrueScale::ScaleFactor(k) => (
+                    method("Mercator (variant A)", 9804),
+                    vec![
+                        angle("Latitude of natural origin", 0.0, 8801),
+                        angle(
+                            "Longitude of natural origin",
+                            longitude_of_projection_origin,
+                            8802,
+                        ),
+                        scale_factor("Scale factor at natural origin", k, 8805),
+                        length("False easting", false_easting, 8806),
+                        length("False northing", false_northing, 8807),
+                    ],
+                ),
+                TrueScale::StandardParallel(lat) => (
+                    method("Mercator (variant B)", 9805),
+                    vec![
+                        angle("Latitude of 1st standard parallel", lat, 8823),
+                        angle(
+                            "Longitude of natural origin",
+                            longitude_of_projection_origin,
+                            8802,
+                        ),
+                        length("False easting", false_easting, 8806),
+                        length("False northing", false_northing, 8807),
+                    ],
+                ),
+            },
+            Self::LambertAzimuthalEqualArea {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+                ..
+            } => (
+                method("Lambert Azimuthal Equal Area", 9820),
+                vec![
+                    angle(
+                        "Latitude of natural origin",
+                        latitude_of_projection_origin,
+                        8801,
+                    ),
+                    angle(
+                        "Longitude of natural origin",
+                        longitude_of_projection_origin,
+                        8802,
+                    ),
+                    length("False easting", false_easting, 8806),
+                    length("False northing", false_northing, 8807),
+                ],
+            ),
+            Self::Orthographic {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+                ..
+            } => (
+                method("Orthographic", 9840),
+                vec![
+                    angle(
+                        "Latitude of natural origin",
+                        latitude_of_projection_origin,
+                        8801,
+                    ),
+                    angle(
+                        "Longitude of natural origin",
+                        longitude_of_projection_origin,
+                        8802,
+                    ),
+                    length("False easting", false_easting, 8806),
+                    length("False northing", false_northing, 8807),
+                ],
+            ),
+            Self::Geostationary {
+                longitude_of_projection_origin,
+                perspective_point_height,
+                sweep_angle_axis,
+                false_easting,
+                false_northing,
+                ..
+            } => {
+                let sweep = match sweep_angle_axis {
+                    SweepAxis::X => "X",
+                    SweepAxis::Y => "Y",
+                };
+                (
+                    format!("METHOD[\"Geostationary Satellite (Sweep {sweep})\"]"),
+                    vec![
+                        angle(
+                            "Longitude of natural origin",
+                            longitude_of_projection_origin,
+                            8802,
+                        ),
+                        format!(
+                            "PARAMETER[\"Satellite Height\",{perspective_point_height},{METRE}]"
+                        ),
+                        length("False easting", false_easting, 8806),
+                        length("False northing", false_northing, 8807),
+                    ],
+                )
+            }
+        };
+
+        format!(
+            "PROJCRS[\"unknown\",BASEGEOGCRS[\"unknown\",{datum}],CONVERSION[\"unknown\",{method},{}],CS[Cartesian,2],AXIS[\"(E)\",east,ORDER[1],{METRE}],AXIS[\"(N)\",north,ORDER[2],{METRE}]]",
+            parameters.join(",")
+        )
+    }
+}
+
+const DEGREE: &str = "ANGLEUNIT[\"degree\",0.0174532925199433]";
+const METRE: &str = "LENGTHUNIT[\"metre\",1]";
+
+fn method(name: &str, epsg: u32) -> String {
+    format!("METHOD[\"{name}\",ID[\"EPSG\",{epsg}]]")
+}
+
+fn angle(name: &str, value: f64, epsg: u32) -> String {
+    format!("PARAMETER[\"{name}\",{value},{DEGREE},ID[\"EPSG\",{epsg}]]")
+}
+
+fn length(name: &str, value: f64, epsg: u32) -> String {
+    format!("PARAMETER[\"{name}\",{value},{METRE},ID[\"EPSG\",{epsg}]]")
+}
+
+fn scale_factor(name: &str, value: f64, epsg: u32) -> String {
+    format!("PARAMETER[\"{name}\",{value},SCALEUNIT[\"unity\",1],ID[\"EPSG\",{epsg}]]")
+}
+
+fn put_true_scale(var: &mut VariableMut, scale: TrueScale) -> error::Result<()> {
+    match scale {
+        TrueScale::StandardParallel(lat) => var.put_attribute("standard_parallel", lat)?,
+        TrueScale::ScaleFactor(k) => var.put_attribute("scale_factor_at_projection_origin", k)?,
+    };
+    Ok(())
+}
+
+/// Wrap a longitude into `[-180, 180)`
+fn normalise_longitude(lon: f64) -> f64 {
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
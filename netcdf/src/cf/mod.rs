@@ -0,0 +1,133 @@
+//! Support for the [CF Conventions](https://cfconventions.org/)
+//!
+//! The Climate and Forecast (CF) conventions describe how metadata
+//! should be attached to variables in a `netCDF` file so that the
+//! contents can be interpreted without additional documentation.
+//!
+//! Use [`check`] or a [`Checker`] to validate a file against
+//! a given version of the conventions:
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let file = netcdf::open("data.nc")?;
+//! let report = netcdf::cf::check(&file, netcdf::cf::CfVersion::V1_8)?;
+//! println!("{report}");
+//! # Ok(()) }
+//! ```
+
+use netcdf_sys::*;
+
+use crate::attribute::AttributeValue;
+use crate::error;
+use crate::variable::Variable;
+
+mod check;
+mod dsg;
+mod grid_mapping;
+#[cfg(feature = "ndarray")]
+mod reduce;
+
+pub use check::{check, Checker, Diagnostic, DiagnosticKind, Report, Severity};
+pub use dsg::{FeatureType, RaggedArray, RaggedArrayWriter, RaggedLayout};
+pub use grid_mapping::{Ellipsoid, GridMapping, SweepAxis, TrueScale};
+#[cfg(feature = "ndarray")]
+pub use reduce::{reduce, Reduction};
+
+/// Version of the CF conventions
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CfVersion {
+    /// CF-1.6
+    V1_6,
+    /// CF-1.7
+    V1_7,
+    /// CF-1.8, introduces support for groups
+    V1_8,
+    /// CF-1.9
+    V1_9,
+    /// CF-1.10
+    V1_10,
+    /// CF-1.11
+    #[default]
+    V1_11,
+}
+
+impl CfVersion {
+    /// The minor version number, e.g. `8` for `CF-1.8`
+    fn minor(self) -> u32 {
+        match self {
+            Self::V1_6 => 6,
+            Self::V1_7 => 7,
+            Self::V1_8 => 8,
+            Self::V1_9 => 9,
+            Self::V1_10 => 10,
+            Self::V1_11 => 11,
+        }
+    }
+}
+
+impl std::fmt::Display for CfVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CF-1.{}", self.minor())
+    }
+}
+
+impl std::str::FromStr for CfVersion {
+    type Err = crate::Error;
+    /// Parses versions on the form `CF-1.8` or `1.8`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let version = s.trim();
+        let version = version.strip_prefix("CF-").unwrap_or(version);
+        Ok(match version {
+            "1.6" => Self::V1_6,
+            "1.7" => Self::V1_7,
+            "1.8" => Self::V1_8,
+            "1.9" => Self::V1_9,
+            "1.10" => Self::V1_10,
+            "1.11" => Self::V1_11,
+            _ => return Err(format!("Unknown CF version {s}").into()),
+        })
+    }
+}
+
+/// Find a variable referenced by name from an attribute in the group `ncid`.
+/// Plain names are searched for in the group and then in the ancestors,
+/// paths are resolved relative to the group or to the root.
+pub(crate) fn find_variable<'g>(ncid: nc_type, name: &str) -> error::Result<Option<Variable<'g>>> {
+    crate::hierarchy::find_referenced_variable(ncid, name, true)
+}
+
+/// Numeric attribute values as `f64`, `None` for text
+pub(crate) fn attribute_numbers(value: AttributeValue) -> Option<Vec<f64>> {
+    Some(match value {
+        AttributeValue::Uchar(x) => vec![x.into()],
+        AttributeValue::Uchars(x) => x.into_iter().map(Into::into).collect(),
+        AttributeValue::Schar(x) => vec![x.into()],
+        AttributeValue::Schars(x) => x.into_iter().map(Into::into).collect(),
+        AttributeValue::Ushort(x) => vec![x.into()],
+        AttributeValue::Ushorts(x) => x.into_iter().map(Into::into).collect(),
+        AttributeValue::Short(x) => vec![x.into()],
+        AttributeValue::Shorts(x) => x.into_iter().map(Into::into).collect(),
+        AttributeValue::Uint(x) => vec![x.into()],
+        AttributeValue::Uints(x) => x.into_iter().map(Into::into).collect(),
+        AttributeValue::Int(x) => vec![x.into()],
+        AttributeValue::Ints(x) => x.into_iter().map(Into::into).collect(),
+        #[allow(clippy::cast_precision_loss)]
+        AttributeValue::Ulonglong(x) => vec![x as f64],
+        #[allow(clippy::cast_precision_loss)]
+        AttributeValue::Ulonglongs(x) => x.into_iter().map(|x| x as f64).collect(),
+        #[allow(clippy::cast_precision_loss)]
+        AttributeValue::Longlong(x) => vec![x as f64],
+        #[allow(clippy::cast_precision_loss)]
+        AttributeValue::Longlongs(x) => x.into_iter().map(|x| x as f64).collect(),
+        AttributeValue::Float(x) => vec![x.into()],
+        AttributeValue::Floats(x) => x.into_iter().map(Into::into).collect(),
+        AttributeValue::Double(x) => vec![x],
+        AttributeValue::Doubles(x) => x,
+        AttributeValue::Str(_)
+        | AttributeValue::Strs(_)
+        | AttributeValue::Chars(_)
+        | AttributeValue::Compound(..)
+        | AttributeValue::Enum(..)
+        | AttributeValue::Opaque(..)
+        | AttributeValue::Vlen(..) => return None,
+    })
+}
@@ -0,0 +1,318 @@
+//! Reductions over a dimension which keep the CF metadata consistent
+
+use ndarray::{ArrayD, Axis};
+
+use super::attribute_numbers;
+use crate::error;
+use crate::file::FileMut;
+use crate::variable::{Variable, VariableMut};
+
+/// Statistic computed by [`reduce`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Reduction {
+    /// Arithmetic mean of the valid values
+    Mean,
+    /// Smallest valid value
+    Minimum,
+    /// Largest valid value
+    Maximum,
+    /// Sum of the valid values
+    Sum,
+}
+
+impl Reduction {
+    /// The method as written in `cell_methods`
+    pub fn cell_method(self) -> &'static str {
+        match self {
+            Self::Mean => "mean",
+            Self::Minimum => "minimum",
+            Self::Maximum => "maximum",
+            Self::Sum => "sum",
+        }
+    }
+
+    /// Reduce the valid values of a lane, `None` if there are none
+    fn apply(self, values: impl Iterator<Item = f64>) -> Option<f64> {
+        let mut count = 0_usize;
+        let mut acc = match self {
+            Self::Mean | Self::Sum => 0.0,
+            Self::Minimum => f64::INFINITY,
+            Self::Maximum => f64::NEG_INFINITY,
+        };
+        for x in values {
+            count += 1;
+            acc = match self {
+                Self::Mean | Self::Sum => acc + x,
+                Self::Minimum => acc.min(x),
+                Self::Maximum => acc.max(x),
+            };
+        }
+        match (self, count) {
+            (_, 0) => None,
+            #[allow(clippy::cast_precision_loss)]
+            (Self::Mean, count) => Some(acc / count as f64),
+            (_, _) => Some(acc),
+        }
+    }
+}
+
+/// Attributes describing valid values in the packed representation
+const VALUE_ATTRIBUTES: &[&str] = &[
+    "_FillValue",
+    "missing_value",
+    "valid_min",
+    "valid_max",
+    "valid_range",
+];
+
+/// Values which are excluded from a reduction
+struct Mask {
+    missing: Vec<f64>,
+    valid_min: f64,
+    valid_max: f64,
+}
+
+impl Mask {
+    fn from_variable(var: &Variable) -> error::Result<Self> {
+        let numbers = |name: &str| -> error::Result<Vec<f64>> {
+            Ok(var
+                .attribute_value(name)
+                .transpose()?
+                .and_then(attribute_numbers)
+                .unwrap_or_default())
+        };
+        let mut missing = numbers("_FillValue")?;
+        missing.extend(numbers("missing_value")?);
+        let (mut valid_min, mut valid_max) = match numbers("valid_range")?.as_slice() {
+            &[min, max] => (min, max),
+            _ => (f64::NEG_INFINITY, f64::INFINITY),
+        };
+        if let Some(&min) = numbers("valid_min")?.first() {
+            valid_min = min;
+        }
+        if let Some(&max) = numbers("valid_max")?.first() {
+            valid_max = max;
+        }
+        Ok(Self {
+            missing,
+            valid_min,
+            valid_max,
+        })
+    }
+
+    fn is_valid(&self, x: f64) -> bool {
+        !x.is_nan() && !self.missing.contains(&x) && x >= self.valid_min && x <= self.valid_max
+    }
+}
+
+/// `scale_factor` and `add_offset` of packed data
+fn packing(var: &Variable) -> error::Result<(f64, f64)> {
+    let number = |name: &str| -> error::Result<Option<f64>> {
+        var.attribute_value(name)
+            .transpose()?
+            .map(f64::try_from)
+            .transpose()
+    };
+    Ok((
+        number("scale_factor")?.unwrap_or(1.0),
+        number("add_offset")?.unwrap_or(0.0),
+    ))
+}
+
+/// Copy attributes, unpacking values and converting them to `f64`
+fn copy_attributes(src: &Variable, dst: &mut VariableMut, skip: &[&str]) -> error::Result<()> {
+    let (scale, offset) = packing(src)?;
+
+    for attr in src.attributes() {
+        let name = attr.name();
+        if skip.contains(&name) || name == "scale_factor" || name == "add_offset" {
+            continue;
+        }
+        let value = attr.value()?;
+        if !VALUE_ATTRIBUTES.contains(&name) {
+            dst.put_attribute(name, value)?;
+            continue;
+        }
+        let Some(numbers) = attribute_numbers(value) else {
+            continue;
+        };
+        // The fill value must stay recognisable, all other values are unpacked
+        let numbers = numbers
+            .into_iter()
+            .map(|x| {
+                if name == "_FillValue" || name == "missing_value" {
+                    x
+                } else {
+                    x * scale + offset
+                }
+            })
+            .collect::<Vec<_>>();
+        match numbers.as_slice() {
+            &[x] => dst.put_attribute(name, x)?,
+            _ => dst.put_attribute(name, numbers)?,
+        };
+    }
+    Ok(())
+}
+
+/// Values of the reduced coordinate and its bounds, computed
+/// from the original bounds or the coordinate values
+fn reduced_coordinate(coordinate: &Variable) -> error::Result<(f64, [f64; 2])> {
+    let bounds = match coordinate.attribute_value("bounds").transpose()? {
+        Some(name) => {
+            let name = String::try_from(name)?;
+            super::find_variable(coordinate.ncid, &name)?
+                .ok_or_else(|| error::Error::NotFound(format!("variable {name}")))?
+                .get_values::<f64, _>(..)?
+        }
+        None => coordinate.get_values::<f64, _>(..)?,
+    };
+    let lower = bounds.iter().copied().fold(f64::INFINITY, f64::min);
+    let upper = bounds.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    Ok((lower + (upper - lower) / 2.0, [lower, upper]))
+}
+
+/// Reduce a variable over one of its dimensions and write the result into `out`
+///
+/// Values equal to `_FillValue` or `missing_value`, outside the valid range,
+/// or `NaN` are ignored. Packed values are unpacked, and the result is
+/// written as a `f64` variable named `name` with the attributes of `var`
+/// and `cell_methods` extended with e.g. `time: mean`. Elements without any
+/// valid values are set to the fill value.
+///
+/// The reduced dimension is kept with length one. If `var` has a
+/// coordinate variable for it, the coordinate is written with the
+/// midpoint of the original bounds as value and the full extent as new `bounds`.
+/// Dimensions which do not exist in `out` are created, named as in the
+/// source, except for the reduced dimension which is named
+/// e.g. `time_mean` if `out` already has a dimension with the original name.
+///
+/// # Example
+/// ```rust,no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use netcdf::cf::Reduction;
+/// let file = netcdf::open("daily.nc")?;
+/// let temperature = file.variable("temperature").expect("no such variable");
+/// let mut out = netcdf::create("climatology.nc")?;
+/// netcdf::cf::reduce(&temperature, "time", Reduction::Mean, &mut out, "temperature")?;
+/// # Ok(()) }
+/// ```
+pub fn reduce<'f>(
+    var: &Variable,
+    dimension: &str,
+    reduction: Reduction,
+    out: &'f mut FileMut,
+    name: &str,
+) -> error::Result<VariableMut<'f>> {
+    let dims = var.dimensions();
+    let axis = dims
+        .iter()
+        .position(|d| d.name() == dimension)
+        .ok_or_else(|| {
+            error::Error::NotFound(format!("dimension {dimension} of variable {}", var.name()))
+        })?;
+
+    let mask = Mask::from_variable(var)?;
+    let fill_value = mask.missing.first().copied();
+    let (scale, offset) = packing(var)?;
+
+    let values: ArrayD<f64> = var.get::<f64, _>(..)?;
+    let reduced = values.map_axis(Axis(axis), |lane| {
+        reduction.apply(
+            lane.iter()
+                .copied()
+                .filter(|&x| mask.is_valid(x))
+                .map(|x| x * scale + offset),
+        )
+    });
+    let needs_fill = reduced.iter().any(Option::is_none);
+    let fill_value = fill_value.unwrap_or(netcdf_sys::NC_FILL_DOUBLE);
+    let reduced = reduced
+        .mapv(|x| x.unwrap_or(fill_value))
+        .insert_axis(Axis(axis));
+
+    // Define the dimensions in the output file
+    let reduced_dim = if out.dimension(dimension).is_none() {
+        dimension.to_owned()
+    } else {
+        format!("{dimension}_{}", reduction.cell_method())
+    };
+    let mut out_dims = Vec::with_capacity(dims.len());
+    for (i, dim) in dims.iter().enumerate() {
+        let (dim_name, len) = if i == axis {
+            (reduced_dim.clone(), 1)
+        } else {
+            (dim.name(), dim.len())
+        };
+        match out.dimension_len(&dim_name) {
+            None => {
+                out.add_dimension(&dim_name, len)?;
+            }
+            Some(existing) if existing == len => {}
+            Some(existing) => {
+                return Err(error::Error::DimensionMismatch {
+                    wanted: len,
+                    actual: existing,
+                })
+            }
+        }
+        out_dims.push(dim_name);
+    }
+
+    // Coordinate and bounds of the reduced dimension
+    let coordinate = super::find_variable(var.ncid, dimension)?
+        .filter(|c| c.dimensions().len() == 1 && c.dimensions()[0].name() == dimension);
+    if let Some(coordinate) = coordinate {
+        if out.variable(&reduced_dim).is_none() {
+            let (value, bounds) = reduced_coordinate(&coordinate)?;
+            let bounds_name = format!("{reduced_dim}_bnds");
+            // An existing `nv` of another length is left to its own variables
+            let nv = match out.dimension_len("nv") {
+                None | Some(2) => "nv".to_owned(),
+                Some(_) => format!("{reduced_dim}_nv"),
+            };
+            match out.dimension_len(&nv) {
+                None => {
+                    out.add_dimension(&nv, 2)?;
+                }
+                Some(2) => {}
+                Some(existing) => {
+                    return Err(error::Error::DimensionMismatch {
+                        wanted: 2,
+                        actual: existing,
+                    })
+                }
+            }
+            let mut bnds = out.add_variable::<f64>(&bounds_name, &[&reduced_dim, &nv])?;
+            bnds.put_values(&bounds, ..)?;
+
+            let mut coord = out.add_variable::<f64>(&reduced_dim, &[&reduced_dim])?;
+            copy_attributes(&coordinate, &mut coord, &["bounds", "climatology"])?;
+            coord.put_attribute("bounds", bounds_name)?;
+            coord.put_values(&[value], ..)?;
+        }
+    }
+
+    let existing = var
+        .attribute_value("cell_methods")
+        .transpose()?
+        .and_then(|value| String::try_from(value).ok());
+    let cell_methods = match existing {
+        Some(existing) if !existing.trim().is_empty() => format!(
+            "{} {reduced_dim}: {}",
+            existing.trim(),
+            reduction.cell_method()
+        ),
+        _ => format!("{reduced_dim}: {}", reduction.cell_method()),
+    };
+
+    let out_dims = out_dims.iter().map(String::as_str).collect::<Vec<_>>();
+    let mut result = out.add_variable::<f64>(name, &out_dims)?;
+    copy_attributes(var, &mut result, &["cell_methods"])?;
+    if needs_fill && result.attribute("_FillValue").is_none() {
+        result.put_attribute("_FillValue", fill_value)?;
+    }
+    result.put_attribute("cell_methods", cell_methods)?;
+    result.put(reduced.view(), ..)?;
+    Ok(result)
+}
@@ -0,0 +1,161 @@
+//! Complex numbers using [`num_complex`]
+//!
+//! [`Complex<f32>`] and [`Complex<f64>`] are stored as a compound with
+//! the fields `r` and `i`, using the type names of
+//! [nc-complex](https://github.com/PlasmaFAIR/nc-complex). The type must
+//! be added to the file before adding a variable:
+//! ```rust,no_run
+//! # fn main() -> Result<(), netcdf::Error> {
+//! use num_complex::Complex;
+//!
+//! let mut file = netcdf::create("spectrum.nc")?;
+//! file.add_type::<Complex<f64>>()?;
+//! file.add_dimension("frequency", 2)?;
+//! let mut var = file.add_variable::<Complex<f64>>("spectrum", &["frequency"])?;
+//! var.put_values(&[Complex::new(1.0, -1.0), Complex::new(0.0, 2.0)], ..)?;
+//! # Ok(()) }
+//! ```
+//!
+//! [`Variable::get_complex_values`](crate::Variable::get_complex_values) reads
+//! other common layouts as well:
+//! * Compounds of two floating point fields named e.g. `r`/`i`,
+//!   `re`/`im` or `real`/`imag`, regardless of the name of the type
+//! * A floating point variable where the last dimension has length 2 and
+//!   is named `ri`, `complex` or `_pfnc_complex`
+
+use num_complex::Complex;
+
+use crate::error;
+use crate::types::{CompoundType, CompoundTypeField, FloatType, NcTypeDescriptor, NcVariableType};
+use crate::Variable;
+
+macro_rules! impl_complex {
+    ($ty: ty, $name: literal) => {
+        unsafe impl NcTypeDescriptor for Complex<$ty> {
+            fn type_descriptor() -> NcVariableType {
+                let field = |name: &str, offset| CompoundTypeField {
+                    name: name.to_owned(),
+                    basetype: <$ty>::type_descriptor(),
+                    arraydims: None,
+                    offset,
+                };
+                NcVariableType::Compound(CompoundType {
+                    name: $name.to_owned(),
+                    size: std::mem::size_of::<Self>(),
+                    fields: vec![
+                        field("r", std::mem::offset_of!(Self, re)),
+                        field("i", std::mem::offset_of!(Self, im)),
+                    ],
+                })
+            }
+        }
+    };
+}
+
+impl_complex!(f32, "_PFNC_FLOAT_COMPLEX_TYPE");
+impl_complex!(f64, "_PFNC_DOUBLE_COMPLEX_TYPE");
+
+/// Floating point type of the parts of a complex number
+pub trait ComplexFloat: NcTypeDescriptor + Copy {
+    /// Convert from a part read as `f64`
+    fn from_f64(x: f64) -> Self;
+}
+
+impl ComplexFloat for f32 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+}
+
+impl ComplexFloat for f64 {
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+}
+
+/// Names of the real and imaginary fields of a complex compound
+const FIELD_NAMES: &[(&str, &str)] = &[
+    ("r", "i"),
+    ("re", "im"),
+    ("real", "imag"),
+    ("real", "imaginary"),
+];
+
+/// Names of a dimension holding the real and imaginary parts
+const DIMENSION_NAMES: &[&str] = &["ri", "complex", "_pfnc_complex"];
+
+/// How complex numbers are stored in a variable
+pub(crate) enum Layout {
+    /// Compound with the offsets of the real and imaginary parts
+    Compound {
+        real: (usize, FloatType),
+        imag: (usize, FloatType),
+    },
+    /// Trailing dimension of length 2
+    Dimension,
+}
+
+fn float_field(field: &CompoundTypeField) -> Option<(usize, FloatType)> {
+    match (&field.basetype, &field.arraydims) {
+        (NcVariableType::Float(float), None) => Some((field.offset, *float)),
+        _ => None,
+    }
+}
+
+/// Find the layout of the complex numbers of `var`
+pub(crate) fn layout(var: &Variable) -> error::Result<Layout> {
+    match var.vartype() {
+        NcVariableType::Compound(compound) if compound.fields.len() == 2 => {
+            let [a, b] = &compound.fields[..] else {
+                unreachable!()
+            };
+            let names_match = |(re, im): &(&str, &str)| {
+                a.name.eq_ignore_ascii_case(re) && b.name.eq_ignore_ascii_case(im)
+            };
+            if let (true, Some(real), Some(imag)) = (
+                FIELD_NAMES.iter().any(names_match),
+                float_field(a),
+                float_field(b),
+            ) {
+                return Ok(Layout::Compound { real, imag });
+            }
+        }
+        NcVariableType::Float(_) => {
+            if let Some(dim) = var.dimensions().last() {
+                if dim.len() == 2 && DIMENSION_NAMES.contains(&dim.name().as_str()) {
+                    return Ok(Layout::Dimension);
+                }
+            }
+        }
+        _ => {}
+    }
+    Err(format!("variable {} does not hold complex numbers", var.name()).into())
+}
+
+/// Read a part of a complex number
+fn read_part((offset, float): (usize, FloatType), bytes: &[u8]) -> f64 {
+    let bytes = &bytes[offset..];
+    match float {
+        FloatType::F32 => f32::from_ne_bytes(bytes[..4].try_into().unwrap()).into(),
+        FloatType::F64 => f64::from_ne_bytes(bytes[..8].try_into().unwrap()),
+    }
+}
+
+/// Decode compounds of the given layout
+pub(crate) fn decode_compounds<T: ComplexFloat>(
+    real: (usize, FloatType),
+    imag: (usize, FloatType),
+    size: usize,
+    bytes: &[u8],
+) -> Vec<Complex<T>> {
+    bytes
+        .chunks_exact(size)
+        .map(|element| {
+            Complex::new(
+                T::from_f64(read_part(real, element)),
+                T::from_f64(read_part(imag, element)),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,342 @@
+//! Copy a file into another, converting format and storage (`nccopy`)
+//!
+//! The format of the copy is given by the options the destination
+//! was created with:
+//! ```rust,no_run
+//! # fn main() -> Result<(), netcdf::Error> {
+//! use netcdf::copy::{CopyOptions, Storage};
+//!
+//! let src = netcdf::open("classic.nc")?;
+//! let mut dst = netcdf::create_with("compressed.nc", netcdf::Options::NETCDF4)?;
+//! let options = CopyOptions {
+//!     chunking: vec![("time".to_owned(), 1)],
+//!     storage: Storage {
+//!         deflate: Some(4),
+//!         shuffle: true,
+//!         ..Storage::default()
+//!     },
+//!     ..CopyOptions::default()
+//! };
+//! netcdf::copy::copy(&src, &mut dst, &options)?;
+//! # Ok(()) }
+//! ```
+
+use std::ops::Range;
+
+use netcdf_sys::*;
+
+use crate::error;
+use crate::extent::{Extent, Extents};
+use crate::group::is_hdf5;
+use crate::variable::{deflate, filters, set_filter, Filter};
+use crate::{Dimension, File, FileMut, Group, GroupMut, Variable, VariableMut};
+
+/// Number of values read and written at a time
+const BLOCK_LEN: usize = 1 << 20;
+
+/// Identifiers of the `HDF5` filters set by
+/// [`set_compression`](VariableMut::set_compression)
+const FILTER_DEFLATE: u32 = 1;
+const FILTER_SHUFFLE: u32 = 2;
+
+/// Compression and filters of a variable
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Storage {
+    /// Deflate level, `Some(0)` removes deflate and shuffle,
+    /// `None` keeps those of the source
+    pub deflate: Option<nc_type>,
+    /// Shuffle bytes before deflating, used when `deflate` is set
+    pub shuffle: bool,
+    /// Other filters, `None` keeps those of the source
+    pub filters: Option<Vec<Filter>>,
+}
+
+/// Options for [`copy`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CopyOptions {
+    /// Chunk size along dimensions given by name. Dimensions not given
+    /// are chunked by their full length, or 1 if unlimited
+    pub chunking: Vec<(String, usize)>,
+    /// Storage of all variables
+    pub storage: Storage,
+    /// Storage of variables given by name or full path, replacing `storage`
+    pub variable_storage: Vec<(String, Storage)>,
+    /// Variables to copy, given by name or full path, `None` copies all
+    pub variables: Option<Vec<String>>,
+    /// Range of indices to copy along dimensions given by name
+    pub slices: Vec<(String, Range<usize>)>,
+    /// Put everything in the root group, where the names of items of
+    /// subgroups are prefixed by the path of the group, e.g. `forecast_time`
+    pub flatten: bool,
+}
+
+/// Copy the groups, types, dimensions, variables, attributes and
+/// data of `src` to `dst`
+///
+/// # Errors
+///
+/// Groups or user defined types in a file which is not `netCDF-4`,
+/// unless flattened, or chunking and compression of a file which is
+/// not `netCDF-4`.
+pub fn copy(src: &File, dst: &mut FileMut, options: &CopyOptions) -> error::Result<()> {
+    let chunked = is_hdf5(dst.ncid())?;
+    let wants_storage = !options.chunking.is_empty()
+        || options.storage != Storage::default()
+        || !options.variable_storage.is_empty();
+    if wants_storage && !chunked {
+        return Err(error::Error::Unsupported(
+            "chunking and compression require a netCDF-4 file".to_owned(),
+        ));
+    }
+    let copier = Copier {
+        options,
+        read_storage: is_hdf5(src.ncid())?,
+        write_storage: chunked,
+    };
+    let src = src.root();
+    let mut dst = dst.root_mut();
+    copier.group(&src, &mut dst, "")?;
+    copier.data(&src, &mut dst, "")
+}
+
+struct Copier<'a> {
+    options: &'a CopyOptions,
+    read_storage: bool,
+    write_storage: bool,
+}
+
+impl Copier<'_> {
+    /// Name of an item of the group at `path` in the copy
+    fn name(&self, path: &str, name: &str) -> String {
+        let path = path.trim_matches('/');
+        if self.options.flatten && !path.is_empty() {
+            format!("{}_{name}", path.replace('/', "_"))
+        } else {
+            name.to_owned()
+        }
+    }
+
+    /// Name of a dimension in the copy
+    fn dimension_name(&self, dim: &Dimension) -> error::Result<String> {
+        let name = dim.try_name()?;
+        if self.options.flatten {
+            Ok(self.name(&dim.group()?.full_path()?, &name))
+        } else {
+            Ok(name)
+        }
+    }
+
+    /// Indices of `dim` to copy
+    fn range(&self, dim: &Dimension) -> error::Result<Range<usize>> {
+        let len = dim.try_len()?;
+        let name = dim.try_name()?;
+        Ok(match self.options.slices.iter().find(|(n, _)| *n == name) {
+            Some((_, range)) => range.start.min(len)..range.end.min(len),
+            None => 0..len,
+        })
+    }
+
+    fn wants(&self, var: &Variable, path: &str) -> error::Result<bool> {
+        let Some(names) = &self.options.variables else {
+            return Ok(true);
+        };
+        let name = var.try_name()?;
+        let full = format!("{}/{name}", path.trim_end_matches('/'));
+        Ok(names.iter().any(|n| {
+            let n = n.trim_start_matches('/');
+            n == name || n == full.trim_start_matches('/')
+        }))
+    }
+
+    fn storage(&self, var: &Variable, path: &str) -> error::Result<&Storage> {
+        let name = var.try_name()?;
+        let full = format!("{}/{name}", path.trim_end_matches('/'));
+        Ok(self
+            .options
+            .variable_storage
+            .iter()
+            .find(|(n, _)| {
+                let n = n.trim_start_matches('/');
+                n == name || n == full.trim_start_matches('/')
+            })
+            .map_or(&self.options.storage, |(_, storage)| storage))
+    }
+
+    /// Copy everything but the data of variables
+    fn group(&self, src: &Group, dst: &mut GroupMut, path: &str) -> error::Result<()> {
+        for typ in src.try_types()? {
+            let typ = typ?;
+            if crate::types::find_type(dst.ncid, &typ)?.is_none() {
+                dst.add_type_from_descriptor(typ)?;
+            }
+        }
+        for dim in src.try_dimensions()? {
+            let dim = dim?;
+            let name = self.name(path, &dim.try_name()?);
+            let range = self.range(&dim)?;
+            if dim.is_unlimited() {
+                dst.add_unlimited_dimension(&name)?;
+            } else {
+                dst.add_dimension(&name, range.len())?;
+            }
+        }
+        for attr in src.try_attributes()? {
+            let attr = attr?;
+            dst.add_attribute(&self.name(path, attr.try_name()?), attr.value()?)?;
+        }
+        for var in src.try_variables()? {
+            let var = var?;
+            if !self.wants(&var, path)? {
+                continue;
+            }
+            let name = self.name(path, &var.try_name()?);
+            let dims = var
+                .dimensions()
+                .iter()
+                .map(|dim| self.dimension_name(dim))
+                .collect::<error::Result<Vec<_>>>()?;
+            let dims = dims.iter().map(String::as_str).collect::<Vec<_>>();
+            let mut copy = dst.add_variable_with_type(&name, &dims, &var.try_vartype()?)?;
+            if self.write_storage {
+                self.set_storage(&var, &mut copy, path)?;
+            }
+            for attr in var.try_attributes()? {
+                let attr = attr?;
+                copy.put_attribute(attr.try_name()?, attr.value()?)?;
+            }
+        }
+        for group in src.try_groups()? {
+            let group = group?;
+            let name = group.try_name()?;
+            let grppath = format!("{}/{name}", path.trim_end_matches('/'));
+            if self.options.flatten {
+                self.group(&group, dst, &grppath)?;
+            } else {
+                let mut copy = dst.add_group(&name)?;
+                self.group(&group, &mut copy, &grppath)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Chunking, compression and filters of a copied variable
+    fn set_storage(&self, src: &Variable, dst: &mut VariableMut, path: &str) -> error::Result<()> {
+        let source_chunking = if self.read_storage {
+            src.chunking()?
+        } else {
+            None
+        };
+        if !src.dimensions().is_empty()
+            && (!self.options.chunking.is_empty() || source_chunking.is_some())
+        {
+            let chunking = src
+                .dimensions()
+                .iter()
+                .enumerate()
+                .map(|(i, dim)| {
+                    let name = dim.try_name()?;
+                    let len = self.range(dim)?.len();
+                    let size = self
+                        .options
+                        .chunking
+                        .iter()
+                        .find(|(n, _)| *n == name)
+                        .map(|(_, size)| *size)
+                        .or_else(|| source_chunking.as_ref().map(|chunks| chunks[i]));
+                    Ok(match size {
+                        Some(size) if dim.is_unlimited() => size.max(1),
+                        Some(size) => size.clamp(1, len.max(1)),
+                        None if dim.is_unlimited() => 1,
+                        None => len.max(1),
+                    })
+                })
+                .collect::<error::Result<Vec<_>>>()?;
+            dst.set_chunking(&chunking)?;
+        }
+
+        let storage = self.storage(src, path)?;
+        let (level, shuffle) = match storage.deflate {
+            Some(level) => (level, storage.shuffle),
+            None if self.read_storage => {
+                let (level, shuffle) = deflate(src)?;
+                (level.unwrap_or(0), shuffle)
+            }
+            None => (0, false),
+        };
+        if level > 0 {
+            dst.set_compression(level, shuffle)?;
+        }
+        let filters = match &storage.filters {
+            Some(filters) => filters.clone(),
+            None if self.read_storage => filters(src)?,
+            None => vec![],
+        };
+        // Deflate and shuffle are set above, unless the source only shuffles
+        let keep_shuffle = level == 0 && storage.deflate.is_none();
+        for filter in filters.iter().filter(|filter| {
+            filter.id != FILTER_DEFLATE && (filter.id != FILTER_SHUFFLE || keep_shuffle)
+        }) {
+            set_filter(dst, filter)?;
+        }
+        Ok(())
+    }
+
+    /// Copy the data of variables, after all definitions
+    fn data(&self, src: &Group, dst: &mut GroupMut, path: &str) -> error::Result<()> {
+        for var in src.try_variables()? {
+            let var = var?;
+            if !self.wants(&var, path)? {
+                continue;
+            }
+            let name = self.name(path, &var.try_name()?);
+            let mut copy = dst
+                .try_variable_mut(&name)?
+                .ok_or_else(|| error::Error::NotFound(format!("variable {name}")))?;
+            self.values(&var, &mut copy)?;
+        }
+        for group in src.try_groups()? {
+            let group = group?;
+            let name = group.try_name()?;
+            let grppath = format!("{}/{name}", path.trim_end_matches('/'));
+            if self.options.flatten {
+                self.data(&group, dst, &grppath)?;
+            } else {
+                let mut copy = dst
+                    .try_group_mut(&name)?
+                    .ok_or_else(|| error::Error::NotFound(format!("group {name}")))?;
+                self.data(&group, &mut copy, &grppath)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the values of a variable in blocks along the first dimension
+    fn values(&self, src: &Variable, dst: &mut VariableMut) -> error::Result<()> {
+        let ranges = src
+            .dimensions()
+            .iter()
+            .map(|dim| self.range(dim))
+            .collect::<error::Result<Vec<_>>>()?;
+        let Some((first, rest)) = ranges.split_first() else {
+            let values = src.get_dynamic_values(..)?;
+            return dst.put_dynamic_values(&values, ..);
+        };
+        let row_len = rest.iter().map(ExactSizeIterator::len).product::<usize>();
+        if first.is_empty() || row_len == 0 {
+            return Ok(());
+        }
+        let rows = (BLOCK_LEN / row_len).max(1);
+        for start in first.clone().step_by(rows) {
+            let end = (start + rows).min(first.end);
+            let mut read = vec![Extent::from(start..end)];
+            read.extend(rest.iter().cloned().map(Extent::from));
+            let values = src.get_dynamic_values(Extents::from(read))?;
+
+            let offset = start - first.start;
+            let mut write = vec![Extent::from(offset..offset + (end - start))];
+            write.extend(rest.iter().map(|range| Extent::from(0..range.len())));
+            dst.put_dynamic_values(&values, Extents::from(write))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+//! Datasets described by a Rust struct
+//!
+//! Implement [`NcDataset`] using `#[derive(NcDataset)]` (requires the `derive`
+//! feature) on a struct where each field holds the data of a variable:
+//! ```rust,no_run
+//! # #[cfg(not(feature = "derive"))]
+//! # fn main() { /* This test does nothing without derive feature flag */ }
+//! # #[cfg(feature = "derive")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use netcdf::NcDataset;
+//!
+//! #[derive(NcDataset)]
+//! #[netcdf(title = "Surface temperature", Conventions = "CF-1.8")]
+//! struct Product {
+//!     #[netcdf(dims = ["time"], units = "days since 2000-01-01")]
+//!     time: Vec<f64>,
+//!     #[netcdf(dims = ["time", "lat", "lon"], units = "K", fill_value = -999.0)]
+//!     temperature: ndarray::ArrayD<f32>,
+//!     #[netcdf(rename = "station_id")]
+//!     station: i32,
+//! }
+//!
+//! let product = Product {
+//!     time: vec![0.0, 1.0],
+//!     temperature: ndarray::ArrayD::zeros(vec![2, 3, 4]),
+//!     station: 18700,
+//! };
+//! let mut file = netcdf::create("product.nc")?;
+//! product.write_to(&mut file)?;
+//!
+//! let file = netcdf::open("product.nc")?;
+//! let product = Product::read_from(&file)?;
+//! # Ok(()) }
+//! ```
+//!
+//! Field attributes:
+//! * `dims = ["a", "b"]`: dimensions of the variable, created on write
+//!   from the shape of the data. Omit for scalars
+//! * `rename = "name"`: name of the variable, defaults to the field name
+//! * `fill_value = value`: fill value of the variable
+//! * `key = value`: any other attribute of the variable
+//!
+//! Attributes on the struct are written as global attributes.
+//! When reading, the dimensions of each variable and the `units`
+//! (if given) are checked against the declaration.
+
+use crate::error;
+use crate::file::{File, FileMut};
+use crate::types::NcTypeDescriptor;
+use crate::variable::{Variable, VariableMut};
+
+/// A dataset which can be written to and read from a file,
+/// usually derived using `#[derive(NcDataset)]`
+pub trait NcDataset: Sized {
+    /// Define the dimensions, variables and attributes in `file` and write the data
+    fn write_to(&self, file: &mut FileMut) -> error::Result<()>;
+    /// Read and validate the variables from `file`
+    fn read_from(file: &File) -> error::Result<Self>;
+}
+
+/// Data of a single variable in a [`NcDataset`]
+pub trait DatasetVariable: Sized {
+    /// Element type of the variable
+    type Element: NcTypeDescriptor + Copy;
+    /// Length of each dimension
+    fn shape(&self) -> Vec<usize>;
+    /// Write all values
+    fn put(&self, var: &mut VariableMut) -> error::Result<()>;
+    /// Read all values
+    fn get(var: &Variable) -> error::Result<Self>;
+}
+
+macro_rules! impl_scalar {
+    ($ty: ty) => {
+        impl DatasetVariable for $ty {
+            type Element = Self;
+            fn shape(&self) -> Vec<usize> {
+                vec![]
+            }
+            fn put(&self, var: &mut VariableMut) -> error::Result<()> {
+                var.put_value(*self, ..)
+            }
+            fn get(var: &Variable) -> error::Result<Self> {
+                var.get_value(..)
+            }
+        }
+    };
+}
+
+impl_scalar!(u8);
+impl_scalar!(u16);
+impl_scalar!(u32);
+impl_scalar!(u64);
+impl_scalar!(i8);
+impl_scalar!(i16);
+impl_scalar!(i32);
+impl_scalar!(i64);
+impl_scalar!(f32);
+impl_scalar!(f64);
+
+impl<T: NcTypeDescriptor + Copy> DatasetVariable for Vec<T> {
+    type Element = T;
+    fn shape(&self) -> Vec<usize> {
+        vec![self.len()]
+    }
+    fn put(&self, var: &mut VariableMut) -> error::Result<()> {
+        var.put_values(self, ..)
+    }
+    fn get(var: &Variable) -> error::Result<Self> {
+        var.get_values(..)
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<T, D> DatasetVariable for ndarray::Array<T, D>
+where
+    T: NcTypeDescriptor + Copy,
+    D: ndarray::Dimension,
+{
+    type Element = T;
+    fn shape(&self) -> Vec<usize> {
+        ndarray::ArrayBase::shape(self).to_vec()
+    }
+    fn put(&self, var: &mut VariableMut) -> error::Result<()> {
+        var.put(self.as_standard_layout().view(), ..)
+    }
+    fn get(var: &Variable) -> error::Result<Self> {
+        var.get::<T, _>(..)?
+            .into_dimensionality::<D>()
+            .map_err(|e| format!("variable {}: {e}", var.name()).into())
+    }
+}
+
+/// Create the dimensions of a variable, or check the lengths of existing dimensions
+///
+/// Empty data is rejected when the dimension does not exist, as a dimension
+/// of length zero would be created as an unlimited dimension
+#[doc(hidden)]
+pub fn define_dimensions(
+    file: &mut FileMut,
+    variable: &str,
+    dims: &[&str],
+    shape: &[usize],
+) -> error::Result<()> {
+    if dims.len() != shape.len() {
+        return Err(format!(
+            "variable {variable} is declared with {} dimensions, but the data has {}",
+            dims.len(),
+            shape.len()
+        )
+        .into());
+    }
+    for (&name, &len) in dims.iter().zip(shape) {
+        match file.dimension_len(name) {
+            None if len == 0 => {
+                return Err(format!(
+                    "variable {variable} has no data along the new dimension {name}"
+                )
+                .into());
+            }
+            None => {
+                file.add_dimension(name, len)?;
+            }
+            Some(existing) if existing == len => {}
+            Some(existing) => {
+                return Err(error::Error::DimensionMismatch {
+                    wanted: existing,
+                    actual: len,
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Find a variable and check the dimensions and units
+#[doc(hidden)]
+pub fn find_variable<'f>(
+    file: &'f File,
+    name: &str,
+    dims: &[&str],
+    units: Option<&str>,
+) -> error::Result<Variable<'f>> {
+    let var = file
+        .variable(name)
+        .ok_or_else(|| error::Error::NotFound(format!("variable {name}")))?;
+    let actual = var
+        .dimensions()
+        .iter()
+        .map(crate::Dimension::name)
+        .collect::<Vec<_>>();
+    if actual != dims {
+        return Err(format!("variable {name} has dimensions {actual:?}, expected {dims:?}").into());
+    }
+    if let Some(units) = units {
+        let actual = var
+            .attribute_value("units")
+            .transpose()?
+            .map(String::try_from)
+            .transpose()?;
+        if actual.as_deref() != Some(units) {
+            return Err(
+                format!("variable {name} has units {actual:?}, expected \"{units}\"").into(),
+            );
+        }
+    }
+    Ok(var)
+}
@@ -0,0 +1,778 @@
+//! Compare the structure and data of two files
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use netcdf::diff::{Differ, Tolerance};
+//!
+//! let expected = netcdf::open("expected.nc")?;
+//! let actual = netcdf::open("actual.nc")?;
+//! let report = Differ::new()
+//!     .with_tolerance(Tolerance { absolute: 1e-6, relative: 0.0 })
+//!     .with_variable_tolerance("temperature", Tolerance { absolute: 0.0, relative: 1e-3 })
+//!     .diff(&expected, &actual)?;
+//! if !report.is_identical() {
+//!     print!("{report}");
+//! }
+//! # Ok(()) }
+//! ```
+
+use std::fmt;
+
+use crate::error;
+use crate::extent::{Extent, Extents};
+use crate::file::File;
+use crate::group::Group;
+use crate::hierarchy::join;
+use crate::types::{FloatType, IntType, NcVariableType};
+use crate::variable::Variable;
+use crate::Dimension;
+
+/// Number of values of each file compared at a time
+const BLOCK_LEN: usize = 1 << 20;
+
+/// Tolerance when comparing numbers
+///
+/// Two values `a` and `b` are equal if
+/// `|a - b| <= absolute + relative * max(|a|, |b|)`
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tolerance {
+    /// Allowed absolute difference
+    pub absolute: f64,
+    /// Allowed difference relative to the larger magnitude of the values
+    pub relative: f64,
+}
+
+impl Tolerance {
+    fn allows(self, a: f64, b: f64) -> bool {
+        // Equal infinities have no finite difference
+        a == b || self.allows_difference((a - b).abs(), a.abs().max(b.abs()))
+    }
+
+    /// Is the absolute difference of values with the larger magnitude allowed
+    fn allows_difference(self, absolute: f64, magnitude: f64) -> bool {
+        absolute <= self.absolute + self.relative * magnitude
+    }
+}
+
+/// What differs between the files
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DifferenceKind {
+    /// An item exists in only one of the files
+    Missing,
+    /// A variable or user defined type has different types
+    Type,
+    /// A dimension has a different length or is unlimited in only one file
+    Length,
+    /// A variable has different dimensions or shape
+    Dimensions,
+    /// An attribute has different values
+    Attribute,
+    /// A variable has different values
+    Data,
+}
+
+impl fmt::Display for DifferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => write!(f, "missing"),
+            Self::Type => write!(f, "type"),
+            Self::Length => write!(f, "length"),
+            Self::Dimensions => write!(f, "dimensions"),
+            Self::Attribute => write!(f, "attribute"),
+            Self::Data => write!(f, "data"),
+        }
+    }
+}
+
+/// Summary of the values of a variable which differ
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataDifference {
+    /// Number of values which differ
+    pub count: usize,
+    /// Largest absolute difference, 0 for types which are not numeric.
+    /// Values which are masked in only one file count as differences,
+    /// but not towards the largest differences
+    pub max_absolute: f64,
+    /// Largest difference relative to the larger magnitude of the values,
+    /// 0 for types which are not numeric
+    pub max_relative: f64,
+    /// Index of the first value which differs
+    pub first_index: Vec<usize>,
+}
+
+impl DataDifference {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            max_absolute: 0.0,
+            max_relative: 0.0,
+            first_index: vec![],
+        }
+    }
+
+    /// Count a value which differs, with the absolute and larger
+    /// magnitude of the values if both are numbers
+    fn record(&mut self, index: Vec<usize>, difference: Option<(f64, f64)>) {
+        if self.count == 0 || index < self.first_index {
+            self.first_index = index;
+        }
+        self.count += 1;
+        if let Some((absolute, magnitude)) = difference {
+            self.max_absolute = self.max_absolute.max(absolute);
+            self.max_relative = self.max_relative.max(absolute / magnitude);
+        }
+    }
+}
+
+/// A difference between the files
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Difference {
+    /// What differs
+    pub kind: DifferenceKind,
+    /// Full path of the item, e.g. `/group/variable`, or
+    /// `/group/variable/attribute` for attributes
+    pub location: String,
+    /// Human readable description of the difference
+    pub message: String,
+    /// Details of differing values, for [`DifferenceKind::Data`]
+    pub data: Option<DataDifference>,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.kind, self.location, self.message)
+    }
+}
+
+/// Result of comparing two files
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    differences: Vec<Difference>,
+}
+
+impl Report {
+    /// All differences, in the order they were encountered
+    pub fn differences(&self) -> &[Difference] {
+        &self.differences
+    }
+    /// The files have no differences
+    pub fn is_identical(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} differences", self.differences.len())?;
+        for difference in &self.differences {
+            writeln!(f, "{difference}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares files
+///
+/// By default numbers must be exactly equal, use
+/// [`with_tolerance`](Self::with_tolerance) to allow small differences.
+#[derive(Debug, Clone)]
+pub struct Differ {
+    tolerance: Tolerance,
+    variable_tolerances: Vec<(String, Tolerance)>,
+    data: bool,
+}
+
+impl Default for Differ {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compare two files with exact equality of numbers
+pub fn diff(first: &File, second: &File) -> error::Result<Report> {
+    Differ::new().diff(first, second)
+}
+
+impl Differ {
+    /// Create a differ comparing structure and data
+    pub fn new() -> Self {
+        Self {
+            tolerance: Tolerance::default(),
+            variable_tolerances: vec![],
+            data: true,
+        }
+    }
+
+    /// Tolerance of all variables
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Tolerance of a variable given by name or full path,
+    /// replacing the tolerance of all variables
+    #[must_use]
+    pub fn with_variable_tolerance(mut self, name: &str, tolerance: Tolerance) -> Self {
+        self.variable_tolerances.push((name.to_owned(), tolerance));
+        self
+    }
+
+    /// Only compare the structure and attributes, not the data
+    #[must_use]
+    pub fn without_data(mut self) -> Self {
+        self.data = false;
+        self
+    }
+
+    /// Compare all groups, types, dimensions, variables, attributes
+    /// and data of the files
+    pub fn diff(&self, first: &File, second: &File) -> error::Result<Report> {
+        let mut context = Context {
+            differ: self,
+            differences: vec![],
+        };
+        context.group(&first.root(), &second.root(), "/")?;
+        Ok(Report {
+            differences: context.differences,
+        })
+    }
+
+    fn tolerance(&self, name: &str, path: &str) -> Tolerance {
+        self.variable_tolerances
+            .iter()
+            .find(|(n, _)| {
+                let n = n.trim_start_matches('/');
+                n == name || n == path.trim_start_matches('/')
+            })
+            .map_or(self.tolerance, |(_, tolerance)| *tolerance)
+    }
+}
+
+struct Context<'d> {
+    differ: &'d Differ,
+    differences: Vec<Difference>,
+}
+
+/// Items of both files matched by name, `None` where missing
+type Pairs<T> = Vec<(String, Option<T>, Option<T>)>;
+
+/// Items of both files, matched by name
+fn pair<T>(
+    first: Vec<T>,
+    second: Vec<T>,
+    name: impl Fn(&T) -> error::Result<String>,
+) -> error::Result<Pairs<T>> {
+    let mut pairs = first
+        .into_iter()
+        .map(|x| Ok((name(&x)?, Some(x), None)))
+        .collect::<error::Result<Vec<_>>>()?;
+    for x in second {
+        let n = name(&x)?;
+        match pairs.iter_mut().find(|(other, _, _)| *other == n) {
+            Some(pair) => pair.2 = Some(x),
+            None => pairs.push((n, None, Some(x))),
+        }
+    }
+    Ok(pairs)
+}
+
+fn is_numeric(typ: &NcVariableType) -> bool {
+    matches!(typ, NcVariableType::Int(_) | NcVariableType::Float(_))
+}
+
+/// Fill value of an integer variable
+fn int_fill_value(var: &Variable, typ: IntType) -> error::Result<Option<i128>> {
+    Ok(match typ {
+        IntType::U8 => var.fill_value::<u8>()?.map(i128::from),
+        IntType::I8 => var.fill_value::<i8>()?.map(i128::from),
+        IntType::U16 => var.fill_value::<u16>()?.map(i128::from),
+        IntType::I16 => var.fill_value::<i16>()?.map(i128::from),
+        IntType::U32 => var.fill_value::<u32>()?.map(i128::from),
+        IntType::I32 => var.fill_value::<i32>()?.map(i128::from),
+        IntType::U64 => var.fill_value::<u64>()?.map(i128::from),
+        IntType::I64 => var.fill_value::<i64>()?.map(i128::from),
+    })
+}
+
+/// Values of an integer variable, without loss of precision
+fn int_values(var: &Variable, typ: IntType, extents: &Extents) -> error::Result<Vec<i128>> {
+    Ok(match typ {
+        IntType::U8 | IntType::U16 | IntType::U32 | IntType::U64 => var
+            .get_values::<u64, _>(extents.clone())?
+            .into_iter()
+            .map(i128::from)
+            .collect(),
+        IntType::I8 | IntType::I16 | IntType::I32 | IntType::I64 => var
+            .get_values::<i64, _>(extents.clone())?
+            .into_iter()
+            .map(i128::from)
+            .collect(),
+    })
+}
+
+/// Fill value of a numeric variable
+fn fill_value(var: &Variable, typ: &NcVariableType) -> error::Result<Option<f64>> {
+    #[allow(clippy::cast_precision_loss)]
+    Ok(match typ {
+        NcVariableType::Int(IntType::U8) => var.fill_value::<u8>()?.map(f64::from),
+        NcVariableType::Int(IntType::I8) => var.fill_value::<i8>()?.map(f64::from),
+        NcVariableType::Int(IntType::U16) => var.fill_value::<u16>()?.map(f64::from),
+        NcVariableType::Int(IntType::I16) => var.fill_value::<i16>()?.map(f64::from),
+        NcVariableType::Int(IntType::U32) => var.fill_value::<u32>()?.map(f64::from),
+        NcVariableType::Int(IntType::I32) => var.fill_value::<i32>()?.map(f64::from),
+        NcVariableType::Int(IntType::U64) => var.fill_value::<u64>()?.map(|x| x as f64),
+        NcVariableType::Int(IntType::I64) => var.fill_value::<i64>()?.map(|x| x as f64),
+        NcVariableType::Float(FloatType::F32) => var.fill_value::<f32>()?.map(f64::from),
+        NcVariableType::Float(FloatType::F64) => var.fill_value::<f64>()?,
+        _ => None,
+    })
+}
+
+/// Index into an array of `shape` from the offset in memory
+fn unravel(mut offset: usize, shape: &[usize]) -> Vec<usize> {
+    let mut index = vec![0; shape.len()];
+    for (i, len) in shape.iter().enumerate().rev() {
+        index[i] = offset % len;
+        offset /= len;
+    }
+    index
+}
+
+fn describe<T: fmt::Display>(first: T, second: T) -> String {
+    format!("{first} in the first file, {second} in the second")
+}
+
+impl Context<'_> {
+    fn push(&mut self, kind: DifferenceKind, location: String, message: String) {
+        self.differences.push(Difference {
+            kind,
+            location,
+            message,
+            data: None,
+        });
+    }
+
+    fn missing(&mut self, what: &str, location: String, in_first: bool) {
+        let file = if in_first { "first" } else { "second" };
+        self.push(
+            DifferenceKind::Missing,
+            location,
+            format!("{what} only in the {file} file"),
+        );
+    }
+
+    fn group(&mut self, first: &Group, second: &Group, path: &str) -> error::Result<()> {
+        let types = pair(
+            first.try_types()?.collect::<error::Result<_>>()?,
+            second.try_types()?.collect::<error::Result<_>>()?,
+            |typ| Ok(typ.name().to_owned()),
+        )?;
+        for (name, a, b) in types {
+            let location = join(path, &name);
+            match (a, b) {
+                (Some(a), Some(b)) if a != b => self.push(
+                    DifferenceKind::Type,
+                    location,
+                    "user defined type differs".to_owned(),
+                ),
+                (Some(_), Some(_)) => {}
+                (a, _) => self.missing("type", location, a.is_some()),
+            }
+        }
+
+        let dims = pair(
+            first.try_dimensions()?.collect::<error::Result<_>>()?,
+            second.try_dimensions()?.collect::<error::Result<_>>()?,
+            Dimension::try_name,
+        )?;
+        for (name, a, b) in dims {
+            let location = join(path, &name);
+            let (Some(a), Some(b)) = (&a, &b) else {
+                self.missing("dimension", location, a.is_some());
+                continue;
+            };
+            let (a_len, b_len) = (a.try_len()?, b.try_len()?);
+            if a_len != b_len {
+                self.push(
+                    DifferenceKind::Length,
+                    location.clone(),
+                    format!("length {}", describe(a_len, b_len)),
+                );
+            }
+            if a.is_unlimited() != b.is_unlimited() {
+                let which = if a.is_unlimited() { "first" } else { "second" };
+                self.push(
+                    DifferenceKind::Length,
+                    location,
+                    format!("unlimited only in the {which} file"),
+                );
+            }
+        }
+
+        self.attributes(first, second, path)?;
+
+        let vars = pair(
+            first.try_variables()?.collect::<error::Result<_>>()?,
+            second.try_variables()?.collect::<error::Result<_>>()?,
+            Variable::try_name,
+        )?;
+        for (name, a, b) in vars {
+            let location = join(path, &name);
+            match (a, b) {
+                (Some(a), Some(b)) => self.variable(&a, &b, &name, &location)?,
+                (a, _) => self.missing("variable", location, a.is_some()),
+            }
+        }
+
+        let groups = pair(
+            first.try_groups()?.collect::<error::Result<_>>()?,
+            second.try_groups()?.collect::<error::Result<_>>()?,
+            Group::try_name,
+        )?;
+        for (name, a, b) in groups {
+            let location = join(path, &name);
+            match (a, b) {
+                (Some(a), Some(b)) => self.group(&a, &b, &location)?,
+                (a, _) => self.missing("group", location, a.is_some()),
+            }
+        }
+        Ok(())
+    }
+
+    fn attributes(&mut self, first: &Group, second: &Group, path: &str) -> error::Result<()> {
+        let attrs = pair(
+            first.try_attributes()?.collect::<error::Result<_>>()?,
+            second.try_attributes()?.collect::<error::Result<_>>()?,
+            |attr| Ok(attr.try_name()?.to_owned()),
+        )?;
+        for (name, a, b) in attrs {
+            self.attribute(
+                join(path, &name),
+                a.map(|a| a.value()).transpose()?,
+                b.map(|b| b.value()).transpose()?,
+            );
+        }
+        Ok(())
+    }
+
+    fn attribute(
+        &mut self,
+        location: String,
+        first: Option<crate::AttributeValue>,
+        second: Option<crate::AttributeValue>,
+    ) {
+        match (first, second) {
+            (Some(a), Some(b)) if a != b => self.push(
+                DifferenceKind::Attribute,
+                location,
+                describe(format!("{a:?}"), format!("{b:?}")),
+            ),
+            (Some(_), Some(_)) => {}
+            (a, _) => self.missing("attribute", location, a.is_some()),
+        }
+    }
+
+    fn variable(
+        &mut self,
+        first: &Variable,
+        second: &Variable,
+        name: &str,
+        location: &str,
+    ) -> error::Result<()> {
+        let (a_type, b_type) = (first.try_vartype()?, second.try_vartype()?);
+        let types_match = a_type == b_type || (is_numeric(&a_type) && is_numeric(&b_type));
+        if a_type != b_type {
+            self.push(
+                DifferenceKind::Type,
+                location.to_owned(),
+                format!("type {}", describe(a_type.name(), b_type.name())),
+            );
+        }
+
+        let names = |var: &Variable| {
+            var.dimensions()
+                .iter()
+                .map(Dimension::try_name)
+                .collect::<error::Result<Vec<_>>>()
+                .map(|names| format!("({})", names.join(", ")))
+        };
+        let shape = |var: &Variable| {
+            var.dimensions()
+                .iter()
+                .map(Dimension::try_len)
+                .collect::<error::Result<Vec<_>>>()
+        };
+        let (a_names, b_names) = (names(first)?, names(second)?);
+        let (a_shape, b_shape) = (shape(first)?, shape(second)?);
+        if a_names != b_names {
+            self.push(
+                DifferenceKind::Dimensions,
+                location.to_owned(),
+                format!("dimensions {}", describe(a_names, b_names)),
+            );
+        } else if a_shape != b_shape {
+            self.push(
+                DifferenceKind::Dimensions,
+                location.to_owned(),
+                format!(
+                    "shape {}",
+                    describe(format!("{a_shape:?}"), format!("{b_shape:?}"))
+                ),
+            );
+        }
+
+        let attrs = pair(
+            first.try_attributes()?.collect::<error::Result<_>>()?,
+            second.try_attributes()?.collect::<error::Result<_>>()?,
+            |attr| Ok(attr.try_name()?.to_owned()),
+        )?;
+        for (attr, a, b) in attrs {
+            self.attribute(
+                join(location, &attr),
+                a.map(|a| a.value()).transpose()?,
+                b.map(|b| b.value()).transpose()?,
+            );
+        }
+
+        if self.differ.data && types_match && a_shape == b_shape {
+            let data = if is_numeric(&a_type) {
+                let tolerance = self.differ.tolerance(name, location);
+                numeric_data(first, second, &a_type, &b_type, &a_shape, tolerance)?
+            } else {
+                dynamic_data(first, second, &a_shape)?
+            };
+            if let Some(data) = data {
+                let mut message = format!("{} values differ", data.count);
+                if is_numeric(&a_type) {
+                    message.push_str(&format!(
+                        ", max absolute difference {}, max relative difference {}",
+                        data.max_absolute, data.max_relative
+                    ));
+                }
+                message.push_str(&format!(", first at {:?}", data.first_index));
+                self.differences.push(Difference {
+                    kind: DifferenceKind::Data,
+                    location: location.to_owned(),
+                    message,
+                    data: Some(data),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Blocks of a variable of `shape`, made of whole chunks if chunked,
+/// otherwise of whole rows along the last dimensions
+///
+/// Each block is given by its start and count
+fn blocks(shape: &[usize], chunking: Option<&[usize]>) -> Vec<(Vec<usize>, Vec<usize>)> {
+    if shape.contains(&0) {
+        return vec![];
+    }
+    let mut block = match chunking {
+        Some(chunks) => chunks
+            .iter()
+            .zip(shape)
+            .map(|(&chunk, &len)| chunk.clamp(1, len))
+            .collect(),
+        None => vec![1; shape.len()],
+    };
+    // Grow the block by whole chunks, starting from the last dimension
+    for i in (0..shape.len()).rev() {
+        let others = block
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, &len)| len)
+            .product::<usize>();
+        let chunks = (BLOCK_LEN / others / block[i]).max(1);
+        block[i] = (block[i] * chunks).min(shape[i]);
+    }
+
+    let counts = shape
+        .iter()
+        .zip(&block)
+        .map(|(len, block)| len.div_ceil(*block))
+        .collect::<Vec<_>>();
+    (0..counts.iter().product::<usize>())
+        .map(|n| {
+            let start = unravel(n, &counts)
+                .iter()
+                .zip(&block)
+                .map(|(i, block)| i * block)
+                .collect::<Vec<_>>();
+            let count = start
+                .iter()
+                .zip(shape)
+                .zip(&block)
+                .map(|((start, len), block)| (*block).min(len - start))
+                .collect();
+            (start, count)
+        })
+        .collect()
+}
+
+/// Blocks of a variable as extents, with the index of each value of the block
+fn block_extents(
+    var: &Variable,
+    shape: &[usize],
+) -> error::Result<impl Iterator<Item = (Extents, impl Fn(usize) -> Vec<usize>)>> {
+    let chunking = var.chunking()?;
+    Ok(blocks(shape, chunking.as_deref())
+        .into_iter()
+        .map(|(start, count)| {
+            let extents = Extents::from(
+                start
+                    .iter()
+                    .zip(&count)
+                    .map(|(&start, &count)| Extent::from(start..start + count))
+                    .collect::<Vec<_>>(),
+            );
+            let index = move |i: usize| {
+                unravel(i, &count)
+                    .iter()
+                    .zip(&start)
+                    .map(|(i, start)| i + start)
+                    .collect()
+            };
+            (extents, index)
+        }))
+}
+
+/// Compare numbers, where values which are `NaN` or fill values
+/// in both files are ignored
+///
+/// Integers are compared exactly, unless compared to floating point numbers
+fn numeric_data(
+    first: &Variable,
+    second: &Variable,
+    a_type: &NcVariableType,
+    b_type: &NcVariableType,
+    shape: &[usize],
+    tolerance: Tolerance,
+) -> error::Result<Option<DataDifference>> {
+    let mut data = DataDifference::new();
+    if let (NcVariableType::Int(a_int), NcVariableType::Int(b_int)) = (a_type, b_type) {
+        let (a_fill, b_fill) = (
+            int_fill_value(first, *a_int)?,
+            int_fill_value(second, *b_int)?,
+        );
+        for (extents, index) in block_extents(first, shape)? {
+            let a_values = int_values(first, *a_int, &extents)?;
+            let b_values = int_values(second, *b_int, &extents)?;
+            for (i, (&a, &b)) in a_values.iter().zip(&b_values).enumerate() {
+                match (Some(a) == a_fill, Some(b) == b_fill) {
+                    (true, true) => {}
+                    (true, false) | (false, true) => data.record(index(i), None),
+                    (false, false) if a == b => {}
+                    (false, false) => {
+                        #[allow(clippy::cast_precision_loss)]
+                        let (absolute, magnitude) = (
+                            a.abs_diff(b) as f64,
+                            a.unsigned_abs().max(b.unsigned_abs()) as f64,
+                        );
+                        if !tolerance.allows_difference(absolute, magnitude) {
+                            data.record(index(i), Some((absolute, magnitude)));
+                        }
+                    }
+                }
+            }
+        }
+    } else {
+        let (a_fill, b_fill) = (fill_value(first, a_type)?, fill_value(second, b_type)?);
+        for (extents, index) in block_extents(first, shape)? {
+            let a_values = first.get_values::<f64, _>(extents.clone())?;
+            let b_values = second.get_values::<f64, _>(extents.clone())?;
+            for (i, (&a, &b)) in a_values.iter().zip(&b_values).enumerate() {
+                let a_masked = a.is_nan() || Some(a) == a_fill;
+                let b_masked = b.is_nan() || Some(b) == b_fill;
+                match (a_masked, b_masked) {
+                    (true, true) => {}
+                    (true, false) | (false, true) => data.record(index(i), None),
+                    (false, false) if tolerance.allows(a, b) => {}
+                    (false, false) => {
+                        let difference = ((a - b).abs(), a.abs().max(b.abs()));
+                        data.record(index(i), Some(difference));
+                    }
+                }
+            }
+        }
+    }
+    Ok((data.count > 0).then_some(data))
+}
+
+/// Compare values which are not numbers by equality
+fn dynamic_data(
+    first: &Variable,
+    second: &Variable,
+    shape: &[usize],
+) -> error::Result<Option<DataDifference>> {
+    let mut data = DataDifference::new();
+    for (extents, index) in block_extents(first, shape)? {
+        let a_values = first.get_dynamic_values(extents.clone())?;
+        let b_values = second.get_dynamic_values(extents.clone())?;
+        for (i, (a, b)) in a_values.iter().zip(&b_values).enumerate() {
+            if a != b {
+                data.record(index(i), None);
+            }
+        }
+    }
+    Ok((data.count > 0).then_some(data))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tolerance() {
+        let exact = Tolerance::default();
+        assert!(exact.allows(1.0, 1.0));
+        assert!(!exact.allows(1.0, 1.0 + f64::EPSILON));
+        let absolute = Tolerance {
+            absolute: 0.1,
+            relative: 0.0,
+        };
+        assert!(absolute.allows(1.0, 1.05));
+        assert!(!absolute.allows(1.0, 1.2));
+        let relative = Tolerance {
+            absolute: 0.0,
+            relative: 0.01,
+        };
+        assert!(relative.allows(1000.0, 1005.0));
+        assert!(!relative.allows(1.0, 1.05));
+    }
+
+    #[test]
+    fn chunked_blocks() {
+        assert_eq!(blocks(&[], None), [(vec![], vec![])]);
+        assert!(blocks(&[0, 3], None).is_empty());
+        assert_eq!(blocks(&[2, 3], None), [(vec![0, 0], vec![2, 3])]);
+        assert_eq!(
+            blocks(&[3, BLOCK_LEN], None),
+            [
+                (vec![0, 0], vec![1, BLOCK_LEN]),
+                (vec![1, 0], vec![1, BLOCK_LEN]),
+                (vec![2, 0], vec![1, BLOCK_LEN]),
+            ]
+        );
+        // Chunks are not split between blocks
+        let blocks = blocks(&[3, 2048, 1000], Some(&[1, 1024, 1024]));
+        assert_eq!(blocks.len(), 6);
+        assert_eq!(blocks[1], (vec![0, 1024, 0], vec![1, 1024, 1000]));
+        assert_eq!(blocks[5], (vec![2, 1024, 0], vec![1, 1024, 1000]));
+    }
+
+    #[test]
+    fn index() {
+        assert_eq!(unravel(5, &[2, 3]), [1, 2]);
+        assert_eq!(unravel(0, &[]), Vec::<usize>::new());
+    }
+}
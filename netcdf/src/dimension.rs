@@ -6,6 +6,7 @@ use std::marker::PhantomData;
 use netcdf_sys::*;
 
 use super::error;
+use super::group::Group;
 use super::utils::{checked_with_lock, with_lock};
 
 /// Represents a netcdf dimension
@@ -27,20 +28,23 @@ pub struct DimensionIdentifier {
 }
 
 #[allow(clippy::len_without_is_empty)]
-impl Dimension<'_> {
+impl<'g> Dimension<'g> {
     /// Get current length of this dimension
     pub fn len(&self) -> usize {
+        // Should log or handle this somehow...
+        self.try_len().unwrap_or(0)
+    }
+    /// Get current length of this dimension
+    pub fn try_len(&self) -> error::Result<usize> {
         if let Some(x) = self.len {
-            x.get()
+            Ok(x.get())
         } else {
             let mut len = 0;
-            let err = checked_with_lock(|| unsafe {
+            checked_with_lock(|| unsafe {
                 // Must lock in case other variables adds to the dimension length
                 nc_inq_dimlen(self.id.ncid, self.id.dimid, &mut len)
-            });
-
-            // Should log or handle this somehow...
-            err.map(|()| len).unwrap_or(0)
+            })?;
+            Ok(len)
         }
     }
 
@@ -51,15 +55,28 @@ impl Dimension<'_> {
 
     /// Gets the name of the dimension
     pub fn name(&self) -> String {
+        self.try_name()
+            .expect("Dimension did not have a valid name")
+    }
+    /// Gets the name of the dimension
+    pub fn try_name(&self) -> error::Result<String> {
         let mut name = vec![0_u8; NC_MAX_NAME as usize + 1];
         checked_with_lock(|| unsafe {
             nc_inq_dimname(self.id.ncid, self.id.dimid, name.as_mut_ptr().cast())
-        })
-        .unwrap();
+        })?;
 
         let zeropos = name.iter().position(|&x| x == 0).unwrap_or(name.len());
         name.resize(zeropos, 0);
-        String::from_utf8(name).expect("Dimension did not have a valid name")
+        Ok(String::from_utf8(name)?)
+    }
+
+    /// The group this dimension is defined in, which may be an
+    /// ancestor of the group it was found through
+    pub fn group(&self) -> error::Result<Group<'g>> {
+        Ok(Group {
+            ncid: owner(self.id.ncid, self.id.dimid)?,
+            _file: PhantomData,
+        })
     }
 
     /// Grabs the unique identifier for this dimension, which
@@ -82,36 +99,78 @@ pub(crate) fn from_name_toid(loc: nc_type, name: &str) -> error::Result<Option<n
 }
 
 pub(crate) fn from_name<'f>(loc: nc_type, name: &str) -> error::Result<Option<Dimension<'f>>> {
-    let mut dimid = 0;
-    let cname = super::utils::short_name_to_bytes(name)?;
-    let e = unsafe { with_lock(|| nc_inq_dimid(loc, cname.as_ptr().cast(), &mut dimid)) };
-    if e == NC_EBADDIM {
-        return Ok(None);
+    dimension_from_name(loc, name)
+}
+
+/// Dimension identifiers defined in the group `ncid`, excluding the parents
+fn own_dimids(ncid: nc_type) -> error::Result<Vec<nc_type>> {
+    let mut ndims = 0;
+    checked_with_lock(|| unsafe {
+        nc_inq_dimids(ncid, &mut ndims, std::ptr::null_mut(), <_>::from(false))
+    })?;
+    let mut dimids = vec![0; ndims.try_into()?];
+    checked_with_lock(|| unsafe {
+        nc_inq_dimids(
+            ncid,
+            std::ptr::null_mut(),
+            dimids.as_mut_ptr(),
+            <_>::from(false),
+        )
+    })?;
+    Ok(dimids)
+}
+
+/// Group defining the dimension `dimid` which is visible from `ncid`,
+/// searching the group and then its ancestors
+fn owner(ncid: nc_type, dimid: nc_type) -> error::Result<nc_type> {
+    let mut grp = ncid;
+    loop {
+        if own_dimids(grp)?.contains(&dimid) {
+            return Ok(grp);
+        }
+        match super::hierarchy::parent(grp)? {
+            Some(parent) => grp = parent,
+            None => return Ok(ncid),
+        }
     }
-    error::checked(e)?;
+}
 
-    let mut dimlen = 0;
-    checked_with_lock(|| unsafe { nc_inq_dimlen(loc, dimid, &mut dimlen) })?;
-    if dimlen != 0 {
+/// Unlimited dimensions visible from the group `ncid`
+///
+/// `nc_inq_unlimdims` only gives the dimensions defined in the group itself
+fn visible_unlimdims(ncid: nc_type) -> error::Result<Vec<nc_type>> {
+    let mut unlimdims = vec![];
+    let mut grp = Some(ncid);
+    while let Some(ncid) = grp {
         let mut nunlim = 0;
-        checked_with_lock(|| unsafe { nc_inq_unlimdims(loc, &mut nunlim, std::ptr::null_mut()) })?;
+        checked_with_lock(|| unsafe { nc_inq_unlimdims(ncid, &mut nunlim, std::ptr::null_mut()) })?;
         if nunlim != 0 {
-            let mut unlimdims = Vec::with_capacity(nunlim.try_into()?);
+            let start = unlimdims.len();
+            unlimdims.resize(start + usize::try_from(nunlim)?, 0);
             checked_with_lock(|| unsafe {
-                nc_inq_unlimdims(loc, std::ptr::null_mut(), unlimdims.as_mut_ptr())
+                nc_inq_unlimdims(ncid, std::ptr::null_mut(), unlimdims[start..].as_mut_ptr())
             })?;
-            unsafe { unlimdims.set_len(nunlim.try_into()?) }
-            if unlimdims.contains(&dimid) {
-                dimlen = 0;
-            }
         }
+        grp = super::hierarchy::parent(ncid)?;
     }
+    Ok(unlimdims)
+}
 
-    Ok(Some(Dimension {
+/// Dimension `dimid` visible from the group `ncid`
+fn dimension_at<'f>(
+    ncid: nc_type,
+    dimid: nc_type,
+    unlimdims: &[nc_type],
+) -> error::Result<Dimension<'f>> {
+    let mut dimlen = 0;
+    if !unlimdims.contains(&dimid) {
+        checked_with_lock(|| unsafe { nc_inq_dimlen(ncid, dimid, &mut dimlen) })?;
+    }
+    Ok(Dimension {
         len: core::num::NonZeroUsize::new(dimlen),
-        id: DimensionIdentifier { ncid: loc, dimid },
+        id: DimensionIdentifier { ncid, dimid },
         _group: PhantomData,
-    }))
+    })
 }
 
 pub(crate) fn dimensions_from_location<'g>(
@@ -165,68 +224,30 @@ pub(crate) fn dimensions_from_variable<'g>(
     checked_with_lock(|| unsafe { nc_inq_varndims(ncid, varid, &mut ndims) })?;
     let mut dimids = vec![0; ndims.try_into()?];
     checked_with_lock(|| unsafe { nc_inq_vardimid(ncid, varid, dimids.as_mut_ptr()) })?;
-    let unlimdims = {
-        let mut nunlimdims = 0;
-        checked_with_lock(|| unsafe {
-            nc_inq_unlimdims(ncid, &mut nunlimdims, std::ptr::null_mut())
-        })?;
-        let mut unlimdims = Vec::with_capacity(nunlimdims.try_into()?);
-        checked_with_lock(|| unsafe {
-            nc_inq_unlimdims(ncid, std::ptr::null_mut(), unlimdims.as_mut_ptr())
-        })?;
-        unsafe {
-            unlimdims.set_len(nunlimdims.try_into()?);
-        }
-        unlimdims
-    };
 
-    Ok(dimids.into_iter().map(move |dimid| {
-        let mut dimlen = 0;
-        if !unlimdims.contains(&dimid) {
-            checked_with_lock(|| unsafe { nc_inq_dimlen(ncid, dimid, &mut dimlen) })?;
-        }
-        Ok(Dimension {
-            len: core::num::NonZeroUsize::new(dimlen),
-            id: DimensionIdentifier { ncid, dimid },
-            _group: PhantomData,
-        })
-    }))
+    // Dimensions may be defined in any of the ancestors
+    let unlimdims = visible_unlimdims(ncid)?;
+    Ok(dimids
+        .into_iter()
+        .map(move |dimid| dimension_at(ncid, dimid, &unlimdims)))
 }
 
+/// Find a dimension following the scoping rules of `netCDF-4`:
+/// the group `ncid` is searched first, then its ancestors
 pub(crate) fn dimension_from_name<'f>(
     ncid: nc_type,
     name: &str,
 ) -> error::Result<Option<Dimension<'f>>> {
     let cname = super::utils::short_name_to_bytes(name)?;
     let mut dimid = 0;
+    // `nc_inq_dimid` searches the ancestors, but does not tell which group holds the dimension
     let e = with_lock(|| unsafe { nc_inq_dimid(ncid, cname.as_ptr().cast(), &mut dimid) });
     if e == NC_EBADDIM {
         return Ok(None);
     }
     error::checked(e)?;
 
-    let mut dimlen = 0;
-    checked_with_lock(|| unsafe { nc_inq_dimlen(ncid, dimid, &mut dimlen) }).unwrap();
-    if dimlen != 0 {
-        // Have to check if this dimension is unlimited
-        let mut nunlim = 0;
-        checked_with_lock(|| unsafe { nc_inq_unlimdims(ncid, &mut nunlim, std::ptr::null_mut()) })?;
-        if nunlim != 0 {
-            let mut unlimdims = Vec::with_capacity(nunlim.try_into()?);
-            checked_with_lock(|| unsafe {
-                nc_inq_unlimdims(ncid, std::ptr::null_mut(), unlimdims.as_mut_ptr())
-            })?;
-            unsafe { unlimdims.set_len(nunlim.try_into()?) }
-            if unlimdims.contains(&dimid) {
-                dimlen = 0;
-            }
-        }
-    }
-    Ok(Some(Dimension {
-        len: core::num::NonZeroUsize::new(dimlen),
-        id: super::dimension::DimensionIdentifier { ncid, dimid },
-        _group: PhantomData,
-    }))
+    Ok(Some(dimension_at(ncid, dimid, &visible_unlimdims(ncid)?)?))
 }
 
 pub(crate) fn add_dimension_at<'f>(
@@ -62,6 +62,9 @@ pub enum Error {
     Utf8Conversion(std::string::FromUtf8Error),
     /// String contains NULL characters
     NulError(std::ffi::NulError),
+    /// Operation is not supported by the format of the file,
+    /// e.g. adding a group to a classic file
+    Unsupported(String),
 }
 
 impl Error {
@@ -172,6 +175,7 @@ impl fmt::Display for Error {
             Self::WrongDataset => write!(f, "this identifier does not belong in this dataset"),
             Self::Utf8Conversion(e) => e.fmt(f),
             Self::NulError(e) => write!(f, "string value contains null bytes {e}"),
+            Self::Unsupported(x) => write!(f, "unsupported operation: {x}"),
         }
     }
 }
@@ -11,6 +11,7 @@ use crate::dimension::Dimension;
 use crate::error;
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// An extent of a dimension
 pub enum Extent {
     /// A slice
@@ -244,6 +245,7 @@ impl Extent {
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A selector for putting and getting data along a dataset
 ///
 /// This type can be constructed in many ways
@@ -469,81 +471,97 @@ pub(crate) struct StartCountStrideIterItem {
     pub(crate) is_upwards_limited: bool,
 }
 
-enum StartCountStrideIter<'a> {
-    All(std::slice::Iter<'a, Dimension<'a>>),
-    Extent(std::iter::Zip<std::slice::Iter<'a, Extent>, std::slice::Iter<'a, Dimension<'a>>>),
-}
+impl StartCountStrideIterItem {
+    /// The whole of a dimension of length `len`
+    fn all(len: usize, is_growable: bool) -> Self {
+        Self {
+            start: 0,
+            count: len,
+            stride: 1,
+            is_an_index: false,
+            is_growable,
+            is_upwards_limited: false,
+        }
+    }
 
-impl Iterator for StartCountStrideIter<'_> {
-    type Item = StartCountStrideIterItem;
-    fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            Self::All(iter) => iter.next().map(|dim| Self::Item {
-                start: 0,
-                count: dim.len(),
+    /// `extent` of a dimension of length `len`
+    fn new(extent: &Extent, len: usize, is_growable: bool) -> Self {
+        match *extent {
+            Extent::Index(start) => Self {
+                start,
+                count: 1,
                 stride: 1,
+                is_an_index: true,
+                is_growable,
+                is_upwards_limited: true,
+            },
+            Extent::Slice { start, stride } => stride.try_into().map_or_else(
+                |_| Self {
+                    start,
+                    count: 0,
+                    stride, // negative stride is not used
+                    is_an_index: false,
+                    is_growable,
+                    is_upwards_limited: false,
+                },
+                |stride| Self {
+                    start,
+                    count: (start..len).step_by(stride).count(),
+                    stride: stride.try_into().expect("stride must be < isize::MAX"),
+                    is_an_index: false,
+                    is_growable,
+                    is_upwards_limited: false,
+                },
+            ),
+            Extent::SliceCount {
+                start,
+                count,
+                stride,
+            } => Self {
+                start,
+                count,
+                stride,
                 is_an_index: false,
-                is_growable: dim.is_unlimited(),
-                is_upwards_limited: false,
-            }),
-            Self::Extent(iter) => iter.next().map(|(extent, dim)| match *extent {
-                Extent::Index(start) => Self::Item {
+                is_growable,
+                is_upwards_limited: true,
+            },
+            Extent::SliceEnd { start, end, stride } => stride.try_into().map_or_else(
+                |_| Self {
                     start,
-                    count: 1,
-                    stride: 1,
-                    is_an_index: true,
-                    is_growable: dim.is_unlimited(),
+                    count: 0,
+                    stride, // negative stride is not used
+                    is_an_index: false,
+                    is_growable,
                     is_upwards_limited: true,
                 },
-                Extent::Slice { start, stride } => stride.try_into().map_or_else(
-                    |_| Self::Item {
-                        start,
-                        count: 0,
-                        stride, // negative stride is not used
-                        is_an_index: false,
-                        is_growable: dim.is_unlimited(),
-                        is_upwards_limited: false,
-                    },
-                    |stride| Self::Item {
-                        start,
-                        count: (start..dim.len()).step_by(stride).count(),
-                        stride: stride.try_into().expect("stride must be < isize::MAX"),
-                        is_an_index: false,
-                        is_growable: dim.is_unlimited(),
-                        is_upwards_limited: false,
-                    },
-                ),
-                Extent::SliceCount {
-                    start,
-                    count,
-                    stride,
-                } => Self::Item {
+                |stride| Self {
                     start,
-                    count,
-                    stride,
+                    count: (start..end).step_by(stride).count(),
+                    stride: stride.try_into().expect("stride must be < isize::MAX"),
                     is_an_index: false,
-                    is_growable: dim.is_unlimited(),
+                    is_growable,
                     is_upwards_limited: true,
                 },
-                Extent::SliceEnd { start, end, stride } => stride.try_into().map_or_else(
-                    |_| Self::Item {
-                        start,
-                        count: 0,
-                        stride, // negative stride is not used
-                        is_an_index: false,
-                        is_growable: dim.is_unlimited(),
-                        is_upwards_limited: true,
-                    },
-                    |stride| Self::Item {
-                        start,
-                        count: (start..end).step_by(stride).count(),
-                        stride: stride.try_into().expect("stride must be < isize::MAX"),
-                        is_an_index: false,
-                        is_growable: dim.is_unlimited(),
-                        is_upwards_limited: true,
-                    },
-                ),
-            }),
+            ),
+        }
+    }
+}
+
+enum StartCountStrideIter<'a> {
+    All(std::slice::Iter<'a, Dimension<'a>>),
+    Extent(std::iter::Zip<std::slice::Iter<'a, Extent>, std::slice::Iter<'a, Dimension<'a>>>),
+}
+
+impl Iterator for StartCountStrideIter<'_> {
+    type Item = StartCountStrideIterItem;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::All(iter) => iter
+                .next()
+                .map(|dim| Self::Item::all(dim.len(), dim.is_unlimited())),
+            Self::Extent(iter) => iter
+                .next()
+                .map(|(extent, dim)| Self::Item::new(extent, dim.len(), dim.is_unlimited())),
         }
     }
 }
@@ -583,6 +601,33 @@ impl Extents {
             }
         }
     }
+
+    /// Like [`iter_with_dims`](Self::iter_with_dims), for dimensions
+    /// of the given lengths which can not grow
+    pub(crate) fn items_with_shape(
+        &self,
+        shape: &[usize],
+    ) -> Result<Vec<StartCountStrideIterItem>, error::Error> {
+        match self {
+            Self::All => Ok(shape
+                .iter()
+                .map(|&len| StartCountStrideIterItem::all(len, false))
+                .collect()),
+            Self::Extent(extents) => {
+                if extents.len() != shape.len() {
+                    return Err(error::Error::DimensionMismatch {
+                        wanted: shape.len(),
+                        actual: extents.len(),
+                    });
+                }
+                Ok(extents
+                    .iter()
+                    .zip(shape)
+                    .map(|(extent, &len)| StartCountStrideIterItem::new(extent, len, false))
+                    .collect())
+            }
+        }
+    }
 }
 
 #[cfg(feature = "ndarray")]
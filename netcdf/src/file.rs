@@ -10,7 +10,8 @@ use super::attribute::{Attribute, AttributeValue};
 use super::dimension::{self, Dimension};
 use super::error;
 use super::group::{Group, GroupMut};
-use super::types::{NcTypeDescriptor, NcVariableType};
+use super::hierarchy::Object;
+use super::types::{NcOwnedType, NcTypeDescriptor, NcVariableType};
 use super::variable::{Variable, VariableMut};
 use crate::group::{get_parent_ncid_and_stem, try_get_ncid, try_get_parent_ncid_and_stem};
 use crate::utils::checked_with_lock;
@@ -213,73 +214,107 @@ impl File {
     }
 
     /// Main entrypoint for interacting with the netcdf file.
-    pub fn root(&self) -> Option<Group<'_>> {
-        let mut format = 0;
-        checked_with_lock(|| unsafe { nc_inq_format(self.ncid(), &mut format) }).unwrap();
-
-        match format {
-            NC_FORMAT_NETCDF4 | NC_FORMAT_NETCDF4_CLASSIC => Some(Group {
-                ncid: self.ncid(),
-                _file: PhantomData,
-            }),
-            _ => None,
+    ///
+    /// Every file has a root group, also files in the classic formats.
+    /// Operations requiring `netCDF-4`, such as adding groups or types,
+    /// fail with [`Error::Unsupported`](crate::Error::Unsupported) on these
+    pub fn root(&self) -> Group<'_> {
+        Group {
+            ncid: self.ncid(),
+            _file: PhantomData,
         }
     }
 
-    fn ncid(&self) -> nc_type {
+    pub(crate) fn ncid(&self) -> nc_type {
         self.0.ncid
     }
 
     /// Get a variable from the group
     pub fn variable<'f>(&'f self, name: &str) -> Option<Variable<'f>> {
-        let (ncid, name) =
-            super::group::try_get_parent_ncid_and_stem(self.ncid(), name).unwrap()?;
-        Variable::find_from_name(ncid, name).unwrap()
+        self.try_variable(name).unwrap()
+    }
+    /// Get a variable from the group, `None` if not found
+    pub fn try_variable<'f>(&'f self, name: &str) -> error::Result<Option<Variable<'f>>> {
+        let Some((ncid, name)) = try_get_parent_ncid_and_stem(self.ncid(), name)? else {
+            return Ok(None);
+        };
+        Variable::find_from_name(ncid, name)
     }
     /// Iterate over all variables in a group
     pub fn variables(&self) -> impl Iterator<Item = Variable<'_>> {
+        self.try_variables().unwrap().map(Result::unwrap)
+    }
+    /// Iterate over all variables in a group
+    pub fn try_variables(
+        &self,
+    ) -> error::Result<impl Iterator<Item = error::Result<Variable<'_>>>> {
         super::variable::variables_at_ncid(self.ncid())
-            .unwrap()
-            .map(Result::unwrap)
     }
     /// Get a single attribute
     pub fn attribute<'f>(&'f self, name: &str) -> Option<Attribute<'f>> {
-        let (ncid, name) = try_get_parent_ncid_and_stem(self.ncid(), name).unwrap()?;
-        Attribute::find_from_name(ncid, None, name).unwrap()
+        self.try_attribute(name).unwrap()
+    }
+    /// Get a single attribute, `None` if not found
+    pub fn try_attribute<'f>(&'f self, name: &str) -> error::Result<Option<Attribute<'f>>> {
+        let Some((ncid, name)) = try_get_parent_ncid_and_stem(self.ncid(), name)? else {
+            return Ok(None);
+        };
+        Attribute::find_from_name(ncid, None, name)
+    }
+    /// Get the values of an attribute in the root group as `T`,
+    /// e.g. of a user defined type
+    pub fn attribute_typed<T: NcTypeDescriptor + Copy>(
+        &self,
+        name: &str,
+    ) -> Option<error::Result<Vec<T>>> {
+        self.attribute(name).as_ref().map(Attribute::value_typed)
     }
     /// Get all attributes in the root group
     pub fn attributes(&self) -> impl Iterator<Item = Attribute<'_>> {
+        self.try_attributes().unwrap().map(Result::unwrap)
+    }
+    /// Get all attributes in the root group
+    pub fn try_attributes(
+        &self,
+    ) -> error::Result<impl Iterator<Item = error::Result<Attribute<'_>>>> {
         crate::attribute::AttributeIterator::new(self.0.ncid, None)
-            .unwrap()
-            .map(Result::unwrap)
     }
 
     /// Get a single dimension
     pub fn dimension<'f>(&self, name: &str) -> Option<Dimension<'f>> {
-        let (ncid, name) =
-            super::group::try_get_parent_ncid_and_stem(self.ncid(), name).unwrap()?;
-        super::dimension::dimension_from_name(ncid, name).unwrap()
+        self.try_dimension(name).unwrap()
+    }
+    /// Get a single dimension, `None` if not found
+    pub fn try_dimension<'f>(&self, name: &str) -> error::Result<Option<Dimension<'f>>> {
+        let Some((ncid, name)) = try_get_parent_ncid_and_stem(self.ncid(), name)? else {
+            return Ok(None);
+        };
+        super::dimension::dimension_from_name(ncid, name)
     }
     /// Iterator over all dimensions in the root group
     pub fn dimensions(&self) -> impl Iterator<Item = Dimension<'_>> {
+        self.try_dimensions().unwrap().map(Result::unwrap)
+    }
+    /// Iterator over all dimensions in the root group
+    pub fn try_dimensions(
+        &self,
+    ) -> error::Result<impl Iterator<Item = error::Result<Dimension<'_>>>> {
         super::dimension::dimensions_from_location(self.ncid())
-            .unwrap()
-            .map(Result::unwrap)
     }
     /// Get the length of a dimension
     pub fn dimension_len(&self, name: &str) -> Option<usize> {
-        let (ncid, name) =
-            super::group::try_get_parent_ncid_and_stem(self.ncid(), name).unwrap()?;
-        super::dimension::dimension_from_name(ncid, name)
-            .unwrap()
-            .map(|x| x.len())
+        self.try_dimension_len(name).unwrap()
+    }
+    /// Get the length of a dimension, `None` if not found
+    pub fn try_dimension_len(&self, name: &str) -> error::Result<Option<usize>> {
+        Ok(self.try_dimension(name)?.map(|x| x.len()))
     }
 
-    /// Get a group
+    /// Get a group, `None` if not found or the file does not support groups
     ///
     /// # Errors
     ///
-    /// Not a `netCDF-4` file
+    /// One of the parent groups does not exist
     pub fn group<'f>(&'f self, name: &str) -> error::Result<Option<Group<'f>>> {
         let (ncid, name) = get_parent_ncid_and_stem(self.ncid(), name)?;
         try_get_ncid(ncid, name).map(|ncid: Option<i32>| {
@@ -289,18 +324,51 @@ impl File {
             })
         })
     }
-    /// Iterator over all subgroups in the root group
+    /// Iterator over all subgroups in the root group,
+    /// empty if the file does not support groups
     ///
     /// # Errors
     ///
-    /// Not a `netCDF-4` file
+    /// Netcdf layer could fail
     pub fn groups(&self) -> error::Result<impl Iterator<Item = Group<'_>>> {
         super::group::groups_at_ncid(self.ncid())
     }
 
+    /// Get a group, variable, dimension or attribute by its path
+    ///
+    /// The last component of the path is looked up as a group, variable,
+    /// dimension or attribute, in that order. Attributes of a variable
+    /// are addressed by appending the name to the path of the variable.
+    /// Dimensions are only found in the group defining them, not through
+    /// the groups they are visible from.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = netcdf::open("forecast.nc")?;
+    /// if let Some(netcdf::Object::Variable(t2m)) = file.get_by_path("/forecast/surface/t2m")? {
+    ///     println!("{:?}", t2m.get_values::<f32, _>(..)?);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn get_by_path(&self, path: &str) -> error::Result<Option<Object<'_>>> {
+        super::hierarchy::get_by_path(self.ncid(), path)
+    }
+    /// All objects in the file (depth-first) with their full paths
+    ///
+    /// Attributes, dimensions and variables (each followed by its
+    /// attributes) of a group are visited before the subgroups
+    pub fn walk(&self) -> error::Result<impl Iterator<Item = (String, Object<'_>)>> {
+        Ok(super::hierarchy::walk(self.ncid(), "/")?.into_iter())
+    }
+
     /// Return all types in the root group
     pub fn types(&self) -> error::Result<impl Iterator<Item = NcVariableType>> {
-        super::types::all_at_location(self.ncid()).map(|x| x.map(Result::unwrap))
+        self.try_types().map(|x| x.map(Result::unwrap))
+    }
+    /// Return all types in the root group
+    pub fn try_types(&self) -> error::Result<impl Iterator<Item = error::Result<NcVariableType>>> {
+        super::types::all_at_location(self.ncid())
     }
 
     /// Close the file
@@ -331,15 +399,22 @@ impl std::ops::Deref for FileMut {
 
 impl FileMut {
     /// Mutable access to the root group
-    ///
-    /// Return None if this can't be a root group
-    pub fn root_mut(&mut self) -> Option<GroupMut<'_>> {
-        self.root().map(|root| GroupMut(root, PhantomData))
+    pub fn root_mut(&mut self) -> GroupMut<'_> {
+        GroupMut(self.root(), PhantomData)
     }
     /// Get a mutable variable from the group
     pub fn variable_mut<'f>(&'f mut self, name: &str) -> Option<VariableMut<'f>> {
         self.variable(name).map(|var| VariableMut(var, PhantomData))
     }
+    /// Get a mutable variable from the group, `None` if not found
+    pub fn try_variable_mut<'f>(
+        &'f mut self,
+        name: &str,
+    ) -> error::Result<Option<VariableMut<'f>>> {
+        Ok(self
+            .try_variable(name)?
+            .map(|var| VariableMut(var, PhantomData)))
+    }
     /// Iterate over all variables in the root group, with mutable access
     ///
     /// # Examples
@@ -360,7 +435,7 @@ impl FileMut {
     ///
     /// # Errors
     ///
-    /// File does not support groups
+    /// One of the parent groups does not exist
     pub fn group_mut<'f>(&'f mut self, name: &str) -> error::Result<Option<GroupMut<'f>>> {
         self.group(name)
             .map(|g| g.map(|g| GroupMut(g, PhantomData)))
@@ -369,7 +444,7 @@ impl FileMut {
     ///
     /// # Errors
     ///
-    /// File does not support groups
+    /// Netcdf layer could fail
     pub fn groups_mut(&mut self) -> error::Result<impl Iterator<Item = GroupMut<'_>>> {
         self.groups().map(|g| g.map(|g| GroupMut(g, PhantomData)))
     }
@@ -383,6 +458,17 @@ impl FileMut {
         Attribute::put(ncid, NC_GLOBAL, name, val.into())
     }
 
+    /// Add an attribute of any type to the root group, e.g. a user defined type.
+    /// The type is added to the file if missing
+    pub fn add_attribute_typed<'a, T: NcTypeDescriptor>(
+        &'a mut self,
+        name: &str,
+        values: &[T],
+    ) -> error::Result<Attribute<'a>> {
+        let (ncid, name) = super::group::get_parent_ncid_and_stem(self.ncid(), name)?;
+        Attribute::put_typed(ncid, NC_GLOBAL, name, values)
+    }
+
     /// Adds a dimension with the given name and size. A size of zero gives an unlimited dimension
     pub fn add_dimension<'f>(&'f mut self, name: &str, len: usize) -> error::Result<Dimension<'f>> {
         let (ncid, name) = super::group::get_parent_ncid_and_stem(self.ncid(), name)?;
@@ -472,6 +558,25 @@ impl FileMut {
         super::variable::add_variable_from_identifiers(ncid, name, dims, xtype)
     }
 
+    /// Create a variable of a type which needs conversion to the `netCDF`
+    /// representation, with no data written into it
+    ///
+    /// The types needed by `T` are added if they do not exist.
+    /// Use [`VariableMut::put_owned_values`] to write values.
+    pub fn add_owned_variable<'f, T>(
+        &'f mut self,
+        name: &str,
+        dims: &[&str],
+    ) -> error::Result<VariableMut<'f>>
+    where
+        T: NcOwnedType,
+    {
+        let (ncid, name) = super::group::get_parent_ncid_and_stem(self.ncid(), name)?;
+        let typ = T::Ffi::type_descriptor();
+        super::types::find_or_add_type(ncid, &typ)?;
+        VariableMut::add_from_str(ncid, &typ, name, dims)
+    }
+
     /// Create a Variable containing strings into the dataset, with no data written into it
     ///
     /// Dimensions are identified using the name of the dimension, and will recurse upwards
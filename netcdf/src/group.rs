@@ -8,7 +8,8 @@ use netcdf_sys::*;
 use super::attribute::{Attribute, AttributeValue};
 use super::dimension::Dimension;
 use super::error;
-use super::types::{NcTypeDescriptor, NcVariableType};
+use super::hierarchy::Object;
+use super::types::{NcOwnedType, NcTypeDescriptor, NcVariableType};
 use super::utils::{checked_with_lock, with_lock};
 use super::variable::{Variable, VariableMut};
 
@@ -42,70 +43,150 @@ impl<'f> std::ops::Deref for GroupMut<'f> {
 impl<'f> Group<'f> {
     /// Name of the current group
     pub fn name(&self) -> String {
+        self.try_name().expect("Group did not have a valid name")
+    }
+    /// Name of the current group
+    pub fn try_name(&self) -> error::Result<String> {
         let mut name = vec![0_u8; NC_MAX_NAME as usize + 1];
-        checked_with_lock(|| unsafe { nc_inq_grpname(self.ncid, name.as_mut_ptr().cast()) })
-            .unwrap();
+        checked_with_lock(|| unsafe { nc_inq_grpname(self.ncid, name.as_mut_ptr().cast()) })?;
         let zeropos = name.iter().position(|&x| x == 0).unwrap_or(name.len());
         name.resize(zeropos, 0);
 
-        String::from_utf8(name).expect("Group did not have a valid name")
+        Ok(String::from_utf8(name)?)
     }
     /// Internal ncid of the group
     fn id(&self) -> nc_type {
         self.ncid
     }
 
+    /// Parent of this group, `None` for the root group
+    pub fn parent(&self) -> error::Result<Option<Group<'f>>> {
+        Ok(super::hierarchy::parent(self.ncid)?.map(|ncid| Group {
+            ncid,
+            _file: PhantomData,
+        }))
+    }
+    /// Full path of this group, e.g. `/forecast/surface`
+    pub fn full_path(&self) -> error::Result<String> {
+        super::hierarchy::full_path(self.ncid)
+    }
+    /// Get a group, variable, dimension or attribute by a path relative to
+    /// this group, or to the root group if the path starts with `/`,
+    /// see [`File::get_by_path`](crate::File::get_by_path)
+    pub fn get_by_path<'g>(&'g self, path: &str) -> error::Result<Option<Object<'g>>>
+    where
+        'f: 'g,
+    {
+        super::hierarchy::get_by_path(self.ncid, path)
+    }
+    /// All objects below this group (depth-first) with their full paths
+    pub fn walk<'g>(&'g self) -> error::Result<impl Iterator<Item = (String, Object<'g>)>>
+    where
+        'f: 'g,
+    {
+        Ok(super::hierarchy::walk(self.ncid, &self.full_path()?)?.into_iter())
+    }
+
     /// Get a variable from the group
     pub fn variable<'g>(&'g self, name: &str) -> Option<Variable<'g>>
     where
         'f: 'g,
     {
-        let (ncid, name) = super::group::try_get_parent_ncid_and_stem(self.id(), name).unwrap()?;
-        Variable::find_from_name(ncid, name).unwrap()
+        self.try_variable(name).unwrap()
+    }
+    /// Get a variable from the group, `None` if not found
+    pub fn try_variable<'g>(&'g self, name: &str) -> error::Result<Option<Variable<'g>>>
+    where
+        'f: 'g,
+    {
+        let Some((ncid, name)) = try_get_parent_ncid_and_stem(self.id(), name)? else {
+            return Ok(None);
+        };
+        Variable::find_from_name(ncid, name)
     }
     /// Iterate over all variables in a group
     pub fn variables<'g>(&'g self) -> impl Iterator<Item = Variable<'g>>
+    where
+        'f: 'g,
+    {
+        self.try_variables().unwrap().map(Result::unwrap)
+    }
+    /// Iterate over all variables in a group
+    pub fn try_variables<'g>(
+        &'g self,
+    ) -> error::Result<impl Iterator<Item = error::Result<Variable<'g>>>>
     where
         'f: 'g,
     {
         super::variable::variables_at_ncid(self.id())
-            .unwrap()
-            .map(Result::unwrap)
     }
 
     /// Get a single attribute
     pub fn attribute<'a>(&'a self, name: &str) -> Option<Attribute<'a>> {
-        let (ncid, name) = try_get_parent_ncid_and_stem(self.id(), name).unwrap()?;
-        Attribute::find_from_name(ncid, None, name).unwrap()
+        self.try_attribute(name).unwrap()
+    }
+    /// Get a single attribute, `None` if not found
+    pub fn try_attribute<'a>(&'a self, name: &str) -> error::Result<Option<Attribute<'a>>> {
+        let Some((ncid, name)) = try_get_parent_ncid_and_stem(self.id(), name)? else {
+            return Ok(None);
+        };
+        Attribute::find_from_name(ncid, None, name)
     }
     /// Get all attributes in the group
     pub fn attributes(&self) -> impl Iterator<Item = Attribute<'_>> {
+        self.try_attributes().unwrap().map(Result::unwrap)
+    }
+    /// Get all attributes in the group
+    pub fn try_attributes(
+        &self,
+    ) -> error::Result<impl Iterator<Item = error::Result<Attribute<'_>>>> {
         // Need to lock when reading the first attribute (per group)
         crate::attribute::AttributeIterator::new(self.ncid, None)
-            .unwrap()
-            .map(Result::unwrap)
     }
     /// Get the attribute value
     pub fn attribute_value(&self, name: &str) -> Option<error::Result<AttributeValue>> {
         self.attribute(name).as_ref().map(Attribute::value)
     }
+    /// Get the attribute values as `T`, e.g. of a user defined type
+    pub fn attribute_typed<T: NcTypeDescriptor + Copy>(
+        &self,
+        name: &str,
+    ) -> Option<error::Result<Vec<T>>> {
+        self.attribute(name).as_ref().map(Attribute::value_typed)
+    }
 
     /// Get a single dimension
     pub fn dimension<'g>(&'g self, name: &str) -> Option<Dimension<'g>>
     where
         'f: 'g,
     {
-        let (ncid, name) = super::group::try_get_parent_ncid_and_stem(self.id(), name).unwrap()?;
-        super::dimension::dimension_from_name(ncid, name).unwrap()
+        self.try_dimension(name).unwrap()
+    }
+    /// Get a single dimension, `None` if not found
+    pub fn try_dimension<'g>(&'g self, name: &str) -> error::Result<Option<Dimension<'g>>>
+    where
+        'f: 'g,
+    {
+        let Some((ncid, name)) = try_get_parent_ncid_and_stem(self.id(), name)? else {
+            return Ok(None);
+        };
+        super::dimension::dimension_from_name(ncid, name)
     }
     /// Iterator over all dimensions
     pub fn dimensions<'g>(&'g self) -> impl Iterator<Item = Dimension<'g>>
+    where
+        'f: 'g,
+    {
+        self.try_dimensions().unwrap().map(Result::unwrap)
+    }
+    /// Iterator over all dimensions
+    pub fn try_dimensions<'g>(
+        &'g self,
+    ) -> error::Result<impl Iterator<Item = error::Result<Dimension<'g>>>>
     where
         'f: 'g,
     {
         super::dimension::dimensions_from_location(self.id())
-            .unwrap()
-            .map(Result::unwrap)
     }
 
     /// Get a group
@@ -113,12 +194,20 @@ impl<'f> Group<'f> {
     where
         'f: 'g,
     {
-        // We are in a group, must support netCDF-4
-        let (ncid, name) = get_parent_ncid_and_stem(self.id(), name).unwrap();
-        Some(Group {
-            ncid: try_get_ncid(ncid, name).unwrap()?,
+        self.try_group(name).unwrap()
+    }
+    /// Get a group, `None` if not found
+    pub fn try_group<'g>(&'g self, name: &str) -> error::Result<Option<Group<'g>>>
+    where
+        'f: 'g,
+    {
+        let Some((ncid, name)) = try_get_parent_ncid_and_stem(self.id(), name)? else {
+            return Ok(None);
+        };
+        Ok(try_get_ncid(ncid, name)?.map(|ncid| Group {
+            ncid,
             _file: PhantomData,
-        })
+        }))
     }
     /// Iterator over all subgroups in this group
     pub fn groups<'g>(&'g self) -> impl Iterator<Item = Group<'g>>
@@ -127,12 +216,21 @@ impl<'f> Group<'f> {
     {
         groups_at_ncid(self.id()).unwrap()
     }
+    /// Iterator over all subgroups in this group
+    pub fn try_groups<'g>(&'g self) -> error::Result<impl Iterator<Item = error::Result<Group<'g>>>>
+    where
+        'f: 'g,
+    {
+        Ok(groups_at_ncid(self.id())?.map(Ok))
+    }
 
     /// Return all types in this group
     pub fn types(&self) -> impl Iterator<Item = NcVariableType> {
+        self.try_types().unwrap().map(Result::unwrap)
+    }
+    /// Return all types in this group
+    pub fn try_types(&self) -> error::Result<impl Iterator<Item = error::Result<NcVariableType>>> {
         super::types::all_at_location(self.ncid)
-            .map(|x| x.map(Result::unwrap))
-            .unwrap()
     }
 }
 
@@ -152,6 +250,16 @@ impl<'f> GroupMut<'f> {
         self.variables().map(|var| VariableMut(var, PhantomData))
     }
 
+    /// Get a mutable variable from the group, `None` if not found
+    pub fn try_variable_mut<'g>(&'g mut self, name: &str) -> error::Result<Option<VariableMut<'g>>>
+    where
+        'f: 'g,
+    {
+        Ok(self
+            .try_variable(name)?
+            .map(|v| VariableMut(v, PhantomData)))
+    }
+
     /// Mutable access to subgroup
     pub fn group_mut<'g>(&'g mut self, name: &str) -> Option<GroupMut<'g>>
     where
@@ -159,6 +267,13 @@ impl<'f> GroupMut<'f> {
     {
         self.group(name).map(|g| GroupMut(g, PhantomData))
     }
+    /// Mutable access to subgroup, `None` if not found
+    pub fn try_group_mut<'g>(&'g mut self, name: &str) -> error::Result<Option<GroupMut<'g>>>
+    where
+        'f: 'g,
+    {
+        Ok(self.try_group(name)?.map(|g| GroupMut(g, PhantomData)))
+    }
     /// Iterator over all groups (mutable access)
     pub fn groups_mut<'g>(&'g mut self) -> impl Iterator<Item = GroupMut<'g>>
     where
@@ -186,6 +301,17 @@ impl<'f> GroupMut<'f> {
         Attribute::put(ncid, NC_GLOBAL, name, val.into())
     }
 
+    /// Add an attribute of any type, e.g. a user defined type.
+    /// The type is added to the group if missing
+    pub fn add_attribute_typed<'a, T: NcTypeDescriptor>(
+        &'a mut self,
+        name: &str,
+        values: &[T],
+    ) -> error::Result<Attribute<'a>> {
+        let (ncid, name) = super::group::get_parent_ncid_and_stem(self.id(), name)?;
+        Attribute::put_typed(ncid, NC_GLOBAL, name, values)
+    }
+
     /// Adds a dimension with the given name and size. A size of zero gives an unlimited dimension
     pub fn add_dimension<'g>(&'g mut self, name: &str, len: usize) -> error::Result<Dimension<'g>> {
         let (ncid, name) = super::group::get_parent_ncid_and_stem(self.id(), name)?;
@@ -246,12 +372,12 @@ impl<'f> GroupMut<'f> {
     }
 
     /// Create a variable with the specified type
-    pub fn add_variable_with_type(
-        &'f mut self,
+    pub fn add_variable_with_type<'g>(
+        &'g mut self,
         name: &str,
         dims: &[&str],
         typ: &super::types::NcVariableType,
-    ) -> error::Result<VariableMut<'f>> {
+    ) -> error::Result<VariableMut<'g>> {
         let (ncid, name) = super::group::get_parent_ncid_and_stem(self.id(), name)?;
         VariableMut::add_from_str(ncid, typ, name, dims)
     }
@@ -271,6 +397,26 @@ impl<'f> GroupMut<'f> {
         super::variable::add_variable_from_identifiers(ncid, name, dims, xtype)
     }
 
+    /// Create a variable of a type which needs conversion to the `netCDF`
+    /// representation, with no data written into it
+    ///
+    /// The types needed by `T` are added if they do not exist.
+    /// Use [`VariableMut::put_owned_values`] to write values.
+    pub fn add_owned_variable<'g, T>(
+        &'g mut self,
+        name: &str,
+        dims: &[&str],
+    ) -> error::Result<VariableMut<'g>>
+    where
+        T: NcOwnedType,
+        'f: 'g,
+    {
+        let (ncid, name) = super::group::get_parent_ncid_and_stem(self.id(), name)?;
+        let typ = T::Ffi::type_descriptor();
+        super::types::find_or_add_type(ncid, &typ)?;
+        VariableMut::add_from_str(ncid, &typ, name, dims)
+    }
+
     /// Create a Variable containing strings into the dataset, with no data written into it
     ///
     /// Dimensions are identified using the name of the dimension, and will recurse upwards
@@ -287,10 +433,16 @@ impl<'f> GroupMut<'f> {
 }
 
 pub(crate) fn groups_at_ncid<'f>(ncid: nc_type) -> error::Result<impl Iterator<Item = Group<'f>>> {
-    let mut num_grps = 0;
-    checked_with_lock(|| unsafe { nc_inq_grps(ncid, &mut num_grps, std::ptr::null_mut()) })?;
-    let mut grps = vec![0; num_grps.try_into()?];
-    checked_with_lock(|| unsafe { nc_inq_grps(ncid, std::ptr::null_mut(), grps.as_mut_ptr()) })?;
+    let mut grps = vec![];
+    // Files without groups have no subgroups
+    if is_enhanced_model(ncid)? {
+        let mut num_grps = 0;
+        checked_with_lock(|| unsafe { nc_inq_grps(ncid, &mut num_grps, std::ptr::null_mut()) })?;
+        grps.resize(num_grps.try_into()?, 0);
+        checked_with_lock(|| unsafe {
+            nc_inq_grps(ncid, std::ptr::null_mut(), grps.as_mut_ptr())
+        })?;
+    }
     Ok(grps.into_iter().map(|id| Group {
         ncid: id,
         _file: PhantomData,
@@ -309,13 +461,49 @@ pub(crate) fn add_group_at_path(mut ncid: nc_type, path: &str) -> error::Result<
     add_group(ncid, name)
 }
 
+/// Format of the file, one of the `NC_FORMAT_*` constants
+pub(crate) fn format(ncid: nc_type) -> error::Result<nc_type> {
+    let mut format = 0;
+    checked_with_lock(|| unsafe { nc_inq_format(ncid, &mut format) })?;
+    Ok(format)
+}
+
+/// Does the file support groups and user defined types (the enhanced data model)
+pub(crate) fn is_enhanced_model(ncid: nc_type) -> error::Result<bool> {
+    Ok(format(ncid)? == NC_FORMAT_NETCDF4)
+}
+
+/// Is the file stored using `HDF5`, which supports chunking and filters
+pub(crate) fn is_hdf5(ncid: nc_type) -> error::Result<bool> {
+    Ok(matches!(
+        format(ncid)?,
+        NC_FORMAT_NETCDF4 | NC_FORMAT_NETCDF4_CLASSIC
+    ))
+}
+
+/// Fail with [`Error::Unsupported`](error::Error::Unsupported) if the file
+/// does not support groups and user defined types
+pub(crate) fn require_enhanced_model(ncid: nc_type, what: &str) -> error::Result<()> {
+    if is_enhanced_model(ncid)? {
+        Ok(())
+    } else {
+        Err(error::Error::Unsupported(format!(
+            "{what} requires a netCDF-4 file"
+        )))
+    }
+}
+
 pub(crate) fn add_group(mut ncid: nc_type, name: &str) -> error::Result<nc_type> {
+    require_enhanced_model(ncid, "adding a group")?;
     let byte_name = super::utils::short_name_to_bytes(name)?;
     checked_with_lock(|| unsafe { nc_def_grp(ncid, byte_name.as_ptr().cast(), &mut ncid) })?;
     Ok(ncid)
 }
 
 pub(crate) fn try_get_ncid(mut ncid: nc_type, name: &str) -> error::Result<Option<nc_type>> {
+    if !is_enhanced_model(ncid)? {
+        return Ok(None);
+    }
     let byte_name = super::utils::short_name_to_bytes(name)?;
     let e = with_lock(|| unsafe { nc_inq_grp_ncid(ncid, byte_name.as_ptr().cast(), &mut ncid) });
     if e == NC_ENOGRP {
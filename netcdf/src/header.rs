@@ -0,0 +1,302 @@
+//! Description of the groups, types, dimensions, variables and
+//! attributes of a file, without the data
+//!
+//! With the `serde` feature a [`Header`] can be serialized, e.g. to JSON,
+//! and an empty file can be created from the deserialized description:
+//! ```rust,no_run
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() { /* This test does nothing without serde feature flag */ }
+//! # #[cfg(feature = "serde")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let file = netcdf::open("data.nc")?;
+//! let header = netcdf::header::Header::from_file(&file)?;
+//! let json = serde_json::to_string(&header)?;
+//!
+//! let header: netcdf::header::Header = serde_json::from_str(&json)?;
+//! let _file = header.create("empty.nc")?;
+//! # Ok(()) }
+//! ```
+
+use netcdf_sys::*;
+
+use crate::attribute::AttributeValue;
+use crate::error;
+use crate::group::{format, is_hdf5};
+use crate::types::NcVariableType;
+use crate::variable::{deflate, filters, set_filter, Filter};
+use crate::{File, FileMut, Group, GroupMut, Options, Variable, VariableMut};
+
+/// Identifier of the deflate filter, which is described by [`Compression`]
+const FILTER_DEFLATE: u32 = 1;
+
+/// Format of a file
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Format {
+    /// The classic format
+    Classic,
+    /// The classic format with 64 bit offsets
+    Offset64,
+    /// The classic format with 64 bit data (`CDF-5`)
+    Data64,
+    /// The `netCDF-4` format, stored using `HDF5`
+    Netcdf4,
+    /// The `netCDF-4` format restricted to the classic data model
+    Netcdf4Classic,
+}
+
+impl Format {
+    /// Options to create a file of this format
+    pub fn options(self) -> Options {
+        match self {
+            Self::Classic => Options::empty(),
+            Self::Offset64 => Options::_64BIT_OFFSET,
+            Self::Data64 => Options::_64BIT_DATA,
+            Self::Netcdf4 => Options::NETCDF4,
+            Self::Netcdf4Classic => Options::NETCDF4 | Options::CLASSIC,
+        }
+    }
+
+    fn of(ncid: nc_type) -> error::Result<Self> {
+        Ok(match format(ncid)? {
+            NC_FORMAT_CLASSIC => Self::Classic,
+            NC_FORMAT_64BIT_OFFSET => Self::Offset64,
+            NC_FORMAT_64BIT_DATA => Self::Data64,
+            NC_FORMAT_NETCDF4 => Self::Netcdf4,
+            NC_FORMAT_NETCDF4_CLASSIC => Self::Netcdf4Classic,
+            other => return Err(error::Error::Unsupported(format!("file format {other}"))),
+        })
+    }
+}
+
+/// Description of a file
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Header {
+    /// Format of the file
+    pub format: Format,
+    /// The root group
+    pub root: GroupHeader,
+}
+
+/// Description of a group
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupHeader {
+    /// Name of the group, `/` for the root group
+    pub name: String,
+    /// User defined types
+    pub types: Vec<NcVariableType>,
+    /// Dimensions
+    pub dimensions: Vec<DimensionHeader>,
+    /// Variables
+    pub variables: Vec<VariableHeader>,
+    /// Attributes of the group
+    pub attributes: Vec<AttributeHeader>,
+    /// Subgroups
+    pub groups: Vec<GroupHeader>,
+}
+
+/// Description of a dimension
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DimensionHeader {
+    /// Name of the dimension
+    pub name: String,
+    /// Length of the dimension, the current length if unlimited
+    pub len: usize,
+    /// The dimension can grow
+    pub unlimited: bool,
+}
+
+/// Description of an attribute
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttributeHeader {
+    /// Name of the attribute
+    pub name: String,
+    /// Value of the attribute
+    pub value: AttributeValue,
+}
+
+/// Deflate compression of a variable
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compression {
+    /// Deflate level, 1 to 9
+    pub deflate_level: nc_type,
+    /// Bytes are shuffled before deflating
+    pub shuffle: bool,
+}
+
+/// Description of a variable
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VariableHeader {
+    /// Name of the variable
+    pub name: String,
+    /// Type of the variable
+    pub vartype: NcVariableType,
+    /// Names of the dimensions, which may be defined in a parent group
+    pub dimensions: Vec<String>,
+    /// Chunk size along each dimension, `None` if contiguous
+    pub chunking: Option<Vec<usize>>,
+    /// Deflate compression
+    pub compression: Option<Compression>,
+    /// Filters other than deflate, in the order they are applied
+    pub filters: Vec<Filter>,
+    /// Attributes of the variable
+    pub attributes: Vec<AttributeHeader>,
+}
+
+impl Header {
+    /// Describe a file
+    pub fn from_file(file: &File) -> error::Result<Self> {
+        Ok(Self {
+            format: Format::of(file.ncid())?,
+            root: GroupHeader::from_group(&file.root(), is_hdf5(file.ncid())?)?,
+        })
+    }
+
+    /// Create a file of the described format, with all items but no data
+    pub fn create<P>(&self, path: P) -> error::Result<FileMut>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let mut file = crate::create_with(path, self.format.options())?;
+        self.write(&mut file)?;
+        Ok(file)
+    }
+
+    /// Add the described items to a file, which must not already contain them
+    pub fn write(&self, file: &mut FileMut) -> error::Result<()> {
+        let storage = is_hdf5(file.ncid())?;
+        self.root.write(&mut file.root_mut(), storage)
+    }
+}
+
+fn attributes<'a>(
+    attributes: impl Iterator<Item = error::Result<crate::Attribute<'a>>>,
+) -> error::Result<Vec<AttributeHeader>> {
+    attributes
+        .map(|attr| {
+            let attr = attr?;
+            Ok(AttributeHeader {
+                name: attr.try_name()?.to_owned(),
+                value: attr.value()?,
+            })
+        })
+        .collect()
+}
+
+impl GroupHeader {
+    fn from_group(group: &Group, storage: bool) -> error::Result<Self> {
+        let dimensions = group
+            .try_dimensions()?
+            .map(|dim| {
+                let dim = dim?;
+                Ok(DimensionHeader {
+                    name: dim.try_name()?,
+                    len: dim.try_len()?,
+                    unlimited: dim.is_unlimited(),
+                })
+            })
+            .collect::<error::Result<_>>()?;
+        let variables = group
+            .try_variables()?
+            .map(|var| VariableHeader::from_variable(&var?, storage))
+            .collect::<error::Result<_>>()?;
+        let groups = group
+            .try_groups()?
+            .map(|child| Self::from_group(&child?, storage))
+            .collect::<error::Result<_>>()?;
+        Ok(Self {
+            name: group.try_name()?,
+            types: group.try_types()?.collect::<error::Result<_>>()?,
+            dimensions,
+            variables,
+            attributes: attributes(group.try_attributes()?)?,
+            groups,
+        })
+    }
+
+    fn write(&self, group: &mut GroupMut, storage: bool) -> error::Result<()> {
+        for typ in &self.types {
+            if crate::types::find_type(group.ncid, typ)?.is_none() {
+                group.add_type_from_descriptor(typ.clone())?;
+            }
+        }
+        for dim in &self.dimensions {
+            if dim.unlimited {
+                group.add_unlimited_dimension(&dim.name)?;
+            } else {
+                group.add_dimension(&dim.name, dim.len)?;
+            }
+        }
+        for attr in &self.attributes {
+            group.add_attribute(&attr.name, attr.value.clone())?;
+        }
+        for var in &self.variables {
+            let dims = var
+                .dimensions
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            let mut variable = group.add_variable_with_type(&var.name, &dims, &var.vartype)?;
+            if storage {
+                var.set_storage(&mut variable)?;
+            }
+            for attr in &var.attributes {
+                variable.put_attribute(&attr.name, attr.value.clone())?;
+            }
+        }
+        for child in &self.groups {
+            let mut subgroup = group.add_group(&child.name)?;
+            child.write(&mut subgroup, storage)?;
+        }
+        Ok(())
+    }
+}
+
+impl VariableHeader {
+    fn from_variable(var: &Variable, storage: bool) -> error::Result<Self> {
+        let (chunking, compression, filters) = if storage {
+            let (deflate_level, shuffle) = deflate(var)?;
+            let compression = deflate_level.map(|deflate_level| Compression {
+                deflate_level,
+                shuffle,
+            });
+            let mut filters = filters(var)?;
+            filters.retain(|filter| filter.id != FILTER_DEFLATE);
+            (var.chunking()?, compression, filters)
+        } else {
+            (None, None, vec![])
+        };
+        Ok(Self {
+            name: var.try_name()?,
+            vartype: var.try_vartype()?,
+            dimensions: var
+                .dimensions()
+                .iter()
+                .map(crate::Dimension::try_name)
+                .collect::<error::Result<_>>()?,
+            chunking,
+            compression,
+            filters,
+            attributes: attributes(var.try_attributes()?)?,
+        })
+    }
+
+    fn set_storage(&self, var: &mut VariableMut) -> error::Result<()> {
+        if let Some(chunking) = &self.chunking {
+            var.set_chunking(chunking)?;
+        }
+        if let Some(compression) = self.compression {
+            var.set_compression(compression.deflate_level, compression.shuffle)?;
+        }
+        for filter in &self.filters {
+            set_filter(var, filter)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+//! Navigate the hierarchy of groups using full paths
+
+use std::marker::PhantomData;
+
+use netcdf_sys::*;
+
+use super::attribute::{Attribute, AttributeIterator};
+use super::dimension::{dimension_from_name, dimensions_from_location, Dimension};
+use super::error;
+use super::group::{groups_at_ncid, is_enhanced_model, try_get_ncid, Group};
+use super::utils::{checked_with_lock, with_lock};
+use super::variable::{variables_at_ncid, Variable};
+
+/// Any item of a file which can be addressed by a path
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum Object<'f> {
+    /// A group
+    Group(Group<'f>),
+    /// A variable
+    Variable(Variable<'f>),
+    /// A dimension
+    Dimension(Dimension<'f>),
+    /// An attribute of a group or a variable
+    Attribute(Attribute<'f>),
+}
+
+/// Parent of the group `ncid`, `None` for the root group
+pub(crate) fn parent(ncid: nc_type) -> error::Result<Option<nc_type>> {
+    if !is_enhanced_model(ncid)? {
+        return Ok(None);
+    }
+    let mut parent = 0;
+    let e = with_lock(|| unsafe { nc_inq_grp_parent(ncid, &mut parent) });
+    if e == NC_ENOGRP {
+        return Ok(None);
+    }
+    error::checked(e)?;
+    Ok(Some(parent))
+}
+
+/// Root group of the file holding the group `ncid`
+pub(crate) fn root(mut ncid: nc_type) -> error::Result<nc_type> {
+    while let Some(p) = parent(ncid)? {
+        ncid = p;
+    }
+    Ok(ncid)
+}
+
+/// Find a variable referenced by name from an attribute in the group `ncid`
+///
+/// Absolute paths are resolved from the root group and relative paths
+/// from the group, where `..` is the parent group. Plain names are
+/// searched for in the group and, if `search_ancestors`, in its ancestors.
+pub(crate) fn find_referenced_variable<'g>(
+    mut ncid: nc_type,
+    name: &str,
+    search_ancestors: bool,
+) -> error::Result<Option<Variable<'g>>> {
+    if !name.contains('/') {
+        loop {
+            if let Some(var) = Variable::find_from_name(ncid, name)? {
+                return Ok(Some(var));
+            }
+            match parent(ncid)? {
+                Some(p) if search_ancestors => ncid = p,
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    let relative = match name.strip_prefix('/') {
+        Some(relative) => {
+            ncid = root(ncid)?;
+            relative
+        }
+        None => name,
+    };
+    let mut components = relative.split('/');
+    let stem = components.next_back().unwrap_or_default();
+    for component in components {
+        ncid = match component {
+            "" | "." => ncid,
+            ".." => match parent(ncid)? {
+                Some(p) => p,
+                None => return Ok(None),
+            },
+            component => match try_get_ncid(ncid, component)? {
+                Some(child) => child,
+                None => return Ok(None),
+            },
+        };
+    }
+    Variable::find_from_name(ncid, stem)
+}
+
+/// Full path of the group `ncid`, `/` for the root group
+pub(crate) fn full_path(ncid: nc_type) -> error::Result<String> {
+    if !is_enhanced_model(ncid)? {
+        return Ok("/".to_owned());
+    }
+    let mut len = 0;
+    checked_with_lock(|| unsafe { nc_inq_grpname_full(ncid, &mut len, std::ptr::null_mut()) })?;
+    let mut name = vec![0_u8; len + 1];
+    checked_with_lock(|| unsafe {
+        nc_inq_grpname_full(ncid, std::ptr::null_mut(), name.as_mut_ptr().cast())
+    })?;
+    name.truncate(len);
+    Ok(String::from_utf8(name)?)
+}
+
+/// Join a child to the path of a group
+pub(crate) fn join(path: &str, name: &str) -> String {
+    if path.ends_with('/') {
+        format!("{path}{name}")
+    } else {
+        format!("{path}/{name}")
+    }
+}
+
+/// Resolve `path`, relative to the group `ncid` or, if it starts
+/// with `/`, to the root group
+///
+/// The last component is looked up as a group, variable, dimension or
+/// attribute, in that order. An attribute of a variable is addressed
+/// by appending its name to the path of the variable.
+pub(crate) fn get_by_path<'f>(mut ncid: nc_type, path: &str) -> error::Result<Option<Object<'f>>> {
+    let path = match path.strip_prefix('/') {
+        Some(path) => {
+            ncid = root(ncid)?;
+            path
+        }
+        None => path,
+    };
+    let path = path.trim_end_matches('/');
+    if path.is_empty() {
+        return Ok(Some(Object::Group(Group {
+            ncid,
+            _file: PhantomData,
+        })));
+    }
+    let mut components = path.split('/').collect::<Vec<_>>();
+    let name = components.pop().unwrap();
+
+    for (i, &component) in components.iter().enumerate() {
+        ncid = match try_get_ncid(ncid, component)? {
+            Some(ncid) => ncid,
+            // Only the last parent may be a variable holding the attribute
+            None if i + 1 == components.len() => {
+                let Some(var) = Variable::find_from_name(ncid, component)? else {
+                    return Ok(None);
+                };
+                return Ok(
+                    Attribute::find_from_name(ncid, Some(var.varid), name)?.map(Object::Attribute)
+                );
+            }
+            None => return Ok(None),
+        };
+    }
+
+    if let Some(ncid) = try_get_ncid(ncid, name)? {
+        return Ok(Some(Object::Group(Group {
+            ncid,
+            _file: PhantomData,
+        })));
+    }
+    if let Some(var) = Variable::find_from_name(ncid, name)? {
+        return Ok(Some(Object::Variable(var)));
+    }
+    // Dimensions of the ancestors are visible by name, but are not part of this path
+    if let Some(dim) = dimension_from_name(ncid, name)? {
+        if dim.group()?.ncid == ncid {
+            return Ok(Some(Object::Dimension(dim)));
+        }
+    }
+    Ok(Attribute::find_from_name(ncid, None, name)?.map(Object::Attribute))
+}
+
+/// Visit every object below the group `ncid` depth-first
+///
+/// Attributes, dimensions and variables (each followed by its attributes)
+/// of a group are visited before the subgroups.
+pub(crate) fn walk<'f>(ncid: nc_type, path: &str) -> error::Result<Vec<(String, Object<'f>)>> {
+    let mut objects = vec![];
+    for attr in AttributeIterator::new(ncid, None)? {
+        let attr = attr?;
+        objects.push((join(path, attr.try_name()?), Object::Attribute(attr)));
+    }
+    for dim in dimensions_from_location(ncid)? {
+        let dim = dim?;
+        objects.push((join(path, &dim.try_name()?), Object::Dimension(dim)));
+    }
+    for var in variables_at_ncid(ncid)? {
+        let var = var?;
+        let varpath = join(path, &var.try_name()?);
+        let attrs = AttributeIterator::new(ncid, Some(var.varid))?.collect::<Vec<_>>();
+        objects.push((varpath.clone(), Object::Variable(var)));
+        for attr in attrs {
+            let attr = attr?;
+            objects.push((join(&varpath, attr.try_name()?), Object::Attribute(attr)));
+        }
+    }
+    for group in groups_at_ncid(ncid)? {
+        let grppath = join(path, &group.try_name()?);
+        let children = walk(group.ncid, &grppath)?;
+        objects.push((grppath, Object::Group(group)));
+        objects.extend(children);
+    }
+    Ok(objects)
+}
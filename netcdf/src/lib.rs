@@ -132,21 +132,35 @@
 
 use netcdf_sys::nc_type;
 
+pub mod aggregation;
 pub(crate) mod attribute;
+pub mod cdl;
+pub mod cf;
+#[cfg(feature = "num-complex")]
+pub mod complex;
+pub mod copy;
+pub mod dataset;
+pub mod diff;
 pub(crate) mod dimension;
 pub(crate) mod error;
 pub(crate) mod extent;
 pub(crate) mod file;
 pub(crate) mod group;
+pub mod header;
+pub(crate) mod hierarchy;
+pub mod metadata;
 #[cfg(feature = "mpi")]
 pub(crate) mod par;
 pub(crate) mod putget;
 #[cfg(feature = "4.9.2")]
 pub mod rc;
 pub mod types;
+pub mod units;
+pub mod value;
 pub(crate) mod variable;
 
 pub use attribute::{Attribute, AttributeValue};
+pub use dataset::NcDataset;
 pub use dimension::{Dimension, DimensionIdentifier};
 pub use error::{Error, Result};
 pub use extent::{Extent, Extents};
@@ -155,11 +169,13 @@ pub use file::FileMem;
 pub(crate) use file::RawFile;
 pub use file::{File, FileMut, Options};
 pub use group::{Group, GroupMut};
+pub use hierarchy::Object;
+pub use metadata::NcAttributes;
 #[cfg(feature = "derive")]
-pub use netcdf_derive::NcType;
+pub use netcdf_derive::{NcAttributes, NcDataset, NcType};
 #[doc(inline)]
 pub use types::NcTypeDescriptor;
-pub use variable::{Endianness, Variable, VariableMut};
+pub use variable::{Endianness, Filter, Variable, VariableMut};
 
 /// Open a netcdf file in create mode
 ///
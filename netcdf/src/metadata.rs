@@ -0,0 +1,185 @@
+//! Typed sets of attributes
+//!
+//! Implement [`NcAttributes`] using `#[derive(NcAttributes)]` (requires the
+//! `derive` feature) on a struct where each field holds the value of an attribute:
+//! ```rust,no_run
+//! # #[cfg(not(feature = "derive"))]
+//! # fn main() { /* This test does nothing without derive feature flag */ }
+//! # #[cfg(feature = "derive")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use netcdf::NcAttributes;
+//!
+//! #[derive(NcAttributes)]
+//! struct Metadata {
+//!     title: String,
+//!     history: Option<String>,
+//!     valid_range: [f32; 2],
+//!     #[netcdf(rename = "Conventions")]
+//!     conventions: String,
+//! }
+//!
+//! let file = netcdf::open("product.nc")?;
+//! let metadata = Metadata::from_attributes(file.attributes())?;
+//!
+//! let mut file = netcdf::create("copy.nc")?;
+//! metadata.put_attributes(&mut file)?;
+//! # Ok(()) }
+//! ```
+//!
+//! Attributes which are not fields of the struct are ignored when reading.
+//! Fields of type `Option<T>` may be missing, and are not written when `None`.
+
+use crate::attribute::{Attribute, AttributeValue};
+use crate::error;
+use crate::file::FileMut;
+use crate::group::GroupMut;
+use crate::variable::VariableMut;
+
+/// A set of attributes, usually derived using `#[derive(NcAttributes)]`
+pub trait NcAttributes: Sized {
+    /// Read the attributes, e.g. from [`Variable::attributes`](crate::Variable::attributes)
+    fn from_attributes<'a>(attributes: impl Iterator<Item = Attribute<'a>>) -> error::Result<Self>;
+    /// Write the attributes to a variable or group
+    fn put_attributes(&self, target: &mut impl AttributeTarget) -> error::Result<()>;
+}
+
+/// Something attributes can be written to
+pub trait AttributeTarget {
+    /// Write a single attribute
+    fn put_attribute_value(&mut self, name: &str, value: AttributeValue) -> error::Result<()>;
+}
+
+impl AttributeTarget for VariableMut<'_> {
+    fn put_attribute_value(&mut self, name: &str, value: AttributeValue) -> error::Result<()> {
+        self.put_attribute(name, value).map(|_| ())
+    }
+}
+
+impl AttributeTarget for GroupMut<'_> {
+    fn put_attribute_value(&mut self, name: &str, value: AttributeValue) -> error::Result<()> {
+        self.add_attribute(name, value).map(|_| ())
+    }
+}
+
+impl AttributeTarget for FileMut {
+    fn put_attribute_value(&mut self, name: &str, value: AttributeValue) -> error::Result<()> {
+        self.add_attribute(name, value).map(|_| ())
+    }
+}
+
+/// Value of a single attribute in a [`NcAttributes`]
+pub trait AttributeField: Sized {
+    /// Convert from the value stored in the file
+    fn from_value(value: AttributeValue) -> error::Result<Self>;
+    /// Value to store in the file, `None` if the attribute should not be written
+    fn to_value(&self) -> Option<AttributeValue>;
+    /// Value to use when the attribute is missing, `None` if it is required
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+/// Split a value into its elements
+fn elements(value: AttributeValue) -> Vec<AttributeValue> {
+    fn split<T: Into<AttributeValue>>(xs: Vec<T>) -> Vec<AttributeValue> {
+        xs.into_iter().map(Into::into).collect()
+    }
+    match value {
+        AttributeValue::Uchars(xs) => split(xs),
+        AttributeValue::Schars(xs) => split(xs),
+        AttributeValue::Ushorts(xs) => split(xs),
+        AttributeValue::Shorts(xs) => split(xs),
+        AttributeValue::Uints(xs) => split(xs),
+        AttributeValue::Ints(xs) => split(xs),
+        AttributeValue::Ulonglongs(xs) => split(xs),
+        AttributeValue::Longlongs(xs) => split(xs),
+        AttributeValue::Floats(xs) => split(xs),
+        AttributeValue::Doubles(xs) => split(xs),
+        AttributeValue::Strs(xs) => split(xs),
+        value => vec![value],
+    }
+}
+
+macro_rules! impl_scalar {
+    ($ty: ty) => {
+        impl AttributeField for $ty {
+            fn from_value(value: AttributeValue) -> error::Result<Self> {
+                let mut elements = elements(value);
+                if elements.len() != 1 {
+                    return Err(format!("expected a single value, found {}", elements.len()).into());
+                }
+                let value = elements.remove(0);
+                let found = format!("{value:?}");
+                Self::try_from(value).map_err(|e| {
+                    format!("can not convert {found} to {}: {e}", stringify!($ty)).into()
+                })
+            }
+            fn to_value(&self) -> Option<AttributeValue> {
+                Some(self.clone().into())
+            }
+        }
+    };
+}
+
+impl_scalar!(u8);
+impl_scalar!(u16);
+impl_scalar!(u32);
+impl_scalar!(u64);
+impl_scalar!(i8);
+impl_scalar!(i16);
+impl_scalar!(i32);
+impl_scalar!(i64);
+impl_scalar!(f32);
+impl_scalar!(f64);
+impl_scalar!(String);
+
+impl<T> AttributeField for Vec<T>
+where
+    T: AttributeField + Clone,
+    Vec<T>: Into<AttributeValue>,
+{
+    fn from_value(value: AttributeValue) -> error::Result<Self> {
+        elements(value).into_iter().map(T::from_value).collect()
+    }
+    fn to_value(&self) -> Option<AttributeValue> {
+        Some(self.clone().into())
+    }
+}
+
+impl<T, const N: usize> AttributeField for [T; N]
+where
+    T: AttributeField + Clone,
+    Vec<T>: Into<AttributeValue>,
+{
+    fn from_value(value: AttributeValue) -> error::Result<Self> {
+        let values = Vec::<T>::from_value(value)?;
+        let len = values.len();
+        values
+            .try_into()
+            .map_err(|_| format!("expected {N} values, found {len}").into())
+    }
+    fn to_value(&self) -> Option<AttributeValue> {
+        Some(self.to_vec().into())
+    }
+}
+
+impl<T: AttributeField> AttributeField for Option<T> {
+    fn from_value(value: AttributeValue) -> error::Result<Self> {
+        T::from_value(value).map(Some)
+    }
+    fn to_value(&self) -> Option<AttributeValue> {
+        self.as_ref().and_then(T::to_value)
+    }
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// Convert the value of the attribute `name`, which was `None` if not found
+#[doc(hidden)]
+pub fn field<T: AttributeField>(name: &str, value: Option<AttributeValue>) -> error::Result<T> {
+    match value {
+        Some(value) => T::from_value(value).map_err(|e| format!("attribute {name}: {e}").into()),
+        None => T::missing().ok_or_else(|| error::Error::NotFound(format!("attribute {name}"))),
+    }
+}
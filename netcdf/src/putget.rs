@@ -7,6 +7,17 @@ fn conversion_supported(from: &NcVariableType, to: &NcVariableType) -> bool {
             &NcVariableType::Int(_) | &NcVariableType::Float(_),
             &NcVariableType::Int(_) | &NcVariableType::Float(_),
         ) => true,
+        (from, to) => same_layout(from, to),
+    }
+}
+
+/// Whether both types are laid out identically in memory,
+/// ragged arrays are compared by their base type and not by name
+fn same_layout(from: &NcVariableType, to: &NcVariableType) -> bool {
+    match (from, to) {
+        (NcVariableType::Vlen(from), NcVariableType::Vlen(to)) => {
+            same_layout(&from.basetype, &to.basetype)
+        }
         (from, to) => from == to,
     }
 }
@@ -212,6 +223,32 @@ pub fn get_raw_values_into(
     Ok(())
 }
 
+/// Non-typechecked version of put_vars
+/// to support putting a bag of bytes in the layout of the variable type
+pub(crate) fn put_raw_values(
+    variable: &mut crate::VariableMut,
+    start: &[usize],
+    count: &[usize],
+    stride: &[isize],
+    buffer: &[u8],
+) -> crate::error::Result<()> {
+    let number_of_elements = count.iter().copied().fold(1_usize, usize::saturating_mul);
+    let varsize = variable.vartype().size();
+    if number_of_elements * varsize != buffer.len() {
+        return Err("Buffer is not of requisite size".into());
+    }
+    checked_with_lock(|| unsafe {
+        netcdf_sys::nc_put_vars(
+            variable.ncid,
+            variable.varid,
+            start.as_ptr(),
+            count.as_ptr(),
+            stride.as_ptr(),
+            buffer.as_ptr().cast(),
+        )
+    })
+}
+
 #[allow(clippy::too_many_lines)]
 fn put_vars_mono(
     var: &mut crate::VariableMut,
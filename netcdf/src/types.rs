@@ -41,6 +41,13 @@ use crate::utils::{checked_with_lock, with_lock};
 /// # #[cfg(feature = "derive")]
 /// #[repr(C)]
 /// #[derive(netcdf::NcType, Debug, Copy, Clone)]
+/// struct Padded {
+///     a: u8,
+///     b: f64,
+/// }
+/// # #[cfg(feature = "derive")]
+/// #[repr(C)]
+/// #[derive(netcdf::NcType, Debug, Copy, Clone)]
 /// struct Arrayed {
 ///     a: [[u8; 3]; 5],
 ///     b: i8,
@@ -93,8 +100,10 @@ use crate::utils::{checked_with_lock, with_lock};
 /// }
 /// ```
 /// ## Vlen type
-/// This type *must* match [`netcdf_sys::nc_vlen_t`]. Be aware that reading using this
-/// type means the memory is backed by `netCDF` and should be
+/// Prefer [`Variable::get_owned_values::<Vec<T>>`](crate::Variable::get_owned_values) and
+/// [`VariableMut::put_owned_values`](crate::VariableMut::put_owned_values) which handle the
+/// memory management. The type below *must* match [`netcdf_sys::nc_vlen_t`].
+/// Be aware that reading using this type means the memory is backed by `netCDF` and should be
 /// freed using [`netcdf_sys::nc_free_vlen`] or [`netcdf_sys::nc_free_vlens`]
 /// to avoid memory leaks.
 /// ```rust,no_run
@@ -147,6 +156,7 @@ pub unsafe trait NcTypeDescriptor {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A `netCDF` type
 ///
 /// This enum contains all variants of types allowed by `netCDF`.
@@ -183,10 +193,33 @@ impl NcVariableType {
             Self::Char => 1,
         }
     }
+    /// Name of the type as used in CDL, e.g. `ubyte` or
+    /// the name of a user defined type
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Compound(x) => &x.name,
+            Self::Opaque(x) => &x.name,
+            Self::Enum(x) => &x.name,
+            Self::Vlen(x) => &x.name,
+            Self::Int(IntType::U8) => "ubyte",
+            Self::Int(IntType::I8) => "byte",
+            Self::Int(IntType::U16) => "ushort",
+            Self::Int(IntType::I16) => "short",
+            Self::Int(IntType::U32) => "uint",
+            Self::Int(IntType::I32) => "int",
+            Self::Int(IntType::U64) => "uint64",
+            Self::Int(IntType::I64) => "int64",
+            Self::Float(FloatType::F32) => "float",
+            Self::Float(FloatType::F64) => "double",
+            Self::Char => "char",
+            Self::String => "string",
+        }
+    }
 }
 
 /// Opaque blob of bytes with a name
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpaqueType {
     /// Name of type
     pub name: String,
@@ -201,6 +234,7 @@ impl OpaqueType {
 
 /// Integer type used in `netCDF`
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum IntType {
     U8,
@@ -226,6 +260,7 @@ impl IntType {
 
 /// Floating type used in `netCDF`
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum FloatType {
     F32,
@@ -242,6 +277,7 @@ impl FloatType {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Field of a compound struct
 pub struct CompoundTypeField {
     /// Name of the compound field
@@ -256,6 +292,7 @@ pub struct CompoundTypeField {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Compound/record type
 pub struct CompoundType {
     /// Name of the compound
@@ -308,6 +345,7 @@ impl PartialEq for CompoundType {
 impl Eq for CompoundType {}
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Inner values of the enum type
 ///
 /// `netCDF` only supports integer types
@@ -357,6 +395,7 @@ from_vec!(i32, Self::I32);
 from_vec!(i64, Self::I64);
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Enum type
 pub struct EnumType {
     /// Name of enum
@@ -430,6 +469,7 @@ impl PartialEq for EnumType {
 impl Eq for EnumType {}
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Ragged array
 pub struct VlenType {
     /// Name of type
@@ -438,6 +478,14 @@ pub struct VlenType {
     pub basetype: Box<NcVariableType>,
 }
 impl VlenType {
+    /// Ragged array of `T`, named after the base type (e.g. `int_vlen`)
+    pub fn of<T: NcTypeDescriptor>() -> Self {
+        let basetype = T::type_descriptor();
+        Self {
+            name: format!("{}_vlen", basetype.name()),
+            basetype: Box::new(basetype),
+        }
+    }
     #[allow(clippy::unused_self)]
     /// Size in bytes
     fn size(&self) -> usize {
@@ -536,6 +584,14 @@ impl_arrayed!(i64);
 impl_arrayed!(f32);
 impl_arrayed!(f64);
 
+/// Find or add a type and the types it depends on, returning its id
+pub(crate) fn find_or_add_type(ncid: nc_type, typ: &NcVariableType) -> Result<nc_type> {
+    match find_type(ncid, typ)? {
+        Some(id) => Ok(id),
+        None => add_type(ncid, typ.clone(), true),
+    }
+}
+
 /// Find a type from a given descriptor
 pub(crate) fn find_type(ncid: nc_type, typ: &NcVariableType) -> Result<Option<nc_type>> {
     match *typ {
@@ -554,6 +610,10 @@ pub(crate) fn find_type(ncid: nc_type, typ: &NcVariableType) -> Result<Option<nc
         _ => {}
     }
 
+    if !crate::group::is_enhanced_model(ncid)? {
+        return Ok(None);
+    }
+
     let name = match &typ {
         NcVariableType::Compound(x) => &x.name,
         NcVariableType::Opaque(x) => &x.name,
@@ -584,6 +644,9 @@ pub(crate) fn add_type(ncid: nc_type, typ: NcVariableType, recursive: bool) -> R
         | NcVariableType::Float(_)
         | NcVariableType::String
         | NcVariableType::Char => Err("basic type can not be added".into()),
+        _ if !crate::group::is_enhanced_model(ncid)? => Err(crate::Error::Unsupported(
+            "adding a user defined type requires a netCDF-4 file".into(),
+        )),
         NcVariableType::Opaque(x) => {
             let name = crate::utils::short_name_to_bytes(&x.name)?;
             let mut id = 0;
@@ -878,7 +941,7 @@ pub(crate) fn read_type(ncid: nc_type, xtype: nc_type) -> Result<NcVariableType>
 pub(crate) fn all_at_location(
     ncid: nc_type,
 ) -> Result<impl Iterator<Item = Result<NcVariableType>>> {
-    let typeids = {
+    let typeids = if crate::group::is_enhanced_model(ncid)? {
         let mut num_typeids = 0;
         checked_with_lock(|| unsafe {
             netcdf_sys::nc_inq_typeids(ncid, &mut num_typeids, std::ptr::null_mut())
@@ -888,15 +951,178 @@ pub(crate) fn all_at_location(
             netcdf_sys::nc_inq_typeids(ncid, std::ptr::null_mut(), typeids.as_mut_ptr())
         })?;
         typeids
+    } else {
+        // Files without user defined types
+        vec![]
     };
     Ok(typeids.into_iter().map(move |x| read_type(ncid, x)))
 }
 
 #[repr(transparent)]
 /// `NC_STRING` compatible struct, no drop implementation, use with caution
-pub(crate) struct NcString(pub(crate) *mut std::ffi::c_char);
+///
+/// This is the representation of [`String`] used by [`NcOwnedType`]
+pub struct NcString(pub(crate) *mut std::ffi::c_char);
 unsafe impl NcTypeDescriptor for NcString {
     fn type_descriptor() -> NcVariableType {
         NcVariableType::String
     }
 }
+
+#[repr(transparent)]
+/// [`netcdf_sys::nc_vlen_t`] holding elements of type `T`,
+/// no drop implementation, use with caution
+///
+/// This is the representation of [`Vec`] used by [`NcOwnedType`]
+pub struct NcVlen<T>(netcdf_sys::nc_vlen_t, std::marker::PhantomData<T>);
+unsafe impl<T: NcTypeDescriptor> NcTypeDescriptor for NcVlen<T> {
+    fn type_descriptor() -> NcVariableType {
+        NcVariableType::Vlen(VlenType::of::<T>())
+    }
+}
+
+/// Types which are stored differently in memory by `netCDF`, such as
+/// [`String`], [`Vec`] and compounds containing these.
+///
+/// Values are converted to [`Self::Ffi`] before writing, and converted back
+/// after reading, freeing the memory allocated by `netCDF`.
+/// Use [`Variable::get_owned_values`](crate::Variable::get_owned_values) and
+/// [`VariableMut::put_owned_values`](crate::VariableMut::put_owned_values)
+/// for reading and writing.
+///
+/// This trait is implemented for the basic types and is derived
+/// together with [`NcTypeDescriptor`] when using [`NcType`](crate::NcType).
+/// Structs with `String` or `Vec` fields only implement this trait, the
+/// `netCDF` compound is described by a generated companion struct.
+///
+/// # Safety
+/// * [`Self::Ffi`] must describe the type as laid out by `netCDF`
+/// * [`Self::free_written`] must release all memory allocated by [`Self::to_ffi`]
+/// * [`Self::from_read`] must release all memory allocated by `netCDF`
+pub unsafe trait NcOwnedType: Sized {
+    /// Representation compatible with `netCDF`
+    type Ffi: NcTypeDescriptor;
+    /// Check whether the value can be converted, e.g. that strings
+    /// do not contain nul bytes
+    fn validate(&self) -> Result<()>;
+    /// Convert the value, memory allocated here must be
+    /// released using [`Self::free_written`]
+    ///
+    /// The value must be checked using [`Self::validate`] before conversion
+    fn to_ffi(&self) -> Self::Ffi;
+    /// Release a value from [`Self::to_ffi`]
+    ///
+    /// # Safety
+    /// `ffi` must have been returned from [`Self::to_ffi`]
+    unsafe fn free_written(ffi: Self::Ffi);
+    /// Convert a value read by `netCDF`, releasing the memory held by `ffi`
+    ///
+    /// # Safety
+    /// `ffi` must have been initialised by `netCDF`
+    unsafe fn from_read(ffi: Self::Ffi) -> Self;
+}
+
+macro_rules! impl_owned_basic {
+    ($ty: ty) => {
+        unsafe impl NcOwnedType for $ty {
+            type Ffi = Self;
+            fn validate(&self) -> Result<()> {
+                Ok(())
+            }
+            fn to_ffi(&self) -> Self {
+                *self
+            }
+            unsafe fn free_written(_ffi: Self) {}
+            unsafe fn from_read(ffi: Self) -> Self {
+                ffi
+            }
+        }
+    };
+}
+
+impl_owned_basic!(u8);
+impl_owned_basic!(u16);
+impl_owned_basic!(u32);
+impl_owned_basic!(u64);
+impl_owned_basic!(i8);
+impl_owned_basic!(i16);
+impl_owned_basic!(i32);
+impl_owned_basic!(i64);
+impl_owned_basic!(f32);
+impl_owned_basic!(f64);
+
+#[doc(hidden)]
+unsafe impl<T: Copy, const N: usize> NcOwnedType for [T; N]
+where
+    [T; N]: NcTypeDescriptor,
+{
+    type Ffi = Self;
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+    fn to_ffi(&self) -> Self {
+        *self
+    }
+    unsafe fn free_written(_ffi: Self) {}
+    unsafe fn from_read(ffi: Self) -> Self {
+        ffi
+    }
+}
+
+unsafe impl NcOwnedType for String {
+    type Ffi = NcString;
+    fn validate(&self) -> Result<()> {
+        if self.contains('\0') {
+            return Err("String contains a nul byte".into());
+        }
+        Ok(())
+    }
+    fn to_ffi(&self) -> NcString {
+        let cstr = std::ffi::CString::new(self.as_str()).unwrap_or_default();
+        NcString(cstr.into_raw())
+    }
+    unsafe fn free_written(ffi: NcString) {
+        drop(std::ffi::CString::from_raw(ffi.0));
+    }
+    unsafe fn from_read(mut ffi: NcString) -> Self {
+        if ffi.0.is_null() {
+            return Self::new();
+        }
+        let s = std::ffi::CStr::from_ptr(ffi.0)
+            .to_string_lossy()
+            .into_owned();
+        with_lock(|| netcdf_sys::nc_free_string(1, &mut ffi.0));
+        s
+    }
+}
+
+unsafe impl<T: NcOwnedType> NcOwnedType for Vec<T> {
+    type Ffi = NcVlen<T::Ffi>;
+    fn validate(&self) -> Result<()> {
+        self.iter().try_for_each(T::validate)
+    }
+    fn to_ffi(&self) -> Self::Ffi {
+        let items = self.iter().map(T::to_ffi).collect::<Box<[_]>>();
+        let len = items.len();
+        let p = Box::into_raw(items).cast();
+        NcVlen(netcdf_sys::nc_vlen_t { len, p }, std::marker::PhantomData)
+    }
+    unsafe fn free_written(ffi: Self::Ffi) {
+        let items = std::ptr::slice_from_raw_parts_mut(ffi.0.p.cast::<T::Ffi>(), ffi.0.len);
+        for item in Box::from_raw(items).into_vec() {
+            T::free_written(item);
+        }
+    }
+    unsafe fn from_read(mut ffi: Self::Ffi) -> Self {
+        if ffi.0.p.is_null() {
+            return Self::new();
+        }
+        let p = ffi.0.p.cast::<T::Ffi>();
+        let values = (0..ffi.0.len)
+            .map(|i| T::from_read(p.add(i).read()))
+            .collect();
+        // Only frees the outer array, inner allocations are released above
+        with_lock(|| netcdf_sys::nc_free_vlen(&mut ffi.0));
+        values
+    }
+}
@@ -0,0 +1,778 @@
+//! Parsing and conversion of physical units
+//!
+//! Supports the subset of the [UDUNITS](https://docs.unidata.ucar.edu/udunits/current/)
+//! grammar commonly found in `units` attributes: SI and common non-SI units
+//! with prefixes, products (`kg m-2 s-1`, `N.m`, `W*m^-2`), quotients
+//! (`m/s`), numeric factors (`1e-3 kg`), offsets (`K @ 273.15`, `degC`) and
+//! time references (`days since 2000-01-01 12:00:00`).
+//!
+//! Time references are interpreted in the proleptic Gregorian calendar,
+//! other calendars must be handled by the caller.
+//!
+//! ```
+//! use netcdf::units::Unit;
+//! # fn main() -> Result<(), netcdf::Error> {
+//! let kelvin: Unit = "K".parse()?;
+//! let celsius: Unit = "degC".parse()?;
+//! let converter = kelvin.converter(&celsius)?;
+//! assert!((converter.convert(273.15) - 0.0).abs() < 1e-12);
+//!
+//! let flux: Unit = "kg m-2 s-1".parse()?;
+//! assert!(!flux.is_compatible(&kelvin));
+//! # Ok(()) }
+//! ```
+
+use crate::error;
+
+/// Number of base dimensions: length, mass, time, current,
+/// temperature, amount of substance, luminous intensity
+const NUM_DIMS: usize = 7;
+
+/// A physical unit, represented by its relation to the SI base units
+///
+/// A value `x` in this unit is `x * scale + offset` in the SI base units
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Unit {
+    scale: f64,
+    offset: f64,
+    dims: [i32; NUM_DIMS],
+}
+
+/// Converts values between two compatible units, see [`Unit::converter`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Converter {
+    scale: f64,
+    offset: f64,
+}
+
+impl Converter {
+    /// Convert a single value
+    pub fn convert(&self, x: f64) -> f64 {
+        x * self.scale + self.offset
+    }
+    /// Convert values in place
+    pub fn convert_slice(&self, values: &mut [f64]) {
+        for x in values {
+            *x = self.convert(*x);
+        }
+    }
+    /// The conversion does not change any values
+    pub fn is_identity(&self) -> bool {
+        self.scale == 1.0 && self.offset == 0.0
+    }
+}
+
+const M: [i32; NUM_DIMS] = [1, 0, 0, 0, 0, 0, 0];
+const KG: [i32; NUM_DIMS] = [0, 1, 0, 0, 0, 0, 0];
+const S: [i32; NUM_DIMS] = [0, 0, 1, 0, 0, 0, 0];
+const A: [i32; NUM_DIMS] = [0, 0, 0, 1, 0, 0, 0];
+const K: [i32; NUM_DIMS] = [0, 0, 0, 0, 1, 0, 0];
+const MOL: [i32; NUM_DIMS] = [0, 0, 0, 0, 0, 1, 0];
+const CD: [i32; NUM_DIMS] = [0, 0, 0, 0, 0, 0, 1];
+const ONE: [i32; NUM_DIMS] = [0; NUM_DIMS];
+
+impl Unit {
+    const fn new(scale: f64, dims: [i32; NUM_DIMS]) -> Self {
+        Self {
+            scale,
+            offset: 0.0,
+            dims,
+        }
+    }
+
+    /// The dimensionless unit `1`
+    pub fn dimensionless() -> Self {
+        Self::new(1.0, ONE)
+    }
+
+    /// Parse a UDUNITS unit string
+    pub fn parse(s: &str) -> error::Result<Self> {
+        parse(s).map_err(|e| format!("could not parse unit \"{s}\": {e}").into())
+    }
+
+    /// The unit has no physical dimension, e.g. `1`, `%` or `ppm`
+    pub fn is_dimensionless(&self) -> bool {
+        self.dims == ONE
+    }
+
+    /// Values in the two units can be converted into each other
+    pub fn is_compatible(&self, other: &Self) -> bool {
+        self.dims == other.dims
+    }
+
+    /// Get a converter from this unit into `target`
+    ///
+    /// # Errors
+    ///
+    /// The units have different dimensions
+    pub fn converter(&self, target: &Self) -> error::Result<Converter> {
+        if !self.is_compatible(target) {
+            return Err(format!(
+                "units are not compatible, {} and {}",
+                self.dimension_string(),
+                target.dimension_string()
+            )
+            .into());
+        }
+        Ok(Converter {
+            scale: self.scale / target.scale,
+            offset: (self.offset - target.offset) / target.scale,
+        })
+    }
+
+    /// Dimensions expressed in SI base units, e.g. `kg m-2 s-1`
+    fn dimension_string(&self) -> String {
+        const NAMES: [&str; NUM_DIMS] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+        let parts = NAMES
+            .iter()
+            .zip(self.dims)
+            .filter(|(_, p)| *p != 0)
+            .map(|(name, p)| {
+                if p == 1 {
+                    (*name).to_owned()
+                } else {
+                    format!("{name}{p}")
+                }
+            })
+            .collect::<Vec<_>>();
+        if parts.is_empty() {
+            "1".to_owned()
+        } else {
+            parts.join(" ")
+        }
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let mut dims = self.dims;
+        for (d, o) in dims.iter_mut().zip(other.dims) {
+            *d += o;
+        }
+        // Offsets have no meaning in products, `degC/s` is a rate
+        Self::new(self.scale * other.scale, dims)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        if n == 1 {
+            return self;
+        }
+        Self::new(self.scale.powi(n), self.dims.map(|d| d * n))
+    }
+}
+
+impl std::str::FromStr for Unit {
+    type Err = error::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Units which can be combined with a prefix
+const UNITS: &[(&[&str], Unit)] = &[
+    (&["m", "meter", "metre"], Unit::new(1.0, M)),
+    (&["g", "gram", "gramme"], Unit::new(1e-3, KG)),
+    (&["s", "second", "sec"], Unit::new(1.0, S)),
+    (&["A", "ampere", "amp"], Unit::new(1.0, A)),
+    (&["K", "kelvin"], Unit::new(1.0, K)),
+    (&["mol", "mole"], Unit::new(1.0, MOL)),
+    (&["cd", "candela"], Unit::new(1.0, CD)),
+    (&["rad", "radian"], Unit::new(1.0, ONE)),
+    (&["sr", "steradian"], Unit::new(1.0, ONE)),
+    (&["Hz", "hertz"], Unit::new(1.0, [0, 0, -1, 0, 0, 0, 0])),
+    (&["N", "newton"], Unit::new(1.0, [1, 1, -2, 0, 0, 0, 0])),
+    (&["Pa", "pascal"], Unit::new(1.0, [-1, 1, -2, 0, 0, 0, 0])),
+    (&["J", "joule"], Unit::new(1.0, [2, 1, -2, 0, 0, 0, 0])),
+    (&["W", "watt"], Unit::new(1.0, [2, 1, -3, 0, 0, 0, 0])),
+    (&["C", "coulomb"], Unit::new(1.0, [0, 0, 1, 1, 0, 0, 0])),
+    (&["V", "volt"], Unit::new(1.0, [2, 1, -3, -1, 0, 0, 0])),
+    (&["ohm"], Unit::new(1.0, [2, 1, -3, -2, 0, 0, 0])),
+    (&["S", "siemens"], Unit::new(1.0, [-2, -1, 3, 2, 0, 0, 0])),
+    (&["F", "farad"], Unit::new(1.0, [-2, -1, 4, 2, 0, 0, 0])),
+    (&["Wb", "weber"], Unit::new(1.0, [2, 1, -2, -1, 0, 0, 0])),
+    (&["T", "tesla"], Unit::new(1.0, [0, 1, -2, -1, 0, 0, 0])),
+    (&["H", "henry"], Unit::new(1.0, [2, 1, -2, -2, 0, 0, 0])),
+    (&["lm", "lumen"], Unit::new(1.0, CD)),
+    (&["lx", "lux"], Unit::new(1.0, [-2, 0, 0, 0, 0, 0, 1])),
+    (&["Bq", "becquerel"], Unit::new(1.0, [0, 0, -1, 0, 0, 0, 0])),
+    (&["Gy", "gray"], Unit::new(1.0, [2, 0, -2, 0, 0, 0, 0])),
+    (
+        &["L", "l", "liter", "litre"],
+        Unit::new(1e-3, [3, 0, 0, 0, 0, 0, 0]),
+    ),
+    (&["t", "tonne"], Unit::new(1e3, KG)),
+    (&["bar"], Unit::new(1e5, [-1, 1, -2, 0, 0, 0, 0])),
+    (
+        &["eV", "electronvolt"],
+        Unit::new(1.602_176_634e-19, [2, 1, -2, 0, 0, 0, 0]),
+    ),
+];
+
+/// Units which can not be combined with a prefix
+const PLAIN_UNITS: &[(&[&str], Unit)] = &[
+    (&["kg", "kilogram"], Unit::new(1.0, KG)),
+    (&["min", "minute"], Unit::new(60.0, S)),
+    (&["h", "hr", "hour"], Unit::new(3600.0, S)),
+    (&["d", "day"], Unit::new(86_400.0, S)),
+    (&["week"], Unit::new(604_800.0, S)),
+    (&["a", "yr", "year"], Unit::new(3.155_692_597_47e7, S)),
+    (&["common_year"], Unit::new(31_536_000.0, S)),
+    (&["month"], Unit::new(3.155_692_597_47e7 / 12.0, S)),
+    (
+        &[
+            "degree",
+            "deg",
+            "arc_degree",
+            "angular_degree",
+            "degree_north",
+            "degree_N",
+            "degreeN",
+            "degree_east",
+            "degree_E",
+            "degreeE",
+            "degree_south",
+            "degree_S",
+            "degreeS",
+            "degree_west",
+            "degree_W",
+            "degreeW",
+            "degree_true",
+            "degree_T",
+        ],
+        Unit::new(std::f64::consts::PI / 180.0, ONE),
+    ),
+    (
+        &["arcminute", "arcmin"],
+        Unit::new(std::f64::consts::PI / 10_800.0, ONE),
+    ),
+    (
+        &["arcsecond", "arcsec"],
+        Unit::new(std::f64::consts::PI / 648_000.0, ONE),
+    ),
+    (&["percent", "%"], Unit::new(1e-2, ONE)),
+    (&["ppt", "permil"], Unit::new(1e-3, ONE)),
+    (&["ppm", "ppmv"], Unit::new(1e-6, ONE)),
+    (&["ppb", "ppbv"], Unit::new(1e-9, ONE)),
+    (&["in", "inch"], Unit::new(0.0254, M)),
+    (&["ft", "foot", "feet"], Unit::new(0.3048, M)),
+    (&["yd", "yard"], Unit::new(0.9144, M)),
+    (&["mi", "mile"], Unit::new(1609.344, M)),
+    (&["nmile", "nautical_mile"], Unit::new(1852.0, M)),
+    (
+        &["kt", "knot"],
+        Unit::new(1852.0 / 3600.0, [1, 0, -1, 0, 0, 0, 0]),
+    ),
+    (&["lb", "pound"], Unit::new(0.453_592_37, KG)),
+    (
+        &["atm", "atmosphere"],
+        Unit::new(101_325.0, [-1, 1, -2, 0, 0, 0, 0]),
+    ),
+    (
+        &["Torr", "mmHg"],
+        Unit::new(133.322_368_421, [-1, 1, -2, 0, 0, 0, 0]),
+    ),
+    (
+        &["cal", "calorie"],
+        Unit::new(4.1868, [2, 1, -2, 0, 0, 0, 0]),
+    ),
+    (&["Sv", "sverdrup"], Unit::new(1e6, [3, 0, -1, 0, 0, 0, 0])),
+    (
+        &[
+            "degC",
+            "deg_C",
+            "degreeC",
+            "degree_C",
+            "degree_Celsius",
+            "celsius",
+            "°C",
+        ],
+        Unit {
+            scale: 1.0,
+            offset: 273.15,
+            dims: K,
+        },
+    ),
+    (
+        &[
+            "degF",
+            "deg_F",
+            "degreeF",
+            "degree_F",
+            "degree_Fahrenheit",
+            "fahrenheit",
+            "°F",
+        ],
+        Unit {
+            scale: 5.0 / 9.0,
+            offset: 459.67 * 5.0 / 9.0,
+            dims: K,
+        },
+    ),
+    (
+        &["degR", "deg_R", "degreeR", "degree_R", "rankine"],
+        Unit::new(5.0 / 9.0, K),
+    ),
+    (&["degK", "deg_K", "degreeK", "degree_K"], Unit::new(1.0, K)),
+];
+
+const PREFIXES: &[(&[&str], f64)] = &[
+    (&["Y", "yotta"], 1e24),
+    (&["Z", "zetta"], 1e21),
+    (&["E", "exa"], 1e18),
+    (&["P", "peta"], 1e15),
+    (&["T", "tera"], 1e12),
+    (&["G", "giga"], 1e9),
+    (&["M", "mega"], 1e6),
+    (&["k", "kilo"], 1e3),
+    (&["h", "hecto"], 1e2),
+    (&["da", "deka", "deca"], 1e1),
+    (&["d", "deci"], 1e-1),
+    (&["c", "centi"], 1e-2),
+    (&["m", "milli"], 1e-3),
+    (&["u", "µ", "micro"], 1e-6),
+    (&["n", "nano"], 1e-9),
+    (&["p", "pico"], 1e-12),
+    (&["f", "femto"], 1e-15),
+    (&["a", "atto"], 1e-18),
+    (&["z", "zepto"], 1e-21),
+    (&["y", "yocto"], 1e-24),
+];
+
+fn lookup(table: &[(&[&str], Unit)], name: &str) -> Option<Unit> {
+    table
+        .iter()
+        .find(|(names, _)| names.contains(&name))
+        .map(|(_, unit)| *unit)
+}
+
+/// Find a unit by symbol or name, with optional prefix and plural `s`
+fn find_unit(name: &str) -> Option<Unit> {
+    // `degrees_north` is the plural of `degree_north`
+    let singular = match name.strip_prefix("degrees") {
+        Some(rest) => Some(format!("degree{rest}")),
+        None => name
+            .strip_suffix('s')
+            .filter(|s| s.len() > 2)
+            .map(str::to_owned),
+    };
+    for name in std::iter::once(name).chain(singular.as_deref()) {
+        if let Some(unit) = lookup(PLAIN_UNITS, name).or_else(|| lookup(UNITS, name)) {
+            return Some(unit);
+        }
+        for (prefixes, factor) in PREFIXES {
+            for prefix in *prefixes {
+                let Some(rest) = name.strip_prefix(prefix) else {
+                    continue;
+                };
+                // Symbols take symbol prefixes, names take name prefixes
+                let is_symbol_prefix = prefix.chars().count() <= 2;
+                let found = UNITS.iter().find(|(names, _)| {
+                    names
+                        .iter()
+                        .position(|n| *n == rest)
+                        .is_some_and(|i| (i == 0) == is_symbol_prefix)
+                });
+                if let Some((_, unit)) = found {
+                    return Some(Unit::new(unit.scale * factor, unit.dims));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String, i32),
+    Power(i32),
+    Mul,
+    Div,
+    Open,
+    Close,
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '%' || c == '°' || c == '\''
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars = s.chars().collect::<Vec<_>>();
+    let integer = |i: &mut usize| -> Option<i32> {
+        let start = *i;
+        if matches!(chars.get(*i), Some('+' | '-')) {
+            *i += 1;
+        }
+        let digits = *i;
+        while chars.get(*i).is_some_and(char::is_ascii_digit) {
+            *i += 1;
+        }
+        if *i == digits {
+            *i = start;
+            return None;
+        }
+        chars[start..*i].iter().collect::<String>().parse().ok()
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                tokens.push(Token::Power(integer(&mut i).ok_or("expected exponent")?));
+            }
+            '^' => {
+                i += 1;
+                tokens.push(Token::Power(integer(&mut i).ok_or("expected exponent")?));
+            }
+            '*' | '·' => {
+                i += 1;
+                tokens.push(Token::Mul);
+            }
+            '.' if !chars.get(i + 1).is_some_and(char::is_ascii_digit) => {
+                i += 1;
+                tokens.push(Token::Mul);
+            }
+            '/' => {
+                i += 1;
+                tokens.push(Token::Div);
+            }
+            '(' => {
+                i += 1;
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                i += 1;
+                tokens.push(Token::Close);
+            }
+            c if c.is_ascii_digit() || c == '.' || c == '-' || c == '+' => {
+                let start = i;
+                i += 1;
+                while chars
+                    .get(i)
+                    .is_some_and(|c| c.is_ascii_digit() || *c == '.')
+                {
+                    i += 1;
+                }
+                if matches!(chars.get(i), Some('e' | 'E'))
+                    && chars
+                        .get(i + 1)
+                        .is_some_and(|c| c.is_ascii_digit() || *c == '-' || *c == '+')
+                {
+                    i += 1;
+                    integer(&mut i).ok_or("invalid exponent")?;
+                }
+                let number = chars[start..i].iter().collect::<String>();
+                let number = number
+                    .parse()
+                    .map_err(|_| format!("invalid number {number}"))?;
+                tokens.push(Token::Number(number));
+            }
+            c if is_name_char(c) => {
+                let start = i;
+                while chars.get(i).copied().is_some_and(is_name_char) {
+                    i += 1;
+                }
+                let name = chars[start..i].iter().collect::<String>();
+                let exponent = integer(&mut i).unwrap_or(1);
+                tokens.push(Token::Name(name, exponent));
+            }
+            c => return Err(format!("unexpected character '{c}'")),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn product(&mut self) -> Result<Unit, String> {
+        let mut unit = self.power()?;
+        loop {
+            match self.peek() {
+                Some(Token::Mul) => {
+                    self.pos += 1;
+                    unit = unit.mul(self.power()?);
+                }
+                Some(Token::Div) => {
+                    self.pos += 1;
+                    unit = unit.mul(self.power()?.powi(-1));
+                }
+                Some(Token::Number(_) | Token::Name(..) | Token::Open) => {
+                    unit = unit.mul(self.power()?);
+                }
+                _ => return Ok(unit),
+            }
+        }
+    }
+
+    fn power(&mut self) -> Result<Unit, String> {
+        let base = match self.tokens.get(self.pos).cloned() {
+            Some(Token::Number(x)) => {
+                self.pos += 1;
+                Unit::new(x, ONE)
+            }
+            Some(Token::Name(name, exponent)) => {
+                self.pos += 1;
+                find_unit(&name)
+                    .ok_or_else(|| format!("unknown unit {name}"))?
+                    .powi(exponent)
+            }
+            Some(Token::Open) => {
+                self.pos += 1;
+                let unit = self.product()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err("unbalanced parenthesis".into());
+                }
+                self.pos += 1;
+                unit
+            }
+            Some(token) => return Err(format!("unexpected {token:?}")),
+            None => return Err("unexpected end".into()),
+        };
+        match self.peek() {
+            Some(&Token::Power(n)) => {
+                self.pos += 1;
+                Ok(base.powi(n))
+            }
+            _ => Ok(base),
+        }
+    }
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Parse a timestamp such as `1970-01-01`, `1970-1-1 0:0:0` or
+/// `1970-01-01T00:00:00Z` into seconds since 1970-01-01
+fn parse_timestamp(s: &str) -> Result<f64, String> {
+    let invalid = || format!("invalid timestamp {s}");
+    let s = s.trim();
+    let s = s
+        .strip_suffix("UTC")
+        .or_else(|| s.strip_suffix('Z'))
+        .unwrap_or(s)
+        .trim();
+    let (date, rest) = match s.find(['T', ' ']) {
+        Some(i) => (&s[..i], s[i + 1..].trim()),
+        None => (s, ""),
+    };
+
+    let (sign, date) = match date.strip_prefix('-') {
+        Some(date) => (-1, date),
+        None => (1, date),
+    };
+    let mut parts = date.split('-');
+    let mut field = || -> Result<i64, String> {
+        parts
+            .next()
+            .map_or(Ok(1), |x| x.parse().map_err(|_| invalid()))
+    };
+    let (year, month, day) = (sign * field()?, field()?, field()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+
+    // Time of day with an optional UTC offset
+    let (time, zone) = match rest.find(['+', '-', ' ']) {
+        Some(i) => (&rest[..i], rest[i..].trim()),
+        None => (rest, ""),
+    };
+    let mut seconds = 0.0;
+    for (part, factor) in time
+        .split(':')
+        .filter(|p| !p.is_empty())
+        .zip([3600.0, 60.0, 1.0])
+    {
+        seconds += part.parse::<f64>().map_err(|_| invalid())? * factor;
+    }
+    if !zone.is_empty() {
+        let (sign, zone) = match zone.strip_prefix('-') {
+            Some(zone) => (-1.0, zone),
+            None => (1.0, zone.trim_start_matches('+')),
+        };
+        let (hours, minutes) = match zone.split_once(':') {
+            Some(parts) => parts,
+            // `hhmm`, split by byte which requires ASCII digits
+            None if zone.len() > 2 => {
+                if !zone.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(invalid());
+                }
+                zone.split_at(zone.len() - 2)
+            }
+            None => (zone, "0"),
+        };
+        let offset = hours.trim().parse::<f64>().map_err(|_| invalid())? * 3600.0
+            + minutes.trim().parse::<f64>().map_err(|_| invalid())? * 60.0;
+        seconds -= sign * offset;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let days = days_from_civil(year, month, day) as f64;
+    Ok(days * 86_400.0 + seconds)
+}
+
+fn parse(s: &str) -> Result<Unit, String> {
+    // Shifted units: `unit @ offset` or `unit since timestamp`
+    let (unit, shift) = match s.split_once('@') {
+        Some((unit, offset)) => (unit, Some((offset, false))),
+        None => {
+            let words = [" since ", " after ", " from ", " ref "];
+            match words.iter().find_map(|w| s.find(w).map(|i| (i, w.len()))) {
+                Some((i, len)) => (&s[..i], Some((&s[i + len..], true))),
+                None => (s, None),
+            }
+        }
+    };
+
+    let tokens = tokenize(unit)?;
+    let mut unit = if tokens.is_empty() {
+        Unit::dimensionless()
+    } else {
+        let mut parser = Parser { tokens, pos: 0 };
+        let unit = parser.product()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected {token:?}"));
+        }
+        unit
+    };
+
+    match shift {
+        None => {}
+        Some((timestamp, true)) => {
+            if unit.dims != S {
+                return Err("only time units can have a reference time".into());
+            }
+            unit.offset = parse_timestamp(timestamp)?;
+        }
+        Some((offset, false)) => {
+            let offset = offset
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid offset {offset}"))?;
+            unit.offset += offset * unit.scale;
+        }
+    }
+    Ok(unit)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn convert(value: f64, from: &str, to: &str) -> f64 {
+        let from = Unit::parse(from).unwrap();
+        let to = Unit::parse(to).unwrap();
+        from.converter(&to).unwrap().convert(value)
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() <= 1e-9 * b.abs().max(1.0), "{a} != {b}");
+    }
+
+    #[test]
+    fn parse_units() {
+        for s in [
+            "K",
+            "degC",
+            "m s-1",
+            "m/s",
+            "m.s-1",
+            "m*s^-1",
+            "m s**-1",
+            "kg m-2 s-1",
+            "W m-2",
+            "hPa",
+            "1",
+            "1e-3",
+            "%",
+            "degrees_north",
+            "days since 2000-01-01",
+            "seconds since 1970-01-01T00:00:00Z",
+            "K @ 273.15",
+            "(m/s)^2",
+            "m*2",
+            "",
+        ] {
+            Unit::parse(s).unwrap();
+        }
+        for s in [
+            "foo",
+            "m s-",
+            "(m",
+            "m @ x",
+            "m since 2000-01-01",
+            "days since 2000-01-01 00:00 +é1",
+            "days since 2000-01-01 00:00 +1é",
+        ] {
+            assert!(Unit::parse(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn compatibility() {
+        let parse = |s| Unit::parse(s).unwrap();
+        assert!(parse("m s-1").is_compatible(&parse("km/h")));
+        assert!(parse("kg m-2 s-1").is_compatible(&parse("mm/day kg m-3")));
+        assert!(parse("N").is_compatible(&parse("kg m s-2")));
+        assert!(parse("K").is_compatible(&parse("degC")));
+        assert!(!parse("K").is_compatible(&parse("m")));
+        assert!(parse("%").is_dimensionless());
+        assert!(parse("K").converter(&parse("m")).is_err());
+    }
+
+    #[test]
+    fn conversions() {
+        assert_close(convert(273.15, "K", "degC"), 0.0);
+        assert_close(convert(100.0, "degC", "degF"), 212.0);
+        assert_close(convert(0.0, "K @ 273.15", "K"), 273.15);
+        assert_close(convert(1.0, "km", "m"), 1000.0);
+        assert_close(convert(36.0, "km/h", "m s-1"), 10.0);
+        assert_close(convert(1013.25, "hPa", "Pa"), 101_325.0);
+        assert_close(
+            convert(1.0, "kg m-2 s-1", "mm/day kg m-3"),
+            86_400.0 * 1000.0,
+        );
+        assert_close(convert(50.0, "%", "1"), 0.5);
+        assert_close(
+            convert(1.0, "days since 2000-01-01", "hours since 2000-01-01"),
+            24.0,
+        );
+        assert_close(
+            convert(
+                0.0,
+                "days since 2000-01-02",
+                "days since 2000-01-01 00:00:00",
+            ),
+            1.0,
+        );
+        assert_close(
+            convert(
+                0.0,
+                "seconds since 2000-01-01T00:00:00+01:00",
+                "hours since 2000-01-01",
+            ),
+            -1.0,
+        );
+        assert_close(
+            convert(0.0, "days since 2000-03-01", "days since 1970-01-01"),
+            11_017.0,
+        );
+    }
+}
@@ -0,0 +1,429 @@
+//! Dynamically typed values
+//!
+//! A [`Value`] can hold an element of any `netCDF` type, and is used
+//! to read and write variables of user defined types for which no Rust
+//! type exists, using
+//! [`Variable::get_dynamic_values`](crate::Variable::get_dynamic_values) and
+//! [`VariableMut::put_dynamic_values`](crate::VariableMut::put_dynamic_values).
+//! The values are decoded using the [`NcVariableType`] of the variable.
+
+use std::ffi::{c_char, CStr, CString};
+
+use netcdf_sys::nc_vlen_t;
+
+use crate::error;
+use crate::types::{CompoundType, EnumType, EnumTypeValues, FloatType, IntType, NcVariableType};
+use crate::utils::with_lock;
+
+/// A single element of any `netCDF` type
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Value {
+    /// Signed integer
+    Int(i64),
+    /// Unsigned integer
+    UInt(u64),
+    /// Floating point number
+    Float(f64),
+    /// Single character
+    Char(u8),
+    /// Variable length string
+    Str(String),
+    /// Compound with the value of each field
+    Compound(Vec<(String, Value)>),
+    /// Member of an enum
+    ///
+    /// Integers which are not members of the enum are given as
+    /// [`Int`](Self::Int) or [`UInt`](Self::UInt)
+    Enum(String),
+    /// Bag of bytes
+    Opaque(Vec<u8>),
+    /// Ragged array
+    Vlen(Vec<Value>),
+    /// Fixed size array of a compound field, in row-major order
+    Array(Vec<Value>),
+}
+
+impl Value {
+    /// Get the field of a compound
+    pub fn field(&self, name: &str) -> Option<&Self> {
+        match self {
+            Self::Compound(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// Read a `T` from the start of `bytes`
+///
+/// # Safety
+///
+/// Any bit pattern must be valid for `T`
+unsafe fn read<T>(bytes: &[u8]) -> T {
+    assert!(bytes.len() >= std::mem::size_of::<T>());
+    std::ptr::read_unaligned(bytes.as_ptr().cast())
+}
+
+/// Write `value` to the start of `bytes`
+fn write<T>(bytes: &mut [u8], value: T) {
+    assert!(bytes.len() >= std::mem::size_of::<T>());
+    unsafe { std::ptr::write_unaligned(bytes.as_mut_ptr().cast(), value) }
+}
+
+fn field_len(arraydims: Option<&Vec<usize>>) -> usize {
+    arraydims.map_or(1, |dims| dims.iter().product())
+}
+
+/// Decode elements of `typ` from memory written by `netCDF`
+///
+/// # Safety
+///
+/// `bytes` must hold valid elements of `typ` as read by `netCDF`
+pub(crate) unsafe fn decode(typ: &NcVariableType, bytes: &[u8]) -> error::Result<Vec<Value>> {
+    let size = typ.size();
+    if size == 0 {
+        return Ok(vec![]);
+    }
+    bytes
+        .chunks_exact(size)
+        .map(|element| decode_element(typ, element))
+        .collect()
+}
+
+unsafe fn decode_element(typ: &NcVariableType, bytes: &[u8]) -> error::Result<Value> {
+    Ok(match typ {
+        NcVariableType::Int(IntType::U8) => Value::UInt(read::<u8>(bytes).into()),
+        NcVariableType::Int(IntType::U16) => Value::UInt(read::<u16>(bytes).into()),
+        NcVariableType::Int(IntType::U32) => Value::UInt(read::<u32>(bytes).into()),
+        NcVariableType::Int(IntType::U64) => Value::UInt(read::<u64>(bytes)),
+        NcVariableType::Int(IntType::I8) => Value::Int(read::<i8>(bytes).into()),
+        NcVariableType::Int(IntType::I16) => Value::Int(read::<i16>(bytes).into()),
+        NcVariableType::Int(IntType::I32) => Value::Int(read::<i32>(bytes).into()),
+        NcVariableType::Int(IntType::I64) => Value::Int(read::<i64>(bytes)),
+        NcVariableType::Float(FloatType::F32) => Value::Float(read::<f32>(bytes).into()),
+        NcVariableType::Float(FloatType::F64) => Value::Float(read::<f64>(bytes)),
+        NcVariableType::Char => Value::Char(bytes[0]),
+        NcVariableType::String => {
+            let p = read::<*const c_char>(bytes);
+            if p.is_null() {
+                Value::Str(String::new())
+            } else {
+                Value::Str(CStr::from_ptr(p).to_string_lossy().into_owned())
+            }
+        }
+        NcVariableType::Opaque(opaque) => Value::Opaque(bytes[..opaque.size].to_vec()),
+        NcVariableType::Enum(enumtype) => decode_enum(enumtype, bytes),
+        NcVariableType::Compound(compound) => {
+            let mut fields = Vec::with_capacity(compound.fields.len());
+            for field in &compound.fields {
+                let size = field.basetype.size();
+                let len = field_len(field.arraydims.as_ref());
+                let bytes = &bytes[field.offset..field.offset + size * len];
+                let value = if field.arraydims.is_some() {
+                    Value::Array(decode(&field.basetype, bytes)?)
+                } else {
+                    decode_element(&field.basetype, bytes)?
+                };
+                fields.push((field.name.clone(), value));
+            }
+            Value::Compound(fields)
+        }
+        NcVariableType::Vlen(vlen) => {
+            let vlen_t = read::<nc_vlen_t>(bytes);
+            if vlen_t.len == 0 || vlen_t.p.is_null() {
+                Value::Vlen(vec![])
+            } else {
+                let data = std::slice::from_raw_parts(
+                    vlen_t.p.cast::<u8>(),
+                    vlen_t.len * vlen.basetype.size(),
+                );
+                Value::Vlen(decode(&vlen.basetype, data)?)
+            }
+        }
+    })
+}
+
+unsafe fn decode_enum(enumtype: &EnumType, bytes: &[u8]) -> Value {
+    macro_rules! member {
+        ($members: expr, $ty: ty, $variant: ident) => {{
+            let value = read::<$ty>(bytes);
+            match $members.iter().position(|&member| member == value) {
+                Some(i) => Value::Enum(enumtype.fieldnames[i].clone()),
+                None => Value::$variant(value.into()),
+            }
+        }};
+    }
+    match &enumtype.fieldvalues {
+        EnumTypeValues::U8(members) => member!(members, u8, UInt),
+        EnumTypeValues::U16(members) => member!(members, u16, UInt),
+        EnumTypeValues::U32(members) => member!(members, u32, UInt),
+        EnumTypeValues::U64(members) => member!(members, u64, UInt),
+        EnumTypeValues::I8(members) => member!(members, i8, Int),
+        EnumTypeValues::I16(members) => member!(members, i16, Int),
+        EnumTypeValues::I32(members) => member!(members, i32, Int),
+        EnumTypeValues::I64(members) => member!(members, i64, Int),
+    }
+}
+
+/// Whether reading elements of `typ` makes `netCDF` allocate memory
+pub(crate) fn allocates(typ: &NcVariableType) -> bool {
+    match typ {
+        NcVariableType::String | NcVariableType::Vlen(_) => true,
+        NcVariableType::Compound(compound) => compound
+            .fields
+            .iter()
+            .any(|field| allocates(&field.basetype)),
+        _ => false,
+    }
+}
+
+/// Free the memory allocated by `netCDF` for elements of `typ`
+///
+/// # Safety
+///
+/// `bytes` must hold valid elements of `typ` as read by `netCDF`,
+/// and must not be used afterwards
+pub(crate) unsafe fn free(typ: &NcVariableType, bytes: &mut [u8]) {
+    let size = typ.size();
+    if size == 0 {
+        return;
+    }
+    for element in bytes.chunks_exact_mut(size) {
+        free_element(typ, element);
+    }
+}
+
+unsafe fn free_element(typ: &NcVariableType, bytes: &mut [u8]) {
+    match typ {
+        NcVariableType::String => {
+            let mut p = read::<*mut c_char>(bytes);
+            if !p.is_null() {
+                with_lock(|| netcdf_sys::nc_free_string(1, &mut p));
+                write(bytes, std::ptr::null_mut::<c_char>());
+            }
+        }
+        NcVariableType::Compound(compound) => {
+            for field in &compound.fields {
+                let size = field.basetype.size();
+                let len = field_len(field.arraydims.as_ref());
+                free(
+                    &field.basetype,
+                    &mut bytes[field.offset..field.offset + size * len],
+                );
+            }
+        }
+        NcVariableType::Vlen(vlen) => {
+            let mut vlen_t = read::<nc_vlen_t>(bytes);
+            if vlen_t.p.is_null() {
+                return;
+            }
+            let data = std::slice::from_raw_parts_mut(
+                vlen_t.p.cast::<u8>(),
+                vlen_t.len * vlen.basetype.size(),
+            );
+            free(&vlen.basetype, data);
+            // Only frees the outer array, inner allocations are released above
+            with_lock(|| netcdf_sys::nc_free_vlen(&mut vlen_t));
+            write(
+                bytes,
+                nc_vlen_t {
+                    len: 0,
+                    p: std::ptr::null_mut(),
+                },
+            );
+        }
+        _ => {}
+    }
+}
+
+/// Values encoded in the memory layout expected by `netCDF`
+///
+/// Strings and ragged arrays point into allocations owned by this struct
+pub(crate) struct Encoded {
+    pub(crate) bytes: Vec<u8>,
+    strings: Vec<CString>,
+    vlens: Vec<Vec<u8>>,
+}
+
+impl Encoded {
+    /// Encode `values` as elements of `typ`
+    pub(crate) fn new(typ: &NcVariableType, values: &[Value]) -> error::Result<Self> {
+        let mut encoded = Self {
+            bytes: vec![],
+            strings: vec![],
+            vlens: vec![],
+        };
+        encoded.bytes = encoded.encode(typ, values)?;
+        Ok(encoded)
+    }
+
+    fn encode(&mut self, typ: &NcVariableType, values: &[Value]) -> error::Result<Vec<u8>> {
+        let size = typ.size();
+        let mut bytes = vec![0_u8; size * values.len()];
+        if size == 0 {
+            return Ok(bytes);
+        }
+        for (value, element) in values.iter().zip(bytes.chunks_exact_mut(size)) {
+            self.encode_element(typ, value, element)?;
+        }
+        Ok(bytes)
+    }
+
+    fn encode_element(
+        &mut self,
+        typ: &NcVariableType,
+        value: &Value,
+        bytes: &mut [u8],
+    ) -> error::Result<()> {
+        match (typ, value) {
+            (NcVariableType::Int(inttype), _) => encode_int(*inttype, value, bytes)?,
+            (NcVariableType::Float(FloatType::F32), _) => {
+                #[allow(clippy::cast_possible_truncation)]
+                write(bytes, float(value)? as f32);
+            }
+            (NcVariableType::Float(FloatType::F64), _) => write(bytes, float(value)?),
+            (NcVariableType::Char, Value::Char(c)) => bytes[0] = *c,
+            (NcVariableType::String, Value::Str(s)) => {
+                let s = CString::new(s.as_str())?;
+                write(bytes, s.as_ptr());
+                self.strings.push(s);
+            }
+            (NcVariableType::Opaque(opaque), Value::Opaque(data)) => {
+                if data.len() != opaque.size {
+                    return Err(error::Error::BufferLen {
+                        wanted: opaque.size,
+                        actual: data.len(),
+                    });
+                }
+                bytes[..opaque.size].copy_from_slice(data);
+            }
+            (NcVariableType::Enum(enumtype), _) => encode_enum(enumtype, value, bytes)?,
+            (NcVariableType::Compound(compound), Value::Compound(fields)) => {
+                self.encode_compound(compound, fields, bytes)?;
+            }
+            (NcVariableType::Vlen(vlen), Value::Vlen(items)) => {
+                let data = self.encode(&vlen.basetype, items)?;
+                let vlen_t = nc_vlen_t {
+                    len: items.len(),
+                    p: data.as_ptr().cast_mut().cast(),
+                };
+                write(bytes, vlen_t);
+                self.vlens.push(data);
+            }
+            (typ, value) => {
+                return Err(format!("can not encode {value:?} as {typ:?}").into());
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_compound(
+        &mut self,
+        compound: &CompoundType,
+        fields: &[(String, Value)],
+        bytes: &mut [u8],
+    ) -> error::Result<()> {
+        for field in &compound.fields {
+            let value = fields
+                .iter()
+                .find(|(name, _)| *name == field.name)
+                .map(|(_, value)| value)
+                .ok_or_else(|| {
+                    error::Error::NotFound(format!(
+                        "field {} of the compound {}",
+                        field.name, compound.name
+                    ))
+                })?;
+            let size = field.basetype.size();
+            let len = field_len(field.arraydims.as_ref());
+            let bytes = &mut bytes[field.offset..field.offset + size * len];
+            match (&field.arraydims, value) {
+                (None, value) => self.encode_element(&field.basetype, value, bytes)?,
+                (Some(_), Value::Array(items)) if items.len() == len => {
+                    bytes.copy_from_slice(&self.encode(&field.basetype, items)?);
+                }
+                (Some(_), Value::Array(items)) => {
+                    return Err(error::Error::BufferLen {
+                        wanted: len,
+                        actual: items.len(),
+                    })
+                }
+                (Some(_), value) => {
+                    return Err(
+                        format!("expected an array for {}, got {value:?}", field.name).into(),
+                    )
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn float(value: &Value) -> error::Result<f64> {
+    #[allow(clippy::cast_precision_loss)]
+    match value {
+        Value::Float(x) => Ok(*x),
+        Value::Int(x) => Ok(*x as f64),
+        Value::UInt(x) => Ok(*x as f64),
+        value => Err(format!("can not encode {value:?} as a float").into()),
+    }
+}
+
+fn encode_int(inttype: IntType, value: &Value, bytes: &mut [u8]) -> error::Result<()> {
+    let x: i128 = match value {
+        Value::Int(x) => (*x).into(),
+        Value::UInt(x) => (*x).into(),
+        value => return Err(format!("can not encode {value:?} as an integer").into()),
+    };
+    macro_rules! int {
+        ($ty: ty) => {
+            write(bytes, <$ty>::try_from(x).map_err(error::Error::Conversion)?)
+        };
+    }
+    match inttype {
+        IntType::U8 => int!(u8),
+        IntType::U16 => int!(u16),
+        IntType::U32 => int!(u32),
+        IntType::U64 => int!(u64),
+        IntType::I8 => int!(i8),
+        IntType::I16 => int!(i16),
+        IntType::I32 => int!(i32),
+        IntType::I64 => int!(i64),
+    }
+    Ok(())
+}
+
+fn encode_enum(enumtype: &EnumType, value: &Value, bytes: &mut [u8]) -> error::Result<()> {
+    let index = match value {
+        Value::Enum(name) => Some(
+            enumtype
+                .fieldnames
+                .iter()
+                .position(|member| member == name)
+                .ok_or_else(|| {
+                    error::Error::NotFound(format!("member {name} of the enum {}", enumtype.name))
+                })?,
+        ),
+        Value::Int(_) | Value::UInt(_) => None,
+        value => return Err(format!("can not encode {value:?} as an enum").into()),
+    };
+    macro_rules! member {
+        ($members: expr, $inttype: expr) => {
+            match index {
+                Some(i) => write(bytes, $members[i]),
+                None => encode_int($inttype, value, bytes)?,
+            }
+        };
+    }
+    match &enumtype.fieldvalues {
+        EnumTypeValues::U8(members) => member!(members, IntType::U8),
+        EnumTypeValues::U16(members) => member!(members, IntType::U16),
+        EnumTypeValues::U32(members) => member!(members, IntType::U32),
+        EnumTypeValues::U64(members) => member!(members, IntType::U64),
+        EnumTypeValues::I8(members) => member!(members, IntType::I8),
+        EnumTypeValues::I16(members) => member!(members, IntType::I16),
+        EnumTypeValues::I32(members) => member!(members, IntType::I32),
+        EnumTypeValues::I64(members) => member!(members, IntType::I64),
+    }
+    Ok(())
+}
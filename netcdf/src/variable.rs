@@ -10,9 +10,10 @@ use netcdf_sys::*;
 use super::attribute::{Attribute, AttributeValue};
 use super::dimension::Dimension;
 use super::error;
-use super::extent::Extents;
-use crate::types::{NcTypeDescriptor, NcVariableType};
+use super::extent::{Extents, StartCountStride};
+use crate::types::{EnumType, EnumTypeValues, NcOwnedType, NcTypeDescriptor, NcVariableType};
 use crate::utils::{checked_with_lock, with_lock};
+use crate::value::{Encoded, Value};
 
 #[allow(clippy::doc_markdown)]
 /// This struct defines a `netCDF` variable.
@@ -70,6 +71,16 @@ pub enum Endianness {
     Big,
 }
 
+/// A filter applied to the data of a variable, such as compression
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Filter {
+    /// Identifier of the `HDF5` filter, e.g. 1 for deflate and 2 for shuffle
+    pub id: u32,
+    /// Parameters of the filter
+    pub params: Vec<u32>,
+}
+
 #[allow(clippy::len_without_is_empty)]
 impl<'g> Variable<'g> {
     pub(crate) fn find_from_name(ncid: nc_type, name: &str) -> error::Result<Option<Variable<'g>>> {
@@ -110,29 +121,43 @@ impl<'g> Variable<'g> {
 
     /// Get the name of variable
     pub fn name(&self) -> String {
+        self.try_name()
+            .expect("Variable name contained invalid sequence")
+    }
+    /// Get the name of variable
+    pub fn try_name(&self) -> error::Result<String> {
         let mut name = vec![0_u8; NC_MAX_NAME as usize + 1];
         checked_with_lock(|| unsafe {
             nc_inq_varname(self.ncid, self.varid, name.as_mut_ptr().cast())
-        })
-        .unwrap();
+        })?;
         let zeropos = name.iter().position(|&x| x == 0).unwrap_or(name.len());
         name.resize(zeropos, 0);
 
-        String::from_utf8(name).expect("Variable name contained invalid sequence")
+        Ok(String::from_utf8(name)?)
     }
     /// Get an attribute of this variable
     pub fn attribute<'a>(&'a self, name: &str) -> Option<Attribute<'a>> {
+        self.try_attribute(name)
+            .expect("Could not retrieve attribute")
+    }
+    /// Get an attribute of this variable, `None` if not found
+    pub fn try_attribute<'a>(&'a self, name: &str) -> error::Result<Option<Attribute<'a>>> {
         // Need to lock when reading the first attribute (per variable)
         Attribute::find_from_name(self.ncid, Some(self.varid), name)
-            .expect("Could not retrieve attribute")
     }
     /// Iterator over all the attributes of this variable
     pub fn attributes(&self) -> impl Iterator<Item = Attribute<'_>> {
-        // Need to lock when reading the first attribute (per variable)
-        crate::attribute::AttributeIterator::new(self.ncid, Some(self.varid))
+        self.try_attributes()
             .expect("Could not get attributes")
             .map(Result::unwrap)
     }
+    /// Iterator over all the attributes of this variable
+    pub fn try_attributes(
+        &self,
+    ) -> error::Result<impl Iterator<Item = error::Result<Attribute<'_>>>> {
+        // Need to lock when reading the first attribute (per variable)
+        crate::attribute::AttributeIterator::new(self.ncid, Some(self.varid))
+    }
     /// Get the attribute value
     ///
     /// # Example
@@ -148,13 +173,24 @@ impl<'g> Variable<'g> {
     pub fn attribute_value(&self, name: &str) -> Option<error::Result<AttributeValue>> {
         self.attribute(name).as_ref().map(Attribute::value)
     }
+    /// Get the attribute values as `T`, e.g. of a user defined type
+    pub fn attribute_typed<T: NcTypeDescriptor + Copy>(
+        &self,
+        name: &str,
+    ) -> Option<error::Result<Vec<T>>> {
+        self.attribute(name).as_ref().map(Attribute::value_typed)
+    }
     /// Dimensions for a variable
     pub fn dimensions(&self) -> &[Dimension<'_>] {
         &self.dimensions
     }
     /// Get the type of this variable
     pub fn vartype(&self) -> NcVariableType {
-        crate::types::read_type(self.ncid, self.vartype).expect("Unknown type encountered")
+        self.try_vartype().expect("Unknown type encountered")
+    }
+    /// Get the type of this variable
+    pub fn try_vartype(&self) -> error::Result<NcVariableType> {
+        crate::types::read_type(self.ncid, self.vartype)
     }
     /// Get current length of the variable
     pub fn len(&self) -> usize {
@@ -228,6 +264,48 @@ impl<'g> Variable<'g> {
             Ok(None)
         }
     }
+
+    /// Get the filters of the variable, in the order they are applied
+    ///
+    /// # Errors
+    ///
+    /// Not a `netCDF-4` file.
+    #[cfg(feature = "4.8.0")]
+    pub fn filters(&self) -> error::Result<Vec<Filter>> {
+        let mut nfilters = 0;
+        checked_with_lock(|| unsafe {
+            nc_inq_var_filter_ids(self.ncid, self.varid, &mut nfilters, std::ptr::null_mut())
+        })?;
+        let mut ids = vec![0; nfilters];
+        checked_with_lock(|| unsafe {
+            nc_inq_var_filter_ids(self.ncid, self.varid, &mut nfilters, ids.as_mut_ptr())
+        })?;
+        ids.into_iter()
+            .map(|id| {
+                let mut nparams = 0;
+                checked_with_lock(|| unsafe {
+                    nc_inq_var_filter_info(
+                        self.ncid,
+                        self.varid,
+                        id,
+                        &mut nparams,
+                        std::ptr::null_mut(),
+                    )
+                })?;
+                let mut params = vec![0; nparams];
+                checked_with_lock(|| unsafe {
+                    nc_inq_var_filter_info(
+                        self.ncid,
+                        self.varid,
+                        id,
+                        &mut nparams,
+                        params.as_mut_ptr(),
+                    )
+                })?;
+                Ok(Filter { id, params })
+            })
+            .collect()
+    }
 }
 impl VariableMut<'_> {
     /// Sets compression on the variable. Must be set before filling in data.
@@ -287,6 +365,66 @@ impl VariableMut<'_> {
 
         Ok(())
     }
+
+    /// Add a filter to the variable. Must be set before inserting data
+    ///
+    /// Filters are applied in the order they are added
+    ///
+    /// # Errors
+    ///
+    /// Not a `netCDF-4` file or the filter is not available
+    #[cfg(feature = "4.8.0")]
+    pub fn set_filter(&mut self, filter: &Filter) -> error::Result<()> {
+        checked_with_lock(|| unsafe {
+            nc_def_var_filter(
+                self.ncid,
+                self.varid,
+                filter.id,
+                filter.params.len(),
+                filter.params.as_ptr(),
+            )
+        })
+    }
+}
+
+/// Deflate level of a variable, if deflated, and whether bytes are shuffled
+pub(crate) fn deflate(var: &Variable) -> error::Result<(Option<nc_type>, bool)> {
+    let mut shuffle = 0;
+    let mut deflate = 0;
+    let mut deflate_level = 0;
+    checked_with_lock(|| unsafe {
+        nc_inq_var_deflate(
+            var.ncid,
+            var.varid,
+            &mut shuffle,
+            &mut deflate,
+            &mut deflate_level,
+        )
+    })?;
+    Ok(((deflate != 0).then_some(deflate_level), shuffle != 0))
+}
+
+/// Filters of a variable, none if they can not be listed by this version of `netCDF`
+#[cfg(feature = "4.8.0")]
+pub(crate) fn filters(var: &Variable) -> error::Result<Vec<Filter>> {
+    var.filters()
+}
+
+#[cfg(not(feature = "4.8.0"))]
+pub(crate) fn filters(_var: &Variable) -> error::Result<Vec<Filter>> {
+    Ok(vec![])
+}
+
+#[cfg(feature = "4.8.0")]
+pub(crate) fn set_filter(var: &mut VariableMut, filter: &Filter) -> error::Result<()> {
+    var.set_filter(filter)
+}
+
+#[cfg(not(feature = "4.8.0"))]
+pub(crate) fn set_filter(_var: &mut VariableMut, _filter: &Filter) -> error::Result<()> {
+    Err(error::Error::Unsupported(
+        "filters require netCDF 4.8.0 or later".to_owned(),
+    ))
 }
 
 impl VariableMut<'_> {
@@ -297,10 +435,24 @@ impl VariableMut<'_> {
     {
         Attribute::put(self.ncid, self.varid, name, val.into())
     }
+    /// Adds an attribute of any type, e.g. a user defined type.
+    /// The type is added to the group if missing
+    pub fn put_attribute_typed<T: NcTypeDescriptor>(
+        &mut self,
+        name: &str,
+        values: &[T],
+    ) -> error::Result<Attribute<'_>> {
+        Attribute::put_typed(self.ncid, self.varid, name, values)
+    }
 }
 
 impl Variable<'_> {
     fn get_values_mono<T: NcTypeDescriptor>(&self, extents: &Extents) -> error::Result<Vec<T>> {
+        self.get_values_as(&T::type_descriptor(), extents)
+    }
+
+    /// Get values stored in memory as `T` with the layout given by `typ`
+    fn get_values_as<T>(&self, typ: &NcVariableType, extents: &Extents) -> error::Result<Vec<T>> {
         let dims = self.dimensions();
         let (start, count, stride) = extents.get_start_count_stride(dims)?;
 
@@ -308,14 +460,7 @@ impl Variable<'_> {
         let mut values = Vec::with_capacity(number_of_elements);
 
         unsafe {
-            super::putget::get_vars(
-                self,
-                &T::type_descriptor(),
-                &start,
-                &count,
-                &stride,
-                values.as_mut_ptr(),
-            )?;
+            super::putget::get_vars(self, typ, &start, &count, &stride, values.as_mut_ptr())?;
             values.set_len(number_of_elements);
         };
         Ok(values)
@@ -389,6 +534,111 @@ impl Variable<'_> {
         Ok(s)
     }
 
+    /// Type of the variable, checked to be an enum
+    fn enum_type(&self) -> error::Result<EnumType> {
+        match self.vartype() {
+            NcVariableType::Enum(typ) => Ok(typ),
+            _ => Err(error::Error::TypeMismatch),
+        }
+    }
+
+    /// Get the member names of the values of an enum variable
+    ///
+    /// Works for any enum type, without a corresponding Rust type
+    ///
+    /// # Errors
+    ///
+    /// The variable is not an enum, or contains values which are
+    /// not members of the enum (e.g. unwritten values)
+    pub fn get_enum_names<E>(&self, extents: E) -> error::Result<Vec<String>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        let typ = self.enum_type()?;
+        let descriptor = NcVariableType::Enum(typ.clone());
+
+        macro_rules! names {
+            ($members: expr) => {{
+                let values = self.get_values_as(&descriptor, &extents)?;
+                values
+                    .iter()
+                    .map(|value| {
+                        $members
+                            .iter()
+                            .position(|member| member == value)
+                            .map(|i| typ.fieldnames[i].clone())
+                            .ok_or_else(|| {
+                                format!("{value} is not a member of the enum {}", typ.name).into()
+                            })
+                    })
+                    .collect()
+            }};
+        }
+        match &typ.fieldvalues {
+            EnumTypeValues::U8(members) => names!(members),
+            EnumTypeValues::U16(members) => names!(members),
+            EnumTypeValues::U32(members) => names!(members),
+            EnumTypeValues::U64(members) => names!(members),
+            EnumTypeValues::I8(members) => names!(members),
+            EnumTypeValues::I16(members) => names!(members),
+            EnumTypeValues::I32(members) => names!(members),
+            EnumTypeValues::I64(members) => names!(members),
+        }
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Get the member names of the values of an enum variable as an ndarray
+    ///
+    /// See [`get_enum_names`](Self::get_enum_names)
+    pub fn get_enum<E>(&self, extents: E) -> error::Result<ArrayD<String>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        let shape = extents
+            .iter_with_dims(self.dimensions())?
+            .filter(|item| !item.is_an_index)
+            .map(|item| item.count)
+            .collect::<Vec<_>>();
+        let names = self.get_enum_names(extents)?;
+        ArrayD::from_shape_vec(shape, names).map_err(|e| e.to_string().into())
+    }
+
+    /// Get values of types which need conversion from the `netCDF`
+    /// representation, such as [`String`], [`Vec`] and structs containing these
+    ///
+    /// The memory allocated by `netCDF` is freed before returning
+    pub fn get_owned_values<T: NcOwnedType, E>(&self, extents: E) -> error::Result<Vec<T>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        let (start, count, stride) = extents.get_start_count_stride(self.dimensions())?;
+
+        let number_of_elements = count.iter().copied().fold(1_usize, usize::saturating_mul);
+        let mut buffer = Vec::<T::Ffi>::with_capacity(number_of_elements);
+        super::putget::get_vars(
+            self,
+            &T::Ffi::type_descriptor(),
+            &start,
+            &count,
+            &stride,
+            buffer.as_mut_ptr(),
+        )?;
+        unsafe {
+            buffer.set_len(number_of_elements);
+        };
+
+        Ok(buffer
+            .into_iter()
+            .map(|x| unsafe { T::from_read(x) })
+            .collect())
+    }
+
     #[cfg(feature = "ndarray")]
     /// Fetches variable
     fn values_arr_mono<T: NcTypeDescriptor>(&self, extents: &Extents) -> error::Result<ArrayD<T>> {
@@ -499,6 +749,98 @@ impl Variable<'_> {
         )
     }
 
+    /// Parse the `units` attribute of this variable,
+    /// `None` if the variable has no such attribute
+    pub fn units(&self) -> error::Result<Option<crate::units::Unit>> {
+        match self.attribute_value("units").transpose()? {
+            Some(units) => Ok(Some(String::try_from(units)?.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Converter from the units of this variable into `target`, and the
+    /// values which mark missing data
+    fn unit_conversion(
+        &self,
+        target: &crate::units::Unit,
+    ) -> error::Result<(crate::units::Converter, Vec<f64>)> {
+        let units = self
+            .units()?
+            .ok_or_else(|| error::Error::NotFound(format!("units of variable {}", self.name())))?;
+        let converter = units.converter(target)?;
+        let mut missing = vec![];
+        for name in ["_FillValue", "missing_value"] {
+            if let Some(value) = self.attribute_value(name).transpose()? {
+                missing.extend(crate::cf::attribute_numbers(value).unwrap_or_default());
+            }
+        }
+        Ok((converter, missing))
+    }
+
+    /// Get values converted from the `units` of this variable into `target`
+    ///
+    /// Values equal to `_FillValue` or `missing_value` are returned unchanged.
+    /// Packed values (`scale_factor` and `add_offset`) are not unpacked
+    /// before the conversion.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # fn main() -> Result<(), netcdf::Error> {
+    /// # let file = netcdf::open("data.nc")?;
+    /// let temperature = file.variable("temperature").unwrap();
+    /// let celsius = temperature.get_values_in_unit(.., &"degC".parse()?)?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The variable has no `units`, or they are not compatible with `target`
+    pub fn get_values_in_unit<E>(
+        &self,
+        extents: E,
+        target: &crate::units::Unit,
+    ) -> error::Result<Vec<f64>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let (converter, missing) = self.unit_conversion(target)?;
+        let mut values = self.get_values::<f64, _>(extents)?;
+        if !converter.is_identity() {
+            for x in &mut values {
+                if !missing.contains(x) {
+                    *x = converter.convert(*x);
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Get values converted into `target`, see [`Variable::get_values_in_unit`]
+    pub fn get_in_unit<E>(
+        &self,
+        extents: E,
+        target: &crate::units::Unit,
+    ) -> error::Result<ArrayD<f64>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let (converter, missing) = self.unit_conversion(target)?;
+        let mut values = self.get::<f64, _>(extents)?;
+        if !converter.is_identity() {
+            values.mapv_inplace(|x| {
+                if missing.contains(&x) {
+                    x
+                } else {
+                    converter.convert(x)
+                }
+            });
+        }
+        Ok(values)
+    }
+
     /// Get the fill value of a variable
     pub fn fill_value<T: NcTypeDescriptor + Copy>(&self) -> error::Result<Option<T>> {
         if T::type_descriptor() != super::types::read_type(self.ncid, self.vartype)? {
@@ -595,6 +937,102 @@ impl Variable<'_> {
 
         Ok(())
     }
+
+    #[cfg(feature = "num-complex")]
+    /// Get complex numbers from a variable
+    ///
+    /// Supports the layouts described in [`crate::complex`]. When the parts
+    /// are stored in the last dimension, `extents` only cover the other dimensions
+    pub fn get_complex_values<T: crate::complex::ComplexFloat, E>(
+        &self,
+        extents: E,
+    ) -> error::Result<Vec<num_complex::Complex<T>>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        match crate::complex::layout(self)? {
+            crate::complex::Layout::Compound { real, imag } => {
+                let bytes = self.get_raw_values(extents)?;
+                Ok(crate::complex::decode_compounds(
+                    real,
+                    imag,
+                    self.vartype().size(),
+                    &bytes,
+                ))
+            }
+            crate::complex::Layout::Dimension => {
+                let dims = self.dimensions();
+                let (mut start, mut count, mut stride) =
+                    extents.get_start_count_stride(&dims[..dims.len() - 1])?;
+                start.push(0);
+                count.push(2);
+                stride.push(1);
+
+                let number_of_elements = count.iter().copied().fold(1_usize, usize::saturating_mul);
+                let mut parts = Vec::<T>::with_capacity(number_of_elements);
+                super::putget::get_vars(
+                    self,
+                    &T::type_descriptor(),
+                    &start,
+                    &count,
+                    &stride,
+                    parts.as_mut_ptr(),
+                )?;
+                unsafe { parts.set_len(number_of_elements) };
+                Ok(parts
+                    .chunks_exact(2)
+                    .map(|ri| num_complex::Complex::new(ri[0], ri[1]))
+                    .collect())
+            }
+        }
+    }
+
+    #[cfg(all(feature = "num-complex", feature = "ndarray"))]
+    /// Get complex numbers from a variable as an ndarray
+    ///
+    /// See [`get_complex_values`](Self::get_complex_values)
+    pub fn get_complex<T: crate::complex::ComplexFloat, E>(
+        &self,
+        extents: E,
+    ) -> error::Result<ArrayD<num_complex::Complex<T>>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        let mut dims = self.dimensions();
+        if let crate::complex::Layout::Dimension = crate::complex::layout(self)? {
+            dims = &dims[..dims.len() - 1];
+        }
+        let shape = extents
+            .iter_with_dims(dims)?
+            .filter(|item| !item.is_an_index)
+            .map(|item| item.count)
+            .collect::<Vec<_>>();
+        let values = self.get_complex_values(extents)?;
+        ArrayD::from_shape_vec(shape, values).map_err(|e| e.to_string().into())
+    }
+
+    /// Get values of any type as [`Value`]s, decoded using the type of the variable
+    ///
+    /// This supports user defined types for which no Rust type exists.
+    /// The memory allocated by `netCDF` is freed before returning
+    pub fn get_dynamic_values<E>(&self, extents: E) -> error::Result<Vec<Value>>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let typ = self.vartype();
+        let mut buffer = self.get_raw_values(extents)?;
+        // Safety: the buffer was filled by netCDF using the type of the variable
+        unsafe {
+            let values = crate::value::decode(&typ, &buffer);
+            crate::value::free(&typ, &mut buffer);
+            values
+        }
+    }
 }
 
 impl VariableMut<'_> {
@@ -603,31 +1041,43 @@ impl VariableMut<'_> {
         values: &[T],
         extents: &Extents,
     ) -> error::Result<()> {
+        self.put_values_as(&T::type_descriptor(), values, extents)
+    }
+
+    /// Put values stored in memory as `T` with the layout given by `typ`
+    fn put_values_as<T>(
+        &mut self,
+        typ: &NcVariableType,
+        values: &[T],
+        extents: &Extents,
+    ) -> error::Result<()> {
+        let (start, count, stride) = self.start_count_stride_for(values.len(), extents)?;
+        crate::putget::put_vars(self, typ, &start, &count, &stride, values.as_ptr())?;
+        Ok(())
+    }
+
+    /// Indices for putting `len` values, a one-dimensional
+    /// variable is extended to fit all values
+    fn start_count_stride_for(
+        &self,
+        len: usize,
+        extents: &Extents,
+    ) -> error::Result<StartCountStride> {
         let dims = self.dimensions();
         let (start, mut count, stride) = extents.get_start_count_stride(dims)?;
 
-        let number_of_elements_to_put = values.len();
         let number_of_elements = count.iter().copied().fold(1_usize, usize::saturating_mul);
-        if number_of_elements != number_of_elements_to_put {
+        if number_of_elements != len {
             if dims.len() == 1 {
-                count[0] = values.len();
+                count[0] = len;
             } else {
                 return Err(error::Error::BufferLen {
                     wanted: number_of_elements,
-                    actual: number_of_elements_to_put,
+                    actual: len,
                 });
             }
         }
-
-        crate::putget::put_vars(
-            self,
-            &T::type_descriptor(),
-            &start,
-            &count,
-            &stride,
-            values.as_ptr(),
-        )?;
-        Ok(())
+        Ok((start, count, stride))
     }
     /// Put a slice of values at `indices`
     pub fn put_values<T: NcTypeDescriptor, E>(
@@ -661,6 +1111,86 @@ impl VariableMut<'_> {
         self.put_value(item, extents)
     }
 
+    /// Put values into an enum variable given by the member names
+    ///
+    /// # Errors
+    ///
+    /// The variable is not an enum, or a name is not a member of the enum
+    pub fn put_enum_names<S: AsRef<str>, E>(&mut self, names: &[S], extents: E) -> error::Result<()>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        let typ = self.enum_type()?;
+        let indices = names
+            .iter()
+            .map(|name| {
+                let name = name.as_ref();
+                typ.fieldnames
+                    .iter()
+                    .position(|member| member == name)
+                    .ok_or_else(|| {
+                        error::Error::NotFound(format!("member {name} of the enum {}", typ.name))
+                    })
+            })
+            .collect::<error::Result<Vec<_>>>()?;
+
+        let descriptor = NcVariableType::Enum(typ.clone());
+        macro_rules! put {
+            ($members: expr) => {{
+                let values = indices.iter().map(|&i| $members[i]).collect::<Vec<_>>();
+                self.put_values_as(&descriptor, &values, &extents)
+            }};
+        }
+        match &typ.fieldvalues {
+            EnumTypeValues::U8(members) => put!(members),
+            EnumTypeValues::U16(members) => put!(members),
+            EnumTypeValues::U32(members) => put!(members),
+            EnumTypeValues::U64(members) => put!(members),
+            EnumTypeValues::I8(members) => put!(members),
+            EnumTypeValues::I16(members) => put!(members),
+            EnumTypeValues::I32(members) => put!(members),
+            EnumTypeValues::I64(members) => put!(members),
+        }
+    }
+
+    /// Put [`Value`]s of any type, encoded using the type of the variable
+    ///
+    /// This supports user defined types for which no Rust type exists
+    pub fn put_dynamic_values<E>(&mut self, values: &[Value], extents: E) -> error::Result<()>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        let encoded = Encoded::new(&self.vartype(), values)?;
+        let (start, count, stride) = self.start_count_stride_for(values.len(), &extents)?;
+        crate::putget::put_raw_values(self, &start, &count, &stride, &encoded.bytes)
+    }
+
+    /// Put values of types which need conversion to the `netCDF`
+    /// representation, such as [`String`], [`Vec`] and structs containing these,
+    /// see e.g. [`FileMut::add_owned_variable`](crate::FileMut::add_owned_variable)
+    pub fn put_owned_values<T: NcOwnedType, E>(
+        &mut self,
+        values: &[T],
+        extents: E,
+    ) -> error::Result<()>
+    where
+        E: TryInto<Extents>,
+        E::Error: Into<error::Error>,
+    {
+        let extents: Extents = extents.try_into().map_err(Into::into)?;
+        values.iter().try_for_each(T::validate)?;
+        let buffer = values.iter().map(T::to_ffi).collect::<Vec<_>>();
+        let result = self.put_values_mono(&buffer, &extents);
+        for x in buffer {
+            unsafe { T::free_written(x) };
+        }
+        result
+    }
+
     /// Set a Fill Value
     ///
     /// # Errors
@@ -0,0 +1,229 @@
+use netcdf::aggregation::{Aggregation, Join};
+
+/// Files of `steps` time steps each, where `temperature` is the index
+/// of the time step in the aggregation times 10 plus the index of `x`
+fn create(dir: &std::path::Path, steps: &[usize]) -> Vec<std::path::PathBuf> {
+    let mut offset = 0;
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, &n)| {
+            let path = dir.join(format!("forecast_{i}.nc"));
+            let mut file = netcdf::create(&path).unwrap();
+            file.add_attribute("run", i as i32).unwrap();
+            file.add_unlimited_dimension("time").unwrap();
+            file.add_dimension("x", 3).unwrap();
+            let mut x = file.add_variable::<f32>("x", &["x"]).unwrap();
+            x.put_values(&[0.0_f32, 0.5, 1.0], ..).unwrap();
+            let mut time = file.add_variable::<f64>("time", &["time"]).unwrap();
+            let times = (offset..offset + n).map(|t| t as f64).collect::<Vec<_>>();
+            time.put_values(&times, ..).unwrap();
+            let mut temperature = file
+                .add_variable::<i32>("temperature", &["time", "x"])
+                .unwrap();
+            temperature.put_attribute("units", "K").unwrap();
+            let values = (offset..offset + n)
+                .flat_map(|t| (0..3).map(move |x| (t * 10 + x) as i32))
+                .collect::<Vec<_>>();
+            temperature.put_values(&values, (..n, ..)).unwrap();
+            offset += n;
+            path
+        })
+        .collect()
+}
+
+fn open(paths: &[std::path::PathBuf]) -> Vec<netcdf::File> {
+    paths
+        .iter()
+        .map(|path| netcdf::open(path).unwrap())
+        .collect()
+}
+
+#[test]
+fn join_existing() {
+    let d = tempfile::tempdir().unwrap();
+    let paths = create(d.path(), &[2, 3, 2]);
+    let aggregation = Aggregation::join_existing(open(&paths), "time").unwrap();
+    assert_eq!(aggregation.join(), Join::Existing);
+    assert_eq!(aggregation.len(), 7);
+
+    let time = aggregation.variable("time").unwrap();
+    assert!(time.is_joined());
+    assert_eq!(
+        time.get_values::<f64, _>(..).unwrap(),
+        [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+    );
+
+    let temperature = aggregation.variable("temperature").unwrap();
+    assert_eq!(temperature.dimensions(), ["time", "x"]);
+    assert_eq!(temperature.shape(), [7, 3]);
+    let units: String = temperature
+        .attribute_value("units")
+        .unwrap()
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(units, "K");
+    // Across the first and second file
+    assert_eq!(
+        temperature.get_values::<i32, _>((1..4, 2)).unwrap(),
+        [12, 22, 32]
+    );
+    // Every other time step, in all files
+    assert_eq!(
+        temperature
+            .get_values::<i32, _>(([0, 1], [4, 2], [2, 1]))
+            .unwrap(),
+        [1, 2, 21, 22, 41, 42, 61, 62]
+    );
+    assert_eq!(temperature.get_values::<i32, _>((6, 0)).unwrap(), [60]);
+    assert!(temperature.get_values::<i32, _>((7, 0)).is_err());
+    // The last index would overflow
+    let extents = netcdf::Extents::from(vec![
+        netcdf::Extent::SliceCount {
+            start: 1,
+            count: 4,
+            stride: isize::MAX,
+        },
+        netcdf::Extent::Index(0),
+    ]);
+    assert!(matches!(
+        temperature.get_values::<i32, _>(extents),
+        Err(netcdf::Error::IndexMismatch)
+    ));
+
+    let x = aggregation.variable("x").unwrap();
+    assert!(!x.is_joined());
+    assert_eq!(x.get_values::<f32, _>(..).unwrap(), [0.0, 0.5, 1.0]);
+    assert_eq!(aggregation.variables().count(), 3);
+}
+
+#[test]
+fn join_new() {
+    let d = tempfile::tempdir().unwrap();
+    let paths = create(d.path(), &[2, 2]);
+    let aggregation = Aggregation::join_new(open(&paths), "run", &["temperature"])
+        .unwrap()
+        .with_coordinates(vec!["00".to_owned(), "12".to_owned()])
+        .unwrap();
+    assert_eq!(aggregation.len(), 2);
+    assert_eq!(
+        aggregation.coordinates(),
+        Some(&["00".to_owned(), "12".to_owned()][..])
+    );
+
+    let temperature = aggregation.variable("temperature").unwrap();
+    assert_eq!(temperature.dimensions(), ["run", "time", "x"]);
+    assert_eq!(temperature.shape(), [2, 2, 3]);
+    assert_eq!(
+        temperature.get_values::<i32, _>((.., 1, 0)).unwrap(),
+        [10, 30]
+    );
+    assert_eq!(
+        temperature.get_dynamic_values((1, 0, 1..)).unwrap(),
+        [netcdf::value::Value::Int(21), netcdf::value::Value::Int(22)]
+    );
+    // Not joined, from the first file
+    assert_eq!(
+        aggregation
+            .variable("time")
+            .unwrap()
+            .get_values::<f64, _>(..)
+            .unwrap(),
+        [0.0, 1.0]
+    );
+
+    // The coordinates of the new dimension
+    let run = aggregation.variable("run").unwrap();
+    assert!(run.first().is_none());
+    assert_eq!(run.dimensions(), ["run"]);
+    assert_eq!(
+        run.vartype(),
+        netcdf::types::NcVariableType::Float(netcdf::types::FloatType::F64)
+    );
+    assert_eq!(run.get_values::<f64, _>(..).unwrap(), [0.0, 12.0]);
+    assert_eq!(run.get_values::<f64, _>(1).unwrap(), [12.0]);
+    assert!(run.get_values::<i32, _>(..).is_err());
+    assert_eq!(aggregation.variables().next().unwrap().name(), "run");
+
+    let aggregation = Aggregation::join_new(open(&paths), "run", &["temperature"])
+        .unwrap()
+        .with_coordinates(vec!["a.nc".to_owned(), "b.nc".to_owned()])
+        .unwrap();
+    let run = aggregation.variable("run").unwrap();
+    assert_eq!(run.vartype(), netcdf::types::NcVariableType::String);
+    assert_eq!(
+        run.get_dynamic_values(..).unwrap(),
+        [
+            netcdf::value::Value::Str("a.nc".to_owned()),
+            netcdf::value::Value::Str("b.nc".to_owned())
+        ]
+    );
+
+    assert!(Aggregation::join_new(open(&paths), "time", &["temperature"]).is_err());
+}
+
+#[test]
+fn mismatched_files() {
+    let d = tempfile::tempdir().unwrap();
+    let paths = create(d.path(), &[1, 1]);
+    let mut file = netcdf::append(&paths[1]).unwrap();
+    file.add_dimension("y", 2).unwrap();
+    file.add_variable::<i32>("other", &["time", "y"]).unwrap();
+    drop(file);
+    let path = d.path().join("other.nc");
+    let mut file = netcdf::create(&path).unwrap();
+    file.add_dimension("time", 1).unwrap();
+    file.add_variable::<i32>("temperature", &["time"]).unwrap();
+    drop(file);
+
+    let mut files = open(&paths);
+    files.push(netcdf::open(&path).unwrap());
+    let aggregation = Aggregation::join_existing(files, "time").unwrap();
+    assert!(aggregation.try_variable("temperature").is_err());
+    assert!(aggregation.variable("temperature").is_none());
+    assert!(aggregation.try_variable("other").unwrap().is_none());
+    // The joined variables are missing from or differ in the last file
+    let names = aggregation
+        .variables()
+        .map(|var| var.name().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["x"]);
+
+    let mut file = netcdf::create(&path).unwrap();
+    file.add_dimension("x", 1).unwrap();
+    drop(file);
+    let mut files = open(&paths);
+    files.push(netcdf::open(&path).unwrap());
+    assert!(Aggregation::join_existing(files, "time").is_err());
+}
+
+#[test]
+#[cfg(feature = "ncml")]
+fn ncml() {
+    let d = tempfile::tempdir().unwrap();
+    let archive = d.path().join("archive");
+    std::fs::create_dir(&archive).unwrap();
+    create(&archive, &[2, 1, 2]);
+    std::fs::write(archive.join("readme.txt"), "not netCDF").unwrap();
+    let path = d.path().join("forecasts.ncml");
+    std::fs::write(
+        &path,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<netcdf xmlns="http://www.unidata.ucar.edu/namespaces/netcdf/ncml-2.2">
+  <aggregation dimName="time" type="joinExisting">
+    <scan location="archive" suffix=".nc"/>
+  </aggregation>
+</netcdf>"#,
+    )
+    .unwrap();
+
+    let aggregation = Aggregation::open_ncml(&path).unwrap();
+    assert_eq!(aggregation.files().len(), 3);
+    let temperature = aggregation.variable("temperature").unwrap();
+    assert_eq!(temperature.shape(), [5, 3]);
+    assert_eq!(
+        temperature.get_values::<i32, _>((1..4, 0)).unwrap(),
+        [10, 20, 30]
+    );
+}
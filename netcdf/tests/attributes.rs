@@ -250,3 +250,44 @@ fn multi_attributes() {
     }
     assert_eq!(atts, 10);
 }
+
+#[test]
+fn text_attribute_types() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("text_attribute_types.nc");
+
+    let values = [
+        ("char", AttributeValue::Str("text".into())),
+        ("string", AttributeValue::Strs(vec!["text".into()])),
+        (
+            "strings",
+            AttributeValue::Strs(vec!["a".into(), "b".into()]),
+        ),
+        ("embedded", AttributeValue::Chars(b"a\0b".to_vec())),
+        ("latin1", AttributeValue::Chars(vec![b'f', 0xf8, b'n'])),
+    ];
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        for (name, value) in &values {
+            file.add_attribute(name, value.clone()).unwrap();
+        }
+        file.add_attribute("terminated", AttributeValue::Chars(b"text\0\0".to_vec()))
+            .unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    for (name, value) in values {
+        let attr = file.attribute(name).unwrap();
+        assert_eq!(attr.value().unwrap(), value);
+    }
+    assert_eq!(
+        file.attribute("terminated").unwrap().value().unwrap(),
+        AttributeValue::Str("text".into())
+    );
+    for name in ["char", "string", "terminated"] {
+        let value = file.attribute(name).unwrap().value().unwrap();
+        assert_eq!(String::try_from(value).unwrap(), "text");
+    }
+    let value = file.attribute("strings").unwrap().value().unwrap();
+    assert!(String::try_from(value).is_err());
+}
@@ -0,0 +1,185 @@
+use netcdf::cdl::{CdlOptions, Data};
+
+fn create(path: &std::path::Path) {
+    let mut file = netcdf::create(path).unwrap();
+    file.add_attribute("title", "CDL test").unwrap();
+    file.add_dimension("x", 3).unwrap();
+    file.add_unlimited_dimension("time").unwrap();
+    file.add_type_from_descriptor(netcdf::types::NcVariableType::Opaque(
+        netcdf::types::OpaqueType {
+            name: "blob".to_owned(),
+            size: 2,
+        },
+    ))
+    .unwrap();
+
+    let mut x = file.add_variable::<f32>("x", &["x"]).unwrap();
+    x.put_values(&[0.5_f32, 1.0, 1.5], ..).unwrap();
+    x.put_attribute("units", "m").unwrap();
+    x.put_attribute("valid_range", vec![0.0_f32, 2.0]).unwrap();
+    x.put_attribute("flag", 1_i16).unwrap();
+    let mut t = file.add_variable::<i32>("t", &["time", "x"]).unwrap();
+    t.put_values(&[1, 2, 3], (0, ..)).unwrap();
+    let mut name = file.add_variable::<u8>("name", &["x"]).unwrap();
+    name.put_attribute("kind", vec!["a".to_owned(), "b".to_owned()])
+        .unwrap();
+
+    let mut group = file.add_group("forecast").unwrap();
+    group.add_dimension("y", 1).unwrap();
+    let mut y = group.add_variable::<f64>("y", &["y"]).unwrap();
+    y.put_values(&[2.0_f64], ..).unwrap();
+}
+
+#[test]
+fn header() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("cdl_header.nc");
+    create(&path);
+    let file = netcdf::open(&path).unwrap();
+
+    let cdl = netcdf::cdl::to_string(&file, "cdl_header", &CdlOptions::default()).unwrap();
+    let lines = cdl.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "netcdf cdl_header {");
+    assert_eq!(lines.last(), Some(&"}"));
+    for line in [
+        "types:",
+        "  opaque(2) blob ;",
+        "dimensions:",
+        "\tx = 3 ;",
+        "\ttime = UNLIMITED ; // (1 currently)",
+        "variables:",
+        "\tfloat x(x) ;",
+        "\t\tx:units = \"m\" ;",
+        "\t\tx:valid_range = 0.f, 2.f ;",
+        "\t\tx:flag = 1s ;",
+        "\tint t(time, x) ;",
+        "\tubyte name(x) ;",
+        "\t\tstring name:kind = \"a\", \"b\" ;",
+        "// global attributes:",
+        "\t\t:title = \"CDL test\" ;",
+        "group: forecast {",
+        "  dimensions:",
+        "  \ty = 1 ;",
+        "  \tdouble y(y) ;",
+        "  } // group forecast",
+    ] {
+        assert!(lines.contains(&line), "{line:?} not found in\n{cdl}");
+    }
+    assert!(!cdl.contains("data:"));
+}
+
+#[test]
+fn data() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("cdl_data.nc");
+    create(&path);
+    let file = netcdf::open(&path).unwrap();
+
+    let options = CdlOptions {
+        data: Data::All,
+        ..CdlOptions::default()
+    };
+    let cdl = netcdf::cdl::to_string(&file, "cdl_data", &options).unwrap();
+    for line in [" x = 0.5, 1, 1.5 ;", " t = 1, 2, 3 ;", "   y = 2 ;"] {
+        assert!(
+            cdl.lines().any(|l| l == line),
+            "{line:?} not found in\n{cdl}"
+        );
+    }
+
+    let options = CdlOptions {
+        data: Data::Coordinates,
+        ..CdlOptions::default()
+    };
+    let cdl = netcdf::cdl::to_string(&file, "cdl_data", &options).unwrap();
+    assert!(cdl.contains(" x = 0.5, 1, 1.5 ;"));
+    assert!(cdl.contains("   y = 2 ;"));
+    assert!(!cdl.contains(" t = "));
+
+    let options = CdlOptions {
+        data: Data::Variables(vec!["t".to_owned()]),
+        float_precision: 2,
+        ..CdlOptions::default()
+    };
+    let cdl = netcdf::cdl::to_string(&file, "cdl_data", &options).unwrap();
+    assert!(cdl.contains(" t = 1, 2, 3 ;"));
+    assert!(!cdl.contains(" x = "));
+}
+
+#[test]
+fn parse_and_write() {
+    let schema = netcdf::cdl::Schema::parse(
+        r#"netcdf generated {
+types:
+  compound point {
+    float x ;
+    short id(2) ;
+  } ;
+  int(*) ragged ;
+dimensions:
+  x = 3 ;
+  time = UNLIMITED ;
+variables:
+  double v(time, x) ;
+    v:units = "m" ;
+    v:scale = 0.5f, 2.f ;
+  point p ;
+  ragged r(x) ;
+  char label(x) ;
+
+// global attributes:
+  :title = "generated" ;
+  string :tags = "a", "b" ;
+data:
+  v = 1, 2, 3, 4, 5 ;
+  p = {1.5, {1, 2}} ;
+  r = {1}, {}, {2, 3} ;
+  label = "ab" ;
+
+group: sub {
+  dimensions:
+    y = 1 ;
+  variables:
+    ubyte u(y) ;
+  data:
+    u = 7 ;
+  }
+}"#,
+    )
+    .unwrap();
+    assert_eq!(schema.name, "generated");
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("cdl_generated.nc");
+    let mut file = netcdf::create(&path).unwrap();
+    schema.write(&mut file).unwrap();
+    drop(file);
+
+    let file = netcdf::open(&path).unwrap();
+    assert_eq!(file.dimension_len("time"), Some(2));
+    assert_eq!(
+        file.attribute("title").unwrap().value().unwrap(),
+        "generated".into()
+    );
+    let v = file.variable("v").unwrap();
+    assert_eq!(
+        v.get_values::<f64, _>(..).unwrap(),
+        [1.0, 2.0, 3.0, 4.0, 5.0, netcdf_sys::NC_FILL_DOUBLE]
+    );
+    assert_eq!(
+        v.attribute("scale").unwrap().value().unwrap(),
+        vec![0.5_f32, 2.0].into()
+    );
+    let label = file.variable("label").unwrap();
+    assert_eq!(label.get_values::<u8, _>(..).unwrap(), b"ab\0");
+
+    let root = file.root();
+    let sub = root.group("sub").unwrap();
+    let u = sub.variable("u").unwrap();
+    assert_eq!(u.get_values::<u8, _>(..).unwrap(), [7]);
+
+    // The file prints back to CDL which parses to the same types
+    let cdl = netcdf::cdl::to_string(&file, "generated", &CdlOptions::default()).unwrap();
+    let reparsed = netcdf::cdl::Schema::parse(&cdl).unwrap();
+    assert_eq!(reparsed.root.types, schema.root.types);
+}
@@ -0,0 +1,353 @@
+use netcdf::cf::{CfVersion, DiagnosticKind, Severity};
+
+#[test]
+fn compliant_file() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("cf_compliant.nc");
+    let mut file = netcdf::create(path).unwrap();
+
+    file.add_attribute("Conventions", "CF-1.8").unwrap();
+    file.add_dimension("lat", 3).unwrap();
+    file.add_dimension("nv", 2).unwrap();
+
+    let mut lat = file.add_variable::<f64>("lat", &["lat"]).unwrap();
+    lat.put_attribute("units", "degrees_north").unwrap();
+    lat.put_attribute("standard_name", "latitude").unwrap();
+    lat.put_attribute("bounds", "lat_bnds").unwrap();
+    lat.put_values(&[10.0, 20.0, 30.0], ..).unwrap();
+
+    let mut bnds = file
+        .add_variable::<f64>("lat_bnds", &["lat", "nv"])
+        .unwrap();
+    bnds.put_values(&[5.0, 15.0, 15.0, 25.0, 25.0, 35.0], ..)
+        .unwrap();
+
+    let mut temp = file.add_variable::<f32>("temp", &["lat"]).unwrap();
+    temp.put_attribute("units", "K").unwrap();
+    temp.put_attribute("standard_name", "air_temperature standard_error")
+        .unwrap();
+    temp.put_attribute("_FillValue", -999.0_f32).unwrap();
+
+    let report = netcdf::cf::check(&file, CfVersion::V1_8).unwrap();
+    assert!(report.is_compliant(), "{report}");
+    assert!(report.diagnostics().is_empty(), "{report}");
+}
+
+#[test]
+fn violations() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("cf_violations.nc");
+    let mut file = netcdf::create(path).unwrap();
+
+    file.add_dimension("x", 3).unwrap();
+    file.add_dimension("y", 2).unwrap();
+    file.add_dimension("nv", 3).unwrap();
+
+    let mut x = file.add_variable::<i32>("x", &["x"]).unwrap();
+    x.put_attribute("bounds", "x_bnds").unwrap();
+    x.put_values(&[1, 3, 2], ..).unwrap();
+    file.add_variable::<i32>("x_bnds", &["x", "nv"]).unwrap();
+    file.add_variable::<f32>("label", &["y"]).unwrap();
+
+    let mut data = file.add_variable::<f32>("data", &["x"]).unwrap();
+    data.put_attribute("units", "m").unwrap();
+    data.put_attribute("standard_name", "Not A Name").unwrap();
+    data.put_attribute("missing_value", -1_i32).unwrap();
+    data.put_attribute("coordinates", "label missing").unwrap();
+
+    let report = netcdf::cf::Checker::new(CfVersion::V1_8)
+        .with_standard_names(["air_temperature"])
+        .check(&file)
+        .unwrap();
+    assert!(!report.is_compliant());
+
+    let kinds = report
+        .diagnostics()
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| (d.location.as_str(), d.kind))
+        .collect::<Vec<_>>();
+    assert!(kinds.contains(&("/", DiagnosticKind::Conventions)));
+    assert!(kinds.contains(&("/x", DiagnosticKind::MissingUnits)));
+    assert!(kinds.contains(&("/x", DiagnosticKind::BadBounds)));
+    assert!(kinds.contains(&("/x", DiagnosticKind::NonMonotonic)));
+    assert!(kinds.contains(&("/data", DiagnosticKind::InvalidStandardName)));
+    assert!(kinds.contains(&("/data", DiagnosticKind::AttributeType)));
+    assert_eq!(
+        kinds
+            .iter()
+            .filter(|k| **k == ("/data", DiagnosticKind::BadCoordinates))
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn coordinates_in_parent_group() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("cf_groups.nc");
+    let mut file = netcdf::create(path).unwrap();
+
+    file.add_attribute("Conventions", "CF-1.8").unwrap();
+    file.add_dimension("time", 2).unwrap();
+    let mut time = file.add_variable::<f64>("time", &["time"]).unwrap();
+    time.put_attribute("units", "days since 2000-01-01")
+        .unwrap();
+    time.put_values(&[0.0, 1.0], ..).unwrap();
+    file.add_variable::<f64>("station_lat", &[])
+        .unwrap()
+        .put_attribute("units", "degrees_north")
+        .unwrap();
+
+    let mut group = file.add_group("station").unwrap();
+    let mut var = group.add_variable::<f32>("obs", &["time"]).unwrap();
+    var.put_attribute("units", "K").unwrap();
+    var.put_attribute("coordinates", "station_lat /time")
+        .unwrap();
+
+    let report = netcdf::cf::check(&file, CfVersion::V1_8).unwrap();
+    assert!(report.diagnostics().is_empty(), "{report}");
+
+    // Searching parent groups was introduced with CF-1.8
+    let report = netcdf::cf::check(&file, CfVersion::V1_7).unwrap();
+    let kinds = report
+        .diagnostics()
+        .iter()
+        .filter(|d| d.location == "/station/obs")
+        .map(|d| d.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [DiagnosticKind::BadCoordinates; 2]);
+}
+
+#[test]
+fn grid_mapping_roundtrip() {
+    use netcdf::cf::{Ellipsoid, GridMapping, TrueScale};
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("cf_grid_mapping.nc");
+    let mut file = netcdf::create(path).unwrap();
+
+    let mapping = GridMapping::LambertConformalConic {
+        standard_parallel: (25.0, Some(60.0)),
+        longitude_of_central_meridian: 265.0,
+        latitude_of_projection_origin: 25.0,
+        false_easting: 0.0,
+        false_northing: 0.0,
+        ellipsoid: Some(Ellipsoid::Sphere {
+            earth_radius: 6_371_229.0,
+        }),
+    };
+    let mut crs = file.add_variable::<i32>("crs", &[]).unwrap();
+    mapping.put_attributes(&mut crs).unwrap();
+
+    let mut group = file.add_group("data").unwrap();
+    let mut var = group.add_variable::<f32>("temp", &[]).unwrap();
+    var.put_attribute("grid_mapping", "crs").unwrap();
+
+    let var = file.variable("data/temp").unwrap();
+    let parsed = GridMapping::for_variable(&var).unwrap().unwrap();
+    assert_eq!(parsed, mapping);
+    assert_eq!(
+        parsed.to_proj(),
+        "+proj=lcc +lat_1=25 +lat_2=60 +lat_0=25 +lon_0=265 +x_0=0 +y_0=0 +R=6371229 +units=m +no_defs"
+    );
+    assert!(parsed
+        .to_wkt2()
+        .contains("METHOD[\"Lambert Conic Conformal (2SP)\",ID[\"EPSG\",9802]]"));
+
+    let var = file.variable("crs").unwrap();
+    assert_eq!(GridMapping::for_variable(&var).unwrap(), None);
+
+    let mappings = [
+        (
+            GridMapping::PolarStereographic {
+                straight_vertical_longitude_from_pole: -45.0,
+                latitude_of_projection_origin: 90.0,
+                scale: TrueScale::ScaleFactor(0.994),
+                false_easting: 2_000_000.0,
+                false_northing: 2_000_000.0,
+                ellipsoid: None,
+            },
+            "+proj=stere +lat_0=90 +k_0=0.994 +lon_0=-45 +x_0=2000000 +y_0=2000000 +ellps=WGS84 +units=m +no_defs",
+            "METHOD[\"Polar Stereographic (variant A)\",ID[\"EPSG\",9810]]",
+        ),
+        (
+            GridMapping::TransverseMercator {
+                scale_factor_at_central_meridian: 0.999_601_271_7,
+                longitude_of_central_meridian: -2.0,
+                latitude_of_projection_origin: 49.0,
+                false_easting: 400_000.0,
+                false_northing: -100_000.0,
+                ellipsoid: Some(Ellipsoid::InverseFlattening {
+                    semi_major_axis: 6_377_563.396,
+                    inverse_flattening: 299.324_964_6,
+                }),
+            },
+            "+proj=tmerc +lat_0=49 +lon_0=-2 +k_0=0.9996012717 +x_0=400000 +y_0=-100000 +a=6377563.396 +rf=299.3249646 +units=m +no_defs",
+            "METHOD[\"Transverse Mercator\",ID[\"EPSG\",9807]]",
+        ),
+    ];
+    for (i, (mapping, proj, method)) in mappings.into_iter().enumerate() {
+        let name = format!("crs_{i}");
+        let mut crs = file.add_variable::<i32>(&name, &[]).unwrap();
+        mapping.put_attributes(&mut crs).unwrap();
+        let mut var = file.add_variable::<f32>(&format!("var_{i}"), &[]).unwrap();
+        var.put_attribute("grid_mapping", name.as_str()).unwrap();
+
+        let var = file.variable(&format!("var_{i}")).unwrap();
+        let parsed = GridMapping::for_variable(&var).unwrap().unwrap();
+        assert_eq!(parsed, mapping);
+        assert_eq!(parsed.to_proj(), proj);
+        let wkt = parsed.to_wkt2();
+        assert!(wkt.contains(method), "{method} not found in {wkt}");
+    }
+}
+
+#[test]
+fn ragged_arrays() {
+    use netcdf::cf::{FeatureType, RaggedArray, RaggedArrayWriter, RaggedLayout};
+
+    let features = vec![vec![1.0_f32, 2.0, 3.0], vec![], vec![4.0, 5.0]];
+    for layout in [RaggedLayout::Contiguous, RaggedLayout::Indexed] {
+        let d = tempfile::tempdir().unwrap();
+        let path = d.path().join("cf_ragged.nc");
+        let mut file = netcdf::create(path).unwrap();
+
+        let writer =
+            RaggedArrayWriter::new(FeatureType::TimeSeries, layout).instance_dimension("station");
+        writer.write(&mut file, "temp", &features).unwrap();
+        let times = vec![vec![0_i32, 1, 2], vec![], vec![0, 1]];
+        writer.write(&mut file, "time", &times).unwrap();
+        assert!(writer.write(&mut file, "bad", &[vec![1_i32]]).is_err());
+
+        let ragged = RaggedArray::from_file(&file).unwrap();
+        assert_eq!(ragged.len(), 1);
+        let ragged = ragged.into_iter().next().unwrap();
+        assert_eq!(ragged.feature_type(), Some(FeatureType::TimeSeries));
+        assert_eq!(ragged.layout(), layout);
+        assert_eq!(ragged.instance_dimension(), "station");
+        assert_eq!(ragged.sample_dimension(), "obs");
+        assert_eq!(ragged.len(), 3);
+        assert_eq!(ragged.feature_len(1), Some(0));
+
+        let temp = file.variable("temp").unwrap();
+        assert_eq!(ragged.features::<f32>(&temp).unwrap(), features);
+        assert_eq!(ragged.feature::<f32>(&temp, 2).unwrap(), [4.0, 5.0]);
+        assert!(ragged.feature::<f32>(&temp, 1).unwrap().is_empty());
+        let time = file.variable("time").unwrap();
+        assert_eq!(ragged.features::<i32>(&time).unwrap(), times);
+    }
+}
+
+#[test]
+fn several_ragged_arrays() {
+    use netcdf::cf::{FeatureType, RaggedArray, RaggedArrayWriter, RaggedLayout};
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("cf_ragged_several.nc");
+    let mut file = netcdf::create(path).unwrap();
+
+    let stations = vec![vec![1_i32, 2], vec![3]];
+    RaggedArrayWriter::new(FeatureType::TimeSeries, RaggedLayout::Contiguous)
+        .instance_dimension("station")
+        .sample_dimension("obs")
+        .write(&mut file, "temp", &stations)
+        .unwrap();
+    let tracks = vec![vec![4_i32], vec![], vec![5, 6, 7]];
+    RaggedArrayWriter::new(FeatureType::TimeSeries, RaggedLayout::Indexed)
+        .instance_dimension("track")
+        .sample_dimension("point")
+        .write(&mut file, "speed", &tracks)
+        .unwrap();
+
+    assert_eq!(RaggedArray::from_file(&file).unwrap().len(), 2);
+
+    let temp = file.variable("temp").unwrap();
+    let ragged = RaggedArray::for_variable(&temp).unwrap().unwrap();
+    assert_eq!(ragged.instance_dimension(), "station");
+    assert_eq!(ragged.features::<i32>(&temp).unwrap(), stations);
+
+    let speed = file.variable("speed").unwrap();
+    let ragged = RaggedArray::for_variable(&speed).unwrap().unwrap();
+    assert_eq!(ragged.layout(), RaggedLayout::Indexed);
+    assert_eq!(ragged.sample_dimension(), "point");
+    assert_eq!(ragged.features::<i32>(&speed).unwrap(), tracks);
+    assert_eq!(ragged.feature::<i32>(&speed, 2).unwrap(), [5, 6, 7]);
+
+    let count = file.variable(ragged.variable()).unwrap();
+    assert!(RaggedArray::for_variable(&count).unwrap().is_none());
+
+    let empty = RaggedArrayWriter::new(FeatureType::Trajectory, RaggedLayout::Contiguous)
+        .instance_dimension("trajectory")
+        .sample_dimension("position");
+    assert!(empty
+        .write(&mut file, "none", &[Vec::<i32>::new()])
+        .is_err());
+    assert!(file.dimension("trajectory").is_none());
+}
+
+#[test]
+#[cfg(feature = "ndarray")]
+fn reduce_over_time() {
+    use netcdf::cf::Reduction;
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("cf_reduce_in.nc");
+    let mut file = netcdf::create(&path).unwrap();
+    file.add_dimension("time", 3).unwrap();
+    file.add_dimension("x", 2).unwrap();
+    file.add_dimension("nv", 2).unwrap();
+
+    let mut time = file.add_variable::<f64>("time", &["time"]).unwrap();
+    time.put_attribute("units", "days since 2000-01-01")
+        .unwrap();
+    time.put_attribute("bounds", "time_bnds").unwrap();
+    time.put_values(&[0.5, 1.5, 2.5], ..).unwrap();
+    file.add_variable::<f64>("time_bnds", &["time", "nv"])
+        .unwrap()
+        .put_values(&[0.0, 1.0, 1.0, 2.0, 2.0, 3.0], ..)
+        .unwrap();
+
+    let mut temp = file.add_variable::<f32>("temp", &["time", "x"]).unwrap();
+    temp.put_attribute("units", "K").unwrap();
+    temp.put_attribute("cell_methods", "area: mean").unwrap();
+    temp.set_fill_value(-1.0_f32).unwrap();
+    temp.put_values(&[1.0, -1.0, 2.0, -1.0, 6.0, -1.0], ..)
+        .unwrap();
+    file.close().unwrap();
+
+    let file = netcdf::open(&path).unwrap();
+    let temp = file.variable("temp").unwrap();
+    let mut out = netcdf::create(d.path().join("cf_reduce_out.nc")).unwrap();
+    netcdf::cf::reduce(&temp, "time", Reduction::Mean, &mut out, "temp").unwrap();
+    netcdf::cf::reduce(&temp, "time", Reduction::Maximum, &mut out, "temp_max").unwrap();
+
+    let mean = out.variable("temp").unwrap();
+    assert_eq!(mean.get_values::<f64, _>(..).unwrap(), [3.0, -1.0]);
+    assert_eq!(
+        mean.attribute_value("cell_methods").unwrap().unwrap(),
+        "area: mean time: mean".into()
+    );
+    assert_eq!(mean.attribute_value("units").unwrap().unwrap(), "K".into());
+    assert_eq!(mean.fill_value::<f64>().unwrap(), Some(-1.0));
+
+    let max = out.variable("temp_max").unwrap();
+    assert_eq!(max.dimensions()[0].name(), "time_maximum");
+    assert_eq!(
+        max.attribute_value("cell_methods").unwrap().unwrap(),
+        "area: mean time_maximum: maximum".into()
+    );
+    assert_eq!(max.get_values::<f64, _>(..).unwrap(), [6.0, -1.0]);
+
+    let time = out.variable("time").unwrap();
+    assert_eq!(time.get_values::<f64, _>(..).unwrap(), [1.5]);
+    let bounds = out.variable("time_bnds").unwrap();
+    assert_eq!(bounds.get_values::<f64, _>(..).unwrap(), [0.0, 3.0]);
+
+    let mut out = netcdf::create(d.path().join("cf_reduce_nv.nc")).unwrap();
+    out.add_dimension("nv", 4).unwrap();
+    netcdf::cf::reduce(&temp, "time", Reduction::Mean, &mut out, "temp").unwrap();
+    let bounds = out.variable("time_bnds").unwrap();
+    assert_eq!(bounds.dimensions()[1].name(), "time_nv");
+    assert_eq!(bounds.get_values::<f64, _>(..).unwrap(), [0.0, 3.0]);
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "num-complex")]
+use netcdf::types::*;
+use num_complex::Complex;
+
+#[test]
+fn put_get_complex() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("complex.nc");
+
+    let values = vec![Complex::new(1.0, -1.0), Complex::new(0.5, 2.0)];
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_type::<Complex<f64>>().unwrap();
+        file.add_dimension("x", 2).unwrap();
+        let mut var = file
+            .add_variable::<Complex<f64>>("spectrum", &["x"])
+            .unwrap();
+        var.put_values(&values, ..).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("spectrum").unwrap();
+    assert_eq!(var.get_values::<Complex<f64>, _>(..).unwrap(), values);
+    assert_eq!(var.get_complex_values::<f64, _>(..).unwrap(), values);
+    assert_eq!(
+        var.get_complex_values::<f32, _>(1..).unwrap(),
+        [Complex::new(0.5_f32, 2.0)]
+    );
+}
+
+#[test]
+fn complex_layouts() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("complex_layouts.nc");
+
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    struct RealImag {
+        real: f32,
+        imag: f32,
+    }
+    unsafe impl NcTypeDescriptor for RealImag {
+        fn type_descriptor() -> NcVariableType {
+            let field = |name: &str, offset| CompoundTypeField {
+                name: name.to_owned(),
+                basetype: f32::type_descriptor(),
+                arraydims: None,
+                offset,
+            };
+            NcVariableType::Compound(CompoundType {
+                name: "complex".to_owned(),
+                size: 8,
+                fields: vec![field("real", 0), field("imag", 4)],
+            })
+        }
+    }
+
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_type::<RealImag>().unwrap();
+        file.add_dimension("x", 2).unwrap();
+        file.add_dimension("y", 3).unwrap();
+        file.add_dimension("ri", 2).unwrap();
+
+        let mut var = file.add_variable::<RealImag>("compound", &["x"]).unwrap();
+        var.put_values(
+            &[
+                RealImag {
+                    real: 1.0,
+                    imag: 2.0,
+                },
+                RealImag {
+                    real: 3.0,
+                    imag: 4.0,
+                },
+            ],
+            ..,
+        )
+        .unwrap();
+
+        let mut var = file
+            .add_variable::<f64>("dimension", &["x", "y", "ri"])
+            .unwrap();
+        let parts = (0..12).map(f64::from).collect::<Vec<_>>();
+        var.put_values(&parts, ..).unwrap();
+
+        file.add_variable::<f64>("real", &["x", "y"]).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("compound").unwrap();
+    assert_eq!(
+        var.get_complex_values::<f64, _>(..).unwrap(),
+        [Complex::new(1.0, 2.0), Complex::new(3.0, 4.0)]
+    );
+
+    let var = file.variable("dimension").unwrap();
+    assert_eq!(
+        var.get_complex_values::<f64, _>((1, 1..)).unwrap(),
+        [Complex::new(8.0, 9.0), Complex::new(10.0, 11.0)]
+    );
+    #[cfg(feature = "ndarray")]
+    {
+        let arr = var.get_complex::<f32, _>(..).unwrap();
+        assert_eq!(arr.shape(), [2, 3]);
+        assert_eq!(arr[[1, 0]], Complex::new(6.0, 7.0));
+    }
+
+    let var = file.variable("real").unwrap();
+    assert!(var.get_complex_values::<f64, _>(..).is_err());
+}
@@ -0,0 +1,135 @@
+use netcdf::copy::{CopyOptions, Storage};
+
+fn create(path: &std::path::Path) {
+    let mut file = netcdf::create(path).unwrap();
+    file.add_attribute("title", "copy test").unwrap();
+    file.add_unlimited_dimension("time").unwrap();
+    file.add_dimension("x", 4).unwrap();
+
+    let mut t = file.add_variable::<i32>("t", &["time", "x"]).unwrap();
+    t.put_values(&[1, 2, 3, 4, 5, 6, 7, 8], (0..2, ..)).unwrap();
+    t.put_attribute("units", "K").unwrap();
+    let mut x = file.add_variable::<f64>("x", &["x"]).unwrap();
+    x.put_values(&[0.0_f64, 1.0, 2.0, 3.0], ..).unwrap();
+
+    let mut group = file.add_group("forecast").unwrap();
+    group.add_dimension("y", 2).unwrap();
+    let mut y = group.add_variable::<i16>("y", &["y", "x"]).unwrap();
+    y.put_values(&[1_i16, 2, 3, 4, 5, 6, 7, 8], ..).unwrap();
+}
+
+#[test]
+fn flatten_to_classic() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("copy_source.nc");
+    create(&path);
+
+    let src = netcdf::open(&path).unwrap();
+    let path = d.path().join("copy_classic.nc");
+    let mut dst = netcdf::create_with(&path, netcdf::Options::empty()).unwrap();
+
+    // Groups can not be copied to a classic file without flattening
+    assert!(matches!(
+        netcdf::copy::copy(&src, &mut dst, &CopyOptions::default()),
+        Err(netcdf::Error::Unsupported(_))
+    ));
+    drop(dst);
+
+    let mut dst = netcdf::create_with(&path, netcdf::Options::empty()).unwrap();
+    let options = CopyOptions {
+        variables: Some(vec!["t".to_owned(), "/forecast/y".to_owned()]),
+        slices: vec![("x".to_owned(), 1..3)],
+        flatten: true,
+        ..CopyOptions::default()
+    };
+    netcdf::copy::copy(&src, &mut dst, &options).unwrap();
+    drop(dst);
+
+    let file = netcdf::open(&path).unwrap();
+    assert!(file.variable("x").is_none());
+    assert_eq!(file.dimension_len("x"), Some(2));
+    assert_eq!(file.dimension_len("forecast_y"), Some(2));
+    let t = file.variable("t").unwrap();
+    assert_eq!(t.get_values::<i32, _>(..).unwrap(), [2, 3, 6, 7]);
+    assert_eq!(t.attribute("units").unwrap().value().unwrap(), "K".into());
+    let y = file.variable("forecast_y").unwrap();
+    assert_eq!(y.get_values::<i16, _>(..).unwrap(), [2, 3, 6, 7]);
+}
+
+#[test]
+fn chunk_and_compress() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("copy_source.nc");
+    create(&path);
+
+    let src = netcdf::open(&path).unwrap();
+    let path = d.path().join("copy_compressed.nc");
+    let mut dst = netcdf::create(&path).unwrap();
+    let options = CopyOptions {
+        chunking: vec![("time".to_owned(), 1), ("x".to_owned(), 2)],
+        storage: Storage {
+            deflate: Some(5),
+            shuffle: true,
+            ..Storage::default()
+        },
+        variable_storage: vec![("x".to_owned(), Storage::default())],
+        ..CopyOptions::default()
+    };
+    netcdf::copy::copy(&src, &mut dst, &options).unwrap();
+    drop(dst);
+
+    let file = netcdf::open(&path).unwrap();
+    assert_eq!(
+        file.attribute("title").unwrap().value().unwrap(),
+        "copy test".into()
+    );
+    let t = file.variable("t").unwrap();
+    assert_eq!(t.chunking().unwrap(), Some(vec![1, 2]));
+    assert_eq!(
+        t.get_values::<i32, _>(..).unwrap(),
+        [1, 2, 3, 4, 5, 6, 7, 8]
+    );
+    #[cfg(feature = "4.8.0")]
+    {
+        let filters = t.filters().unwrap();
+        assert!(filters.iter().any(|filter| filter.id == 1));
+        assert!(file.variable("x").unwrap().filters().unwrap().is_empty());
+    }
+
+    let root = file.root();
+    let forecast = root.group("forecast").unwrap();
+    let y = forecast.variable("y").unwrap();
+    assert_eq!(
+        y.get_values::<i16, _>(..).unwrap(),
+        [1, 2, 3, 4, 5, 6, 7, 8]
+    );
+}
+
+#[test]
+fn keep_compression() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("copy_deflated.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 4).unwrap();
+        let mut x = file.add_variable::<f64>("x", &["x"]).unwrap();
+        x.set_compression(3, true).unwrap();
+        x.put_values(&[0.0_f64, 1.0, 2.0, 3.0], ..).unwrap();
+    }
+
+    let src = netcdf::open(&path).unwrap();
+    let path = d.path().join("copy_deflated_copy.nc");
+    let mut dst = netcdf::create(&path).unwrap();
+    netcdf::copy::copy(&src, &mut dst, &CopyOptions::default()).unwrap();
+    drop(dst);
+
+    let file = netcdf::open(&path).unwrap();
+    let header = netcdf::header::Header::from_file(&file).unwrap();
+    assert_eq!(
+        header.root.variables[0].compression,
+        Some(netcdf::header::Compression {
+            deflate_level: 3,
+            shuffle: true,
+        })
+    );
+}
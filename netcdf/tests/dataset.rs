@@ -0,0 +1,96 @@
+#![cfg(all(feature = "derive", feature = "ndarray"))]
+use netcdf::NcDataset;
+
+#[derive(NcDataset, Debug, PartialEq)]
+#[netcdf(title = "Test product", Conventions = "CF-1.8")]
+struct Product {
+    #[netcdf(dims = ["time"], units = "days since 2000-01-01")]
+    time: Vec<f64>,
+    #[netcdf(
+        dims = ["time", "lat", "lon"],
+        units = "K",
+        long_name = "Air temperature",
+        fill_value = -999.0
+    )]
+    temperature: ndarray::ArrayD<f32>,
+    #[netcdf(dims = ["lat", "lon"], valid_range = [0, 100])]
+    mask: ndarray::Array2<u8>,
+    #[netcdf(rename = "station_id")]
+    station: i32,
+}
+
+#[test]
+fn roundtrip() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("dataset_roundtrip.nc");
+
+    let product = Product {
+        time: vec![0.0, 1.0],
+        temperature: ndarray::ArrayD::from_shape_fn(vec![2, 3, 4], |i| (i[0] + i[1] * i[2]) as f32),
+        mask: ndarray::Array2::from_shape_fn((3, 4), |(j, i)| (j * i) as u8),
+        station: 18700,
+    };
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        product.write_to(&mut file).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    assert_eq!(
+        file.attribute("title").unwrap().value().unwrap(),
+        "Test product".into()
+    );
+    assert_eq!(file.dimension_len("lat"), Some(3));
+    let var = file.variable("temperature").unwrap();
+    assert_eq!(var.fill_value::<f32>().unwrap(), Some(-999.0));
+    assert_eq!(
+        var.attribute_value("long_name").unwrap().unwrap(),
+        "Air temperature".into()
+    );
+    assert!(file.variable("station_id").is_some());
+
+    assert_eq!(Product::read_from(&file).unwrap(), product);
+}
+
+#[test]
+fn validation() {
+    let d = tempfile::tempdir().unwrap();
+
+    #[derive(NcDataset)]
+    struct Mismatched {
+        #[netcdf(dims = ["x"])]
+        a: Vec<f64>,
+        #[netcdf(dims = ["x"])]
+        b: Vec<f64>,
+    }
+    let mut file = netcdf::create(d.path().join("dataset_mismatch.nc")).unwrap();
+    let dataset = Mismatched {
+        a: vec![1.0, 2.0],
+        b: vec![1.0],
+    };
+    dataset.write_to(&mut file).unwrap_err();
+
+    #[derive(NcDataset)]
+    struct Empty {
+        #[netcdf(dims = ["y"])]
+        a: Vec<f64>,
+    }
+    let mut file = netcdf::create(d.path().join("dataset_empty.nc")).unwrap();
+    Empty { a: vec![] }.write_to(&mut file).unwrap_err();
+    assert_eq!(file.dimension_len("y"), None);
+
+    #[derive(NcDataset)]
+    struct Temperature {
+        #[netcdf(dims = ["x"], units = "K")]
+        temperature: Vec<f32>,
+    }
+    let path = d.path().join("dataset_units.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 1).unwrap();
+        let mut var = file.add_variable::<f32>("temperature", &["x"]).unwrap();
+        var.put_attribute("units", "degC").unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+    assert!(Temperature::read_from(&file).is_err());
+}
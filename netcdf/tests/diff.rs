@@ -0,0 +1,158 @@
+use netcdf::diff::{Differ, DifferenceKind, Tolerance};
+
+fn create(path: &std::path::Path, temperature: &[f32], units: &str) {
+    let mut file = netcdf::create(path).unwrap();
+    file.add_attribute("title", "diff test").unwrap();
+    file.add_dimension("x", 4).unwrap();
+
+    let mut t = file.add_variable::<f32>("t", &["x"]).unwrap();
+    t.set_fill_value(-999.0_f32).unwrap();
+    t.put_values(temperature, ..).unwrap();
+    t.put_attribute("units", units).unwrap();
+
+    let mut group = file.add_group("forecast").unwrap();
+    let mut n = group.add_variable::<i32>("n", &["x"]).unwrap();
+    n.put_values(&[1, 2, 3, 4], ..).unwrap();
+}
+
+#[test]
+fn identical() {
+    let d = tempfile::tempdir().unwrap();
+    let first = d.path().join("diff_first.nc");
+    let second = d.path().join("diff_second.nc");
+    create(&first, &[1.0, f32::NAN, 3.0, -999.0], "K");
+    create(&second, &[1.0, -999.0, 3.0, f32::NAN], "K");
+
+    let first = netcdf::open(&first).unwrap();
+    let second = netcdf::open(&second).unwrap();
+    // Values which are NaN or fill values in both files are not compared
+    let report = netcdf::diff::diff(&first, &second).unwrap();
+    assert!(report.is_identical(), "{report}");
+}
+
+#[test]
+fn infinities() {
+    let d = tempfile::tempdir().unwrap();
+    let first = d.path().join("diff_first.nc");
+    let second = d.path().join("diff_second.nc");
+    create(&first, &[f32::INFINITY, f32::NEG_INFINITY, 3.0, 4.0], "K");
+    create(
+        &second,
+        &[f32::INFINITY, f32::NEG_INFINITY, 3.0, f32::INFINITY],
+        "K",
+    );
+
+    let first = netcdf::open(&first).unwrap();
+    let second = netcdf::open(&second).unwrap();
+    let report = Differ::new()
+        .with_tolerance(Tolerance {
+            absolute: 0.0,
+            relative: 1e-3,
+        })
+        .diff(&first, &second)
+        .unwrap();
+    let differences = report.differences();
+    assert_eq!(differences.len(), 1, "{report}");
+    let data = differences[0].data.as_ref().unwrap();
+    assert_eq!(data.count, 1);
+    assert_eq!(data.first_index, [3]);
+}
+
+#[test]
+fn masked_in_one_file() {
+    let d = tempfile::tempdir().unwrap();
+    let first = d.path().join("diff_first.nc");
+    let second = d.path().join("diff_second.nc");
+    create(&first, &[1.0, f32::NAN, 3.0, -999.0], "K");
+    create(&second, &[1.0, 2.0, 3.0, 4.0], "K");
+
+    let first = netcdf::open(&first).unwrap();
+    let second = netcdf::open(&second).unwrap();
+    let report = netcdf::diff::diff(&first, &second).unwrap();
+    let differences = report.differences();
+    assert_eq!(differences.len(), 1, "{report}");
+    let data = differences[0].data.as_ref().unwrap();
+    assert_eq!(data.count, 2);
+    assert_eq!(data.first_index, [1]);
+    assert_eq!(data.max_absolute, 0.0);
+}
+
+#[test]
+fn exact_integers() {
+    let d = tempfile::tempdir().unwrap();
+    let first = d.path().join("diff_first.nc");
+    let second = d.path().join("diff_second.nc");
+    for (path, value) in [(&first, 1_i64 << 53), (&second, (1_i64 << 53) + 1)] {
+        let mut file = netcdf::create(path).unwrap();
+        file.add_dimension("x", 1).unwrap();
+        let mut var = file.add_variable::<i64>("n", &["x"]).unwrap();
+        var.put_values(&[value], ..).unwrap();
+    }
+
+    let first = netcdf::open(&first).unwrap();
+    let second = netcdf::open(&second).unwrap();
+    // The values are the same as `f64`
+    let report = netcdf::diff::diff(&first, &second).unwrap();
+    let differences = report.differences();
+    assert_eq!(differences.len(), 1, "{report}");
+    let data = differences[0].data.as_ref().unwrap();
+    assert_eq!(data.count, 1);
+    assert_eq!(data.max_absolute, 1.0);
+}
+
+#[test]
+fn differences() {
+    let d = tempfile::tempdir().unwrap();
+    let first = d.path().join("diff_first.nc");
+    let second = d.path().join("diff_second.nc");
+    create(&first, &[1.0, 2.0, 3.0, 4.0], "K");
+    create(&second, &[1.0, 2.1, 3.0, 5.0], "degC");
+    {
+        let mut file = netcdf::append(&second).unwrap();
+        file.add_attribute("history", "modified").unwrap();
+    }
+
+    let first = netcdf::open(&first).unwrap();
+    let second = netcdf::open(&second).unwrap();
+    let report = netcdf::diff::diff(&first, &second).unwrap();
+    let differences = report.differences();
+    assert_eq!(differences.len(), 3, "{report}");
+
+    assert_eq!(differences[0].kind, DifferenceKind::Missing);
+    assert_eq!(differences[0].location, "/history");
+    assert_eq!(differences[1].kind, DifferenceKind::Attribute);
+    assert_eq!(differences[1].location, "/t/units");
+    assert_eq!(differences[2].kind, DifferenceKind::Data);
+    assert_eq!(differences[2].location, "/t");
+    let data = differences[2].data.as_ref().unwrap();
+    assert_eq!(data.count, 2);
+    assert_eq!(data.first_index, [1]);
+    assert!((data.max_absolute - 1.0).abs() < 1e-6);
+
+    // The small difference is within the tolerance of the variable
+    let report = Differ::new()
+        .with_variable_tolerance(
+            "/t",
+            Tolerance {
+                absolute: 0.2,
+                relative: 0.0,
+            },
+        )
+        .diff(&first, &second)
+        .unwrap();
+    let data = report.differences()[2].data.as_ref().unwrap();
+    assert_eq!(data.count, 1);
+    assert_eq!(data.first_index, [3]);
+
+    let report = Differ::new().without_data().diff(&first, &second).unwrap();
+    assert_eq!(report.differences().len(), 2);
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.starts_with(r#"{"differences":[{"kind":"Missing","location":"/history""#));
+        assert_eq!(
+            serde_json::from_str::<netcdf::diff::Report>(&json).unwrap(),
+            report
+        );
+    }
+}
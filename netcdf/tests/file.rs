@@ -70,3 +70,41 @@ fn fetch_from_path() {
         _ => panic!(),
     }
 }
+
+#[test]
+fn root_of_classic_file() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("root_of_classic_file.nc");
+    {
+        let mut file = netcdf::create_with(&path, netcdf::Options::_64BIT_OFFSET).unwrap();
+        let mut root = file.root_mut();
+        root.add_dimension("x", 2).unwrap();
+        root.add_variable::<i32>("var", &["x"]).unwrap();
+        root.add_attribute("title", "classic").unwrap();
+
+        assert!(matches!(
+            root.add_group("group"),
+            Err(netcdf::Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            root.add_type_from_descriptor(netcdf::types::NcVariableType::Opaque(
+                netcdf::types::OpaqueType {
+                    name: "opaque".to_owned(),
+                    size: 4,
+                }
+            )),
+            Err(netcdf::Error::Unsupported(_))
+        ));
+    }
+    let file = netcdf::open(&path).unwrap();
+    let root = file.root();
+    assert_eq!(root.variables().count(), 1);
+    assert_eq!(root.dimensions().count(), 1);
+    assert_eq!(root.attributes().count(), 1);
+    assert_eq!(root.groups().count(), 0);
+    assert_eq!(root.types().count(), 0);
+    assert!(root.group("group").is_none());
+    assert!(root.parent().unwrap().is_none());
+    assert_eq!(root.full_path().unwrap(), "/");
+    assert_eq!(file.walk().unwrap().count(), 3);
+}
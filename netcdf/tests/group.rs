@@ -95,7 +95,7 @@ fn add_and_get_from_path() {
         assert!(file.add_attribute("a/c/test", "test").is_err());
     }
     let file = netcdf::open(path).unwrap();
-    let root = file.root().unwrap();
+    let root = file.root();
     assert_eq!(
         root.group("a/b").unwrap().variable("var").unwrap().name(),
         root.variable("a/b/var").unwrap().name(),
@@ -106,3 +106,171 @@ fn add_and_get_from_path() {
         _ => panic!(),
     }
 }
+
+#[test]
+fn fallible_accessors() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("fallible_accessors.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        let mut group = file.add_group("group").unwrap();
+        group.add_dimension("x", 2).unwrap();
+        group.add_variable::<i32>("var", &["x"]).unwrap();
+        group.add_attribute("attr", 1_i32).unwrap();
+        group.add_group("sub").unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+    let root = file.root();
+    assert!(file.try_variable("missing/var").unwrap().is_none());
+    assert_eq!(file.try_dimension_len("group/x").unwrap(), Some(2));
+
+    let group = root.try_group("group").unwrap().unwrap();
+    assert_eq!(group.try_name().unwrap(), "group");
+    let var = group.try_variable("var").unwrap().unwrap();
+    assert_eq!(var.try_name().unwrap(), "var");
+    assert_eq!(
+        var.try_vartype().unwrap(),
+        netcdf::types::NcVariableType::Int(netcdf::types::IntType::I32)
+    );
+    assert!(var.try_attribute("missing").unwrap().is_none());
+    assert_eq!(group.try_variables().unwrap().count(), 1);
+    let attrs = group
+        .try_attributes()
+        .unwrap()
+        .collect::<netcdf::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(attrs[0].try_name().unwrap(), "attr");
+    let dims = group
+        .try_dimensions()
+        .unwrap()
+        .collect::<netcdf::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(dims[0].try_name().unwrap(), "x");
+    assert_eq!(dims[0].try_len().unwrap(), 2);
+    assert_eq!(group.try_groups().unwrap().count(), 1);
+    assert_eq!(group.try_types().unwrap().count(), 0);
+    assert!(group.try_group("missing/sub").unwrap().is_none());
+    assert!(root.try_group("group/sub").unwrap().is_some());
+}
+
+#[test]
+fn hierarchy() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("hierarchy.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_attribute("title", "forecast").unwrap();
+        let mut surface = file.add_group("forecast/surface").unwrap();
+        surface.add_dimension("x", 2).unwrap();
+        let mut var = surface.add_variable::<f32>("t2m", &["x"]).unwrap();
+        var.put_attribute("units", "K").unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+    let root = file.root();
+    assert!(root.parent().unwrap().is_none());
+    assert_eq!(root.full_path().unwrap(), "/");
+
+    let surface = file.group("forecast/surface").unwrap().unwrap();
+    assert_eq!(surface.full_path().unwrap(), "/forecast/surface");
+    let forecast = surface.parent().unwrap().unwrap();
+    assert_eq!(forecast.name(), "forecast");
+
+    match file.get_by_path("/forecast/surface/t2m").unwrap() {
+        Some(netcdf::Object::Variable(var)) => assert_eq!(var.name(), "t2m"),
+        x => panic!("{x:?}"),
+    }
+    match file.get_by_path("/forecast/surface/t2m/units").unwrap() {
+        Some(netcdf::Object::Attribute(attr)) => assert_eq!(attr.name(), "units"),
+        x => panic!("{x:?}"),
+    }
+    match file.get_by_path("/forecast/surface/x").unwrap() {
+        Some(netcdf::Object::Dimension(dim)) => assert_eq!(dim.len(), 2),
+        x => panic!("{x:?}"),
+    }
+    match forecast.get_by_path("surface").unwrap() {
+        Some(netcdf::Object::Group(group)) => assert_eq!(group.name(), "surface"),
+        x => panic!("{x:?}"),
+    }
+    assert!(forecast.get_by_path("/surface").unwrap().is_none());
+    match forecast.get_by_path("/forecast/surface/").unwrap() {
+        Some(netcdf::Object::Group(group)) => assert_eq!(group.name(), "surface"),
+        x => panic!("{x:?}"),
+    }
+    assert!(matches!(
+        file.get_by_path("/title").unwrap(),
+        Some(netcdf::Object::Attribute(_))
+    ));
+    assert!(matches!(
+        forecast.get_by_path("/title").unwrap(),
+        Some(netcdf::Object::Attribute(_))
+    ));
+    assert!(file.get_by_path("/forecast/missing").unwrap().is_none());
+    assert!(file.get_by_path("/missing/t2m/units").unwrap().is_none());
+
+    let paths = file
+        .walk()
+        .unwrap()
+        .map(|(path, _)| path)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            "/title",
+            "/forecast",
+            "/forecast/surface",
+            "/forecast/surface/x",
+            "/forecast/surface/t2m",
+            "/forecast/surface/t2m/units",
+        ]
+    );
+    assert_eq!(surface.walk().unwrap().count(), 3);
+}
+
+#[test]
+fn scoped_dimensions() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("scoped_dimensions.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 3).unwrap();
+        file.add_unlimited_dimension("time").unwrap();
+        let mut outer = file.add_group("outer").unwrap();
+        outer.add_dimension("y", 2).unwrap();
+        let mut inner = outer.add_group("inner").unwrap();
+        inner.add_dimension("x", 4).unwrap();
+        let mut var = inner
+            .add_variable::<i32>("var", &["time", "y", "x"])
+            .unwrap();
+        var.put_values(&[1; 8], (0, .., ..)).unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+    let inner = file.group("outer/inner").unwrap().unwrap();
+
+    let x = inner.dimension("x").unwrap();
+    assert_eq!(x.len(), 4);
+    assert_eq!(x.group().unwrap().full_path().unwrap(), "/outer/inner");
+    let y = inner.dimension("y").unwrap();
+    assert_eq!(y.len(), 2);
+    assert_eq!(y.group().unwrap().full_path().unwrap(), "/outer");
+    let time = inner.dimension("time").unwrap();
+    assert!(time.is_unlimited());
+    assert_eq!(time.len(), 1);
+    assert_eq!(time.group().unwrap().full_path().unwrap(), "/");
+    assert!(inner.dimension("z").is_none());
+
+    let var = inner.variable("var").unwrap();
+    let groups = var
+        .dimensions()
+        .iter()
+        .map(|dim| dim.group().unwrap().full_path().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(groups, ["/", "/outer", "/outer/inner"]);
+    assert!(var.dimensions()[0].is_unlimited());
+
+    assert!(file.get_by_path("/outer/inner/time").unwrap().is_none());
+    assert!(file.get_by_path("/outer/y").unwrap().is_some());
+    match file.get_by_path("/outer/inner/x").unwrap() {
+        Some(netcdf::Object::Dimension(dim)) => assert_eq!(dim.len(), 4),
+        x => panic!("{x:?}"),
+    }
+}
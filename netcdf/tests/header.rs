@@ -0,0 +1,85 @@
+#![cfg(feature = "serde")]
+use netcdf::header::{Compression, Format, Header};
+use netcdf::types::{EnumType, NcVariableType};
+use netcdf::{AttributeValue, Extent, Extents};
+
+fn create(path: &std::path::Path) {
+    let mut file = netcdf::create(path).unwrap();
+    file.add_attribute("title", "header test").unwrap();
+    file.add_unlimited_dimension("time").unwrap();
+    file.add_dimension("x", 4).unwrap();
+    file.add_type_from_descriptor(NcVariableType::Enum(EnumType {
+        name: "flag".to_owned(),
+        fieldnames: vec!["off".to_owned(), "on".to_owned()],
+        fieldvalues: vec![0_u8, 1].into(),
+    }))
+    .unwrap();
+
+    let mut t = file.add_variable::<f32>("t", &["time", "x"]).unwrap();
+    t.set_chunking(&[1, 4]).unwrap();
+    t.set_compression(4, true).unwrap();
+    t.put_attribute("units", "K").unwrap();
+    t.put_values(&[1.0_f32, 2.0, 3.0, 4.0], (0, ..)).unwrap();
+
+    let mut group = file.add_group("forecast").unwrap();
+    group.add_attribute("members", vec![1_i32, 2, 3]).unwrap();
+    group.add_variable::<u8>("mask", &["x"]).unwrap();
+}
+
+#[test]
+fn json_roundtrip() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("header_source.nc");
+    create(&path);
+
+    let file = netcdf::open(&path).unwrap();
+    let header = Header::from_file(&file).unwrap();
+    assert_eq!(header.format, Format::Netcdf4);
+    assert_eq!(header.root.dimensions[0].len, 1);
+    assert!(header.root.dimensions[0].unlimited);
+    let t = &header.root.variables[0];
+    assert_eq!(t.dimensions, ["time", "x"]);
+    assert_eq!(t.chunking, Some(vec![1, 4]));
+    assert_eq!(
+        t.compression,
+        Some(Compression {
+            deflate_level: 4,
+            shuffle: true
+        })
+    );
+    assert_eq!(header.root.groups[0].name, "forecast");
+    assert_eq!(header.root.groups[0].variables[0].dimensions, ["x"]);
+
+    let json = serde_json::to_string(&header).unwrap();
+    let parsed: Header = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, header);
+
+    // The created file has the same description, without the data
+    let path = d.path().join("header_created.nc");
+    drop(parsed.create(&path).unwrap());
+    let created = netcdf::open(&path).unwrap();
+    let mut expected = header.clone();
+    expected.root.dimensions[0].len = 0;
+    assert_eq!(Header::from_file(&created).unwrap(), expected);
+    assert_eq!(created.variable("t").unwrap().len(), 0);
+}
+
+#[test]
+fn serialize_values() {
+    let value = AttributeValue::Doubles(vec![1.0, 2.5]);
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"Doubles":[1.0,2.5]}"#);
+    assert_eq!(
+        serde_json::from_str::<AttributeValue>(&json).unwrap(),
+        value
+    );
+
+    let typ = NcVariableType::Int(netcdf::types::IntType::U16);
+    let json = serde_json::to_string(&typ).unwrap();
+    assert_eq!(serde_json::from_str::<NcVariableType>(&json).unwrap(), typ);
+
+    let extents = Extents::from(vec![Extent::from(1..3), Extent::from(2)]);
+    let json = serde_json::to_string(&extents).unwrap();
+    let parsed: Extents = serde_json::from_str(&json).unwrap();
+    assert_eq!(format!("{parsed:?}"), format!("{extents:?}"));
+}
@@ -450,7 +450,7 @@ fn all_var_types() {
 
         let dim_name = "dim1";
 
-        let mut root = file.root_mut().unwrap();
+        let mut root = file.root_mut();
         root.add_dimension(dim_name, 10).unwrap();
 
         // byte
@@ -1801,3 +1801,28 @@ fn read_raw_values() {
     var.get_raw_values_into(&mut buffer[..d_lon * 1 * 4 + 1], (0, ..))
         .unwrap_err();
 }
+
+#[test]
+fn values_in_unit() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("values_in_unit.nc");
+
+    let mut file = netcdf::create(path).unwrap();
+    file.add_dimension("x", 3).unwrap();
+    let mut var = file.add_variable::<f32>("temperature", &["x"]).unwrap();
+    var.put_attribute("units", "K").unwrap();
+    var.put_attribute("_FillValue", -999.0_f32).unwrap();
+    var.put_values(&[273.15_f32, -999.0, 283.15], ..).unwrap();
+
+    let units = var.units().unwrap().unwrap();
+    let celsius: netcdf::units::Unit = "degC".parse().unwrap();
+    assert!(units.is_compatible(&celsius));
+
+    let values = var.get_values_in_unit(.., &celsius).unwrap();
+    assert!((values[0] - 0.0).abs() < 1e-4);
+    assert_eq!(values[1], -999.0);
+    assert!((values[2] - 10.0).abs() < 1e-4);
+
+    var.get_values_in_unit(.., &"m".parse().unwrap())
+        .unwrap_err();
+}
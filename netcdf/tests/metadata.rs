@@ -0,0 +1,87 @@
+#![cfg(feature = "derive")]
+use netcdf::NcAttributes;
+
+#[derive(NcAttributes, Debug, PartialEq)]
+struct Metadata {
+    title: String,
+    history: Option<String>,
+    valid_range: [f32; 2],
+    flag_values: Vec<u8>,
+    #[netcdf(rename = "Conventions")]
+    conventions: String,
+}
+
+#[test]
+fn roundtrip() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("metadata_roundtrip.nc");
+
+    let metadata = Metadata {
+        title: "Test product".to_owned(),
+        history: None,
+        valid_range: [-1.0, 1.0],
+        flag_values: vec![1, 2, 4],
+        conventions: "CF-1.8".to_owned(),
+    };
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        metadata.put_attributes(&mut file).unwrap();
+        file.add_dimension("x", 1).unwrap();
+        let mut var = file.add_variable::<f32>("var", &["x"]).unwrap();
+        metadata.put_attributes(&mut var).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    assert!(file.attribute("history").is_none());
+    assert_eq!(
+        file.attribute("Conventions").unwrap().value().unwrap(),
+        "CF-1.8".into()
+    );
+    assert_eq!(
+        Metadata::from_attributes(file.attributes()).unwrap(),
+        metadata
+    );
+    let var = file.variable("var").unwrap();
+    assert_eq!(
+        Metadata::from_attributes(var.attributes()).unwrap(),
+        metadata
+    );
+}
+
+#[test]
+fn errors() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("metadata_errors.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_attribute("title", 4_i32).unwrap();
+        file.add_attribute("valid_range", vec![0.0_f32, 1.0, 2.0])
+            .unwrap();
+        file.add_attribute("flag_values", vec![1_u8]).unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+
+    #[derive(NcAttributes, Debug)]
+    struct Title {
+        title: String,
+    }
+    let err = Title::from_attributes(file.attributes()).unwrap_err();
+    assert!(err.to_string().contains("title"));
+
+    #[derive(NcAttributes, Debug)]
+    struct Range {
+        valid_range: [f32; 2],
+    }
+    let err = Range::from_attributes(file.attributes()).unwrap_err();
+    assert!(err.to_string().contains("valid_range"));
+
+    #[derive(NcAttributes, Debug)]
+    struct Missing {
+        #[allow(dead_code)]
+        flag_values: Vec<u8>,
+        #[allow(dead_code)]
+        history: String,
+    }
+    let err = Missing::from_attributes(file.attributes()).unwrap_err();
+    assert!(matches!(err, netcdf::Error::NotFound(_)));
+}
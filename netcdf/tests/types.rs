@@ -1,4 +1,6 @@
 use netcdf::types::*;
+use netcdf::value::Value;
+use netcdf::AttributeValue;
 
 mod common;
 
@@ -32,7 +34,7 @@ fn test_empty_basic_types() {
     let file = netcdf::open(&path).unwrap();
     assert_eq!(file.types().unwrap().count(), 0);
 
-    let root = file.root().unwrap();
+    let root = file.root();
     assert_eq!(root.types().count(), 0);
     for var in file.variables() {
         match var.name().as_str() {
@@ -371,6 +373,46 @@ fn put_get_enum() {
     assert_eq!(bytes, bytes_copy);
 }
 
+#[test]
+fn put_get_enum_names() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_put_get_enum_names.nc");
+
+    let typ = NcVariableType::Enum(EnumType {
+        name: "cloud".to_owned(),
+        fieldnames: vec![
+            "clear".to_owned(),
+            "cumulus".to_owned(),
+            "stratus".to_owned(),
+        ],
+        fieldvalues: EnumTypeValues::U16(vec![0, 10, 20]),
+    });
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_type_from_descriptor(typ.clone()).unwrap();
+        file.add_dimension("x", 4).unwrap();
+        let mut var = file.add_variable_with_type("clouds", &["x"], &typ).unwrap();
+
+        var.put_enum_names(&["stratus", "clear", "clear", "cumulus"], ..)
+            .unwrap();
+        var.put_enum_names(&["cirrus"], [0]).unwrap_err();
+        var.put_values(&[1.0_f32], [0]).unwrap_err();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("clouds").unwrap();
+    assert_eq!(
+        var.get_enum_names(..).unwrap(),
+        ["stratus", "clear", "clear", "cumulus"]
+    );
+    assert_eq!(var.get_enum_names(2..).unwrap(), ["clear", "cumulus"]);
+    #[cfg(feature = "ndarray")]
+    assert_eq!(
+        var.get_enum(1..3).unwrap(),
+        ndarray::arr1(&["clear".to_owned(), "clear".to_owned()]).into_dyn()
+    );
+}
+
 #[test]
 fn put_get_vlen() {
     let d = tempfile::tempdir().unwrap();
@@ -424,6 +466,425 @@ fn put_get_vlen() {
     assert_eq!(errcode, netcdf_sys::NC_NOERR);
 }
 
+#[test]
+fn put_get_vlens() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_put_get_vlens.nc");
+
+    let values = vec![vec![1_i32, 2, 3], vec![], vec![4], vec![5, 6]];
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 4).unwrap();
+        let mut var = file.add_owned_variable::<Vec<i32>>("var", &["x"]).unwrap();
+        var.put_owned_values(&values, ..).unwrap();
+        var.put_owned_values(&[vec![1.0_f32]], ..).unwrap_err();
+
+        let mut group = file.add_group("g").unwrap();
+        group.add_owned_variable::<Vec<i32>>("var", &["x"]).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("var").unwrap();
+    assert_eq!(
+        var.vartype(),
+        NcVariableType::Vlen(VlenType {
+            name: "int_vlen".to_owned(),
+            basetype: Box::new(NcVariableType::Int(IntType::I32)),
+        })
+    );
+    assert_eq!(var.get_owned_values::<Vec<i32>, _>(..).unwrap(), values);
+    assert_eq!(
+        var.get_owned_values::<Vec<i32>, _>(1..3).unwrap(),
+        &values[1..3]
+    );
+    var.get_owned_values::<Vec<i64>, _>(..).unwrap_err();
+    var.get_values::<i32, _>(..).unwrap_err();
+}
+
+#[test]
+fn put_get_vlens_named() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_put_get_vlens_named.nc");
+
+    let values = vec![vec![1.5_f32], vec![], vec![2.0, 3.0]];
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 3).unwrap();
+        let typ = NcVariableType::Vlen(VlenType {
+            name: "ragged_t".to_owned(),
+            basetype: Box::new(NcVariableType::Float(FloatType::F32)),
+        });
+        file.add_type_from_descriptor(typ.clone()).unwrap();
+        let mut var = file.add_variable_with_type("var", &["x"], &typ).unwrap();
+        var.put_owned_values(&values, ..).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("var").unwrap();
+    assert_eq!(var.get_owned_values::<Vec<f32>, _>(..).unwrap(), values);
+    var.get_owned_values::<Vec<f64>, _>(..).unwrap_err();
+}
+
+#[test]
+#[cfg(feature = "derive")]
+fn put_get_padded_compound() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_put_get_padded_compound.nc");
+
+    #[derive(netcdf_derive::NcType, Copy, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Padded {
+        a: u8,
+        b: f64,
+        c: i16,
+    }
+
+    let values = (0..4)
+        .map(|i| Padded {
+            a: i,
+            b: f64::from(i) * 1.5,
+            c: -i16::from(i),
+        })
+        .collect::<Vec<_>>();
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", values.len()).unwrap();
+        file.add_type::<Padded>().unwrap();
+        let mut var = file.add_variable::<Padded>("var", &["x"]).unwrap();
+        var.put_values(&values, ..).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("var").unwrap();
+    assert_eq!(var.get_values::<Padded, _>(..).unwrap(), values);
+}
+
+#[test]
+#[cfg(feature = "derive")]
+fn put_get_vlens_compound() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_put_get_vlens_compound.nc");
+
+    #[derive(netcdf_derive::NcType, Copy, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Inner {
+        a: i16,
+        b: i16,
+    }
+
+    #[derive(netcdf_derive::NcType, Copy, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Outer {
+        inner: Inner,
+        c: f64,
+    }
+
+    let item = |x: i16| Outer {
+        inner: Inner { a: x, b: -x },
+        c: f64::from(x) / 2.0,
+    };
+    let values = vec![vec![item(1), item(2)], vec![item(3)]];
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 2).unwrap();
+        let mut var = file
+            .add_owned_variable::<Vec<Outer>>("var", &["x"])
+            .unwrap();
+        var.put_owned_values(&values, ..).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("var").unwrap();
+    assert_eq!(var.get_owned_values::<Vec<Outer>, _>(..).unwrap(), values);
+}
+
+#[test]
+fn put_get_owned_strings() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_put_get_owned_strings.nc");
+
+    let values = vec!["a".to_owned(), String::new(), "ragged".to_owned()];
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 3).unwrap();
+        let mut var = file.add_owned_variable::<String>("var", &["x"]).unwrap();
+        var.put_owned_values(&values, ..).unwrap();
+        var.put_owned_values(&["nul\0".to_owned()], 0).unwrap_err();
+
+        let mut var = file
+            .add_owned_variable::<Vec<String>>("nested", &["x"])
+            .unwrap();
+        var.put_owned_values(&[vec![], values.clone(), vec!["b".to_owned()]], ..)
+            .unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("var").unwrap();
+    assert_eq!(var.get_owned_values::<String, _>(..).unwrap(), values);
+    let var = file.variable("nested").unwrap();
+    assert_eq!(
+        var.get_owned_values::<Vec<String>, _>(1).unwrap(),
+        vec![values]
+    );
+}
+
+#[test]
+#[cfg(feature = "derive")]
+fn put_get_owned_compound() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_put_get_owned_compound.nc");
+
+    #[derive(netcdf_derive::NcType, Debug, Clone, PartialEq)]
+    #[netcdf(rename = "station")]
+    struct Station {
+        #[netcdf(rename = "station_name")]
+        name: String,
+        samples: Vec<f32>,
+        id: i32,
+    }
+
+    #[derive(netcdf_derive::NcType, Debug, Clone, PartialEq)]
+    #[netcdf(owned)]
+    struct Network {
+        station: Station,
+        code: u8,
+    }
+
+    let stations = vec![
+        Station {
+            name: "Blindern".to_owned(),
+            samples: vec![1.0, 2.5],
+            id: 18700,
+        },
+        Station {
+            name: String::new(),
+            samples: vec![],
+            id: -1,
+        },
+    ];
+    let networks = stations
+        .iter()
+        .map(|station| Network {
+            station: station.clone(),
+            code: 4,
+        })
+        .collect::<Vec<_>>();
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 2).unwrap();
+        let mut var = file
+            .add_owned_variable::<Station>("stations", &["x"])
+            .unwrap();
+        var.put_owned_values(&stations, ..).unwrap();
+        let mut var = file
+            .add_owned_variable::<Network>("networks", &["x"])
+            .unwrap();
+        var.put_owned_values(&networks, ..).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("stations").unwrap();
+    let NcVariableType::Compound(compound) = var.vartype() else {
+        panic!("not a compound type");
+    };
+    assert_eq!(compound.name, "station");
+    assert_eq!(compound.fields[0].name, "station_name");
+    assert_eq!(compound.fields[0].basetype, NcVariableType::String);
+    assert_eq!(var.get_owned_values::<Station, _>(..).unwrap(), stations);
+    let var = file.variable("networks").unwrap();
+    assert_eq!(var.get_owned_values::<Network, _>(..).unwrap(), networks);
+}
+
+#[test]
+fn put_get_dynamic_values() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_put_get_dynamic_values.nc");
+
+    let tag = NcVariableType::Enum(EnumType {
+        name: "tag".to_owned(),
+        fieldnames: vec!["low".to_owned(), "high".to_owned()],
+        fieldvalues: EnumTypeValues::U8(vec![1, 2]),
+    });
+    let raw = NcVariableType::Opaque(OpaqueType {
+        name: "raw".to_owned(),
+        size: 3,
+    });
+    let samples = NcVariableType::Vlen(VlenType::of::<i16>());
+    let field = |name: &str, basetype: &NcVariableType, arraydims, offset| CompoundTypeField {
+        name: name.to_owned(),
+        basetype: basetype.clone(),
+        arraydims,
+        offset,
+    };
+    let typ = NcVariableType::Compound(CompoundType {
+        name: "record".to_owned(),
+        size: 48,
+        fields: vec![
+            field("id", &i32::type_descriptor(), None, 0),
+            field("name", &NcVariableType::String, None, 8),
+            field("samples", &samples, None, 16),
+            field("tag", &tag, None, 32),
+            field("raw", &raw, None, 33),
+            field("pos", &f32::type_descriptor(), Some(vec![2]), 36),
+        ],
+    });
+
+    let record = |id: i64, name: &str, samples: &[i64], tag: &str| {
+        Value::Compound(vec![
+            ("id".to_owned(), Value::Int(id)),
+            ("name".to_owned(), Value::Str(name.to_owned())),
+            (
+                "samples".to_owned(),
+                Value::Vlen(samples.iter().map(|&x| Value::Int(x)).collect()),
+            ),
+            ("tag".to_owned(), Value::Enum(tag.to_owned())),
+            ("raw".to_owned(), Value::Opaque(vec![1, 2, 3])),
+            (
+                "pos".to_owned(),
+                Value::Array(vec![Value::Float(0.5), Value::Float(-1.5)]),
+            ),
+        ])
+    };
+    let values = vec![
+        record(1, "first", &[1, 2, 3], "low"),
+        record(2, "", &[], "high"),
+        record(-3, "third", &[-4], "low"),
+    ];
+
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_type_from_descriptor(tag.clone()).unwrap();
+        file.add_type_from_descriptor(raw.clone()).unwrap();
+        file.add_type_from_descriptor(samples.clone()).unwrap();
+        file.add_type_from_descriptor(typ.clone()).unwrap();
+        file.add_dimension("x", 3).unwrap();
+        let mut var = file
+            .add_variable_with_type("records", &["x"], &typ)
+            .unwrap();
+        var.put_dynamic_values(&values, ..).unwrap();
+
+        let mut bad = values[0].clone();
+        if let Value::Compound(fields) = &mut bad {
+            fields[3].1 = Value::Enum("medium".to_owned());
+        }
+        var.put_dynamic_values(&[bad], [0]).unwrap_err();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("records").unwrap();
+    assert_eq!(var.get_dynamic_values(..).unwrap(), values);
+    let last = var.get_dynamic_values([2]).unwrap();
+    assert_eq!(last[0].field("name"), Some(&Value::Str("third".to_owned())));
+}
+
+#[test]
+#[cfg(feature = "derive")]
+fn typed_attributes() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_typed_attributes.nc");
+
+    #[repr(C)]
+    #[derive(netcdf::NcType, Debug, Copy, Clone, PartialEq)]
+    struct Calibration {
+        gain: f32,
+        offset: f64,
+    }
+    #[repr(u8)]
+    #[derive(netcdf::NcType, Debug, Copy, Clone, PartialEq, Eq)]
+    #[allow(dead_code)]
+    enum Quality {
+        Good = 0,
+        Bad = 1,
+    }
+
+    let calibration = [
+        Calibration {
+            gain: 1.5,
+            offset: -2.0,
+        },
+        Calibration {
+            gain: 0.5,
+            offset: 4.0,
+        },
+    ];
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_attribute_typed("calibration", &calibration)
+            .unwrap();
+        file.add_dimension("x", 1).unwrap();
+        let mut var = file.add_variable::<f32>("var", &["x"]).unwrap();
+        var.put_attribute_typed("quality", &[Quality::Bad]).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    assert_eq!(
+        file.attribute_typed::<Calibration>("calibration")
+            .unwrap()
+            .unwrap(),
+        calibration
+    );
+    file.attribute_typed::<Quality>("calibration")
+        .unwrap()
+        .unwrap_err();
+    let var = file.variable("var").unwrap();
+    assert_eq!(
+        var.attribute_typed::<Quality>("quality").unwrap().unwrap(),
+        [Quality::Bad]
+    );
+    let AttributeValue::Enum(typ, values) = var.attribute_value("quality").unwrap().unwrap() else {
+        panic!("expected an enum attribute");
+    };
+    assert_eq!(typ.name, "Quality");
+    assert_eq!(values, [Value::Enum("Bad".to_owned())]);
+    let AttributeValue::Compound(_, values) =
+        file.attribute("calibration").unwrap().value().unwrap()
+    else {
+        panic!("expected a compound attribute");
+    };
+    assert_eq!(values[1].field("gain"), Some(&Value::Float(0.5)));
+}
+
+#[test]
+fn dynamic_attributes() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("test_dynamic_attributes.nc");
+
+    let vlen = VlenType::of::<i32>();
+    let values = vec![
+        Value::Vlen(vec![Value::Int(1), Value::Int(2)]),
+        Value::Vlen(vec![]),
+    ];
+    let opaque = OpaqueType {
+        name: "blob".to_owned(),
+        size: 2,
+    };
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_attribute("ragged", AttributeValue::Vlen(vlen.clone(), values.clone()))
+            .unwrap();
+        file.add_attribute(
+            "blob",
+            AttributeValue::Opaque(opaque.clone(), vec![Value::Opaque(vec![7, 8])]),
+        )
+        .unwrap();
+        file.add_attribute(
+            "bad",
+            AttributeValue::Opaque(opaque.clone(), vec![Value::Opaque(vec![1])]),
+        )
+        .unwrap_err();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    assert_eq!(
+        file.attribute("ragged").unwrap().value().unwrap(),
+        AttributeValue::Vlen(vlen, values)
+    );
+    assert_eq!(
+        file.attribute("blob").unwrap().value().unwrap(),
+        AttributeValue::Opaque(opaque, vec![Value::Opaque(vec![7, 8])])
+    );
+}
+
 #[test]
 fn char() {
     let d = tempfile::tempdir().unwrap();